@@ -76,6 +76,12 @@ pub trait LogIndexerBackend<Block: BlockT>: Send + Sync {
 	/// Indicate whether the log indexing feature is supported.
 	fn is_indexed(&self) -> bool;
 
+	/// Get the number of the oldest block whose logs are indexed, the logs of the blocks
+	/// below it are not filtered by `filter_logs`. `None` if no block is indexed yet.
+	fn first_indexed_block(&self) -> Option<u64> {
+		Some(0)
+	}
+
 	/// Filter the logs by the parameters.
 	async fn filter_logs(
 		&self,
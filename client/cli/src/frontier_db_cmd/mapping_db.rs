@@ -23,7 +23,7 @@ use serde::Deserialize;
 // Substrate
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
-use sp_runtime::traits::Block as BlockT;
+use sp_runtime::traits::{Block as BlockT, UniqueSaturatedInto};
 // Frontier
 use fp_rpc::EthereumRuntimeRPCApi;
 
@@ -99,6 +99,7 @@ where
 
 						let commitment = fc_db::kv::MappingCommitment::<B> {
 							block_hash: *substrate_block_hash,
+							block_number: self.block_number(*substrate_block_hash)?,
 							ethereum_block_hash: *ethereum_block_hash,
 							ethereum_transaction_hashes: existing_transaction_hashes,
 						};
@@ -157,6 +158,7 @@ where
 
 						let commitment = fc_db::kv::MappingCommitment::<B> {
 							block_hash: *substrate_block_hash,
+							block_number: self.block_number(*substrate_block_hash)?,
 							ethereum_block_hash: *ethereum_block_hash,
 							ethereum_transaction_hashes: existing_transaction_hashes,
 						};
@@ -174,6 +176,17 @@ where
 		}
 		Ok(())
 	}
+
+	fn block_number(&self, substrate_block_hash: B::Hash) -> sc_cli::Result<u32> {
+		match self
+			.client
+			.number(substrate_block_hash)
+			.map_err(|e| format!("{e:?}"))?
+		{
+			Some(number) => Ok(UniqueSaturatedInto::<u32>::unique_saturated_into(number)),
+			None => Err(format!("Block {substrate_block_hash:?} not found").into()),
+		}
+	}
 }
 
 impl<B: BlockT, C: HeaderBackend<B>> FrontierDbMessage for MappingDb<'_, B, C> {}
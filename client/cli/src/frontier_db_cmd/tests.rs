@@ -57,6 +57,7 @@ pub fn open_frontier_backend<Block: BlockT, C: HeaderBackend<Block>>(
 				path,
 				cache_size: 0,
 			},
+			log_index: false,
 		},
 	)?))
 }
@@ -0,0 +1,598 @@
+// This file is part of Frontier.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{
+	collections::{hash_map::Entry, BTreeSet, HashMap},
+	marker::PhantomData,
+	sync::Arc,
+};
+
+use parking_lot::Mutex;
+use scale_codec::{Decode, Encode};
+// Substrate
+use sp_blockchain::HeaderBackend;
+use sp_core::{H160, H256};
+use sp_runtime::traits::Block as BlockT;
+// Frontier
+use fc_api::FilteredLog;
+use fp_storage::EthereumStorageSchema;
+
+use super::{columns, Database, DbHash};
+
+/// Number of blocks sharing the list of blocks of an address or topic.
+///
+/// Larger buckets need fewer reads for a block range but make each write rewrite a longer
+/// list of blocks for a frequently emitted address or topic.
+const BUCKET_SIZE: u32 = 64;
+
+/// Maximum number of logs returned by a filter, one more than the RPC default limit so
+/// that the caller can detect an overflowing query.
+const MAX_FILTERED_LOGS: usize = 10_001;
+
+/// Key prefixes of the log index column.
+mod prefixes {
+	/// Logs of a block, keyed by substrate block hash.
+	pub const BLOCK: u8 = 0;
+	/// Blocks emitting an address, keyed by address and bucket, and the positions of the
+	/// address in each of these blocks, keyed by address, bucket and block hash.
+	pub const ADDRESS: u8 = 1;
+	/// Same as the address entries for a topic. The topic position (0 to 3) is added to the
+	/// prefix.
+	pub const TOPIC: u8 = 2;
+	/// Number of the oldest indexed block.
+	pub const FIRST_BLOCK: u8 = 6;
+}
+
+/// A log to be indexed.
+#[derive(Clone, Debug)]
+pub struct IndexedLog {
+	pub address: H160,
+	pub topics: Vec<H256>,
+	pub transaction_index: u32,
+	pub log_index: u32,
+}
+
+/// The logs of a block to be indexed.
+#[derive(Debug)]
+pub struct LogIndexCommitment<Block: BlockT> {
+	pub block_hash: Block::Hash,
+	pub block_number: u32,
+	pub ethereum_block_hash: H256,
+	pub ethereum_storage_schema: EthereumStorageSchema,
+	pub logs: Vec<IndexedLog>,
+}
+
+/// The indexed logs of a block, as `(transaction_index, log_index)` pairs, and the address
+/// and topic entries listing the block.
+#[derive(Encode, Decode)]
+struct BlockLogs {
+	block_number: u32,
+	ethereum_block_hash: H256,
+	ethereum_storage_schema: EthereumStorageSchema,
+	logs: Vec<(u32, u32)>,
+	bucket_keys: Vec<Vec<u8>>,
+}
+
+/// The position of a log in the chain.
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+struct LogPosition<Hash> {
+	block_number: u32,
+	substrate_block_hash: Hash,
+	transaction_index: u32,
+	log_index: u32,
+}
+
+/// Address and topic index of the logs of the synced blocks.
+///
+/// Logs of every imported block are indexed, including non-canonical ones, so the filter
+/// only returns the logs of blocks that are canonical at query time. Only the blocks synced
+/// while the index is enabled are indexed, from the block returned by `first_indexed_block`.
+pub struct LogIndexDb<Block> {
+	db: Arc<dyn Database<DbHash>>,
+	write_lock: Arc<Mutex<()>>,
+	_marker: PhantomData<Block>,
+}
+
+impl<Block: BlockT> LogIndexDb<Block> {
+	pub(crate) fn new(db: Arc<dyn Database<DbHash>>) -> Self {
+		Self {
+			db,
+			write_lock: Arc::new(Mutex::new(())),
+			_marker: PhantomData,
+		}
+	}
+
+	pub fn is_indexed(&self, block_hash: &Block::Hash) -> bool {
+		self.db
+			.contains(columns::LOG_INDEX, &block_key::<Block>(block_hash))
+	}
+
+	/// The number of the oldest indexed block, the blocks synced from it on are indexed.
+	pub fn first_indexed_block(&self) -> Result<Option<u32>, String> {
+		match self.db.get(columns::LOG_INDEX, &[prefixes::FIRST_BLOCK]) {
+			Some(raw) => Ok(Some(
+				u32::decode(&mut &raw[..]).map_err(|e| format!("{e:?}"))?,
+			)),
+			None => Ok(None),
+		}
+	}
+
+	/// Forget the indexed blocks, the index starts over from the next written block.
+	///
+	/// To be called when the blocks are synced without indexing them.
+	pub fn reset(&self) -> Result<(), String> {
+		let _lock = self.write_lock.lock();
+
+		let mut transaction = sp_database::Transaction::new();
+		transaction.remove(columns::LOG_INDEX, &[prefixes::FIRST_BLOCK]);
+		self.db.commit(transaction).map_err(|e| e.to_string())
+	}
+
+	pub fn write_logs(&self, commitment: LogIndexCommitment<Block>) -> Result<(), String> {
+		let _lock = self.write_lock.lock();
+
+		if self.is_indexed(&commitment.block_hash) {
+			return Ok(());
+		}
+
+		let mut transaction = sp_database::Transaction::new();
+
+		let bucket = commitment.block_number / BUCKET_SIZE;
+		let mut entries: HashMap<Vec<u8>, Vec<(u32, u32)>> = HashMap::new();
+		for log in commitment.logs.iter() {
+			let keys =
+				std::iter::once(bucket_key(
+					prefixes::ADDRESS,
+					log.address.as_bytes(),
+					bucket,
+				))
+				.chain(log.topics.iter().take(4).enumerate().map(|(i, topic)| {
+					bucket_key(prefixes::TOPIC + i as u8, topic.as_bytes(), bucket)
+				}));
+			for key in keys {
+				entries
+					.entry(key)
+					.or_default()
+					.push((log.transaction_index, log.log_index));
+			}
+		}
+		let bucket_keys = entries.keys().cloned().collect();
+		// Only the short list of blocks of the bucket is rewritten, the positions of the
+		// block are written once under their own key.
+		for (key, positions) in entries {
+			let mut blocks = self.bucket_blocks(&key)?;
+			blocks.push((commitment.block_number, commitment.block_hash));
+			transaction.set(columns::LOG_INDEX, &key, &blocks.encode());
+			transaction.set(
+				columns::LOG_INDEX,
+				&block_entry_key::<Block>(&key, &commitment.block_hash),
+				&positions.encode(),
+			);
+		}
+
+		let block_logs = BlockLogs {
+			block_number: commitment.block_number,
+			ethereum_block_hash: commitment.ethereum_block_hash,
+			ethereum_storage_schema: commitment.ethereum_storage_schema,
+			logs: commitment
+				.logs
+				.iter()
+				.map(|log| (log.transaction_index, log.log_index))
+				.collect(),
+			bucket_keys,
+		};
+		transaction.set(
+			columns::LOG_INDEX,
+			&block_key::<Block>(&commitment.block_hash),
+			&block_logs.encode(),
+		);
+
+		// The blocks are synced in ascending order, a lower block written later is a late fork
+		// whose canonical ancestors may not be indexed.
+		if self.first_indexed_block()?.is_none() {
+			transaction.set(
+				columns::LOG_INDEX,
+				&[prefixes::FIRST_BLOCK],
+				&commitment.block_number.encode(),
+			);
+		}
+
+		self.db.commit(transaction).map_err(|e| e.to_string())?;
+
+		Ok(())
+	}
+
+	/// Removes the logs of the blocks from the index.
+	pub fn prune(&self, block_hashes: &[Block::Hash]) -> Result<(), String> {
+		let _lock = self.write_lock.lock();
+
+		let mut transaction = sp_database::Transaction::new();
+
+		// Blocks of the same bucket may share entries, so the entries are updated once all of
+		// them are removed.
+		let mut buckets: HashMap<Vec<u8>, Vec<(u32, Block::Hash)>> = HashMap::new();
+		for block_hash in block_hashes {
+			let Some(block_logs) = self.block_logs(block_hash)? else {
+				continue;
+			};
+			for key in block_logs.bucket_keys {
+				transaction.remove(
+					columns::LOG_INDEX,
+					&block_entry_key::<Block>(&key, block_hash),
+				);
+				let blocks = match buckets.entry(key) {
+					Entry::Occupied(entry) => entry.into_mut(),
+					Entry::Vacant(entry) => {
+						let blocks = self.bucket_blocks(entry.key())?;
+						entry.insert(blocks)
+					}
+				};
+				blocks.retain(|(_, hash)| hash != block_hash);
+			}
+			transaction.remove(columns::LOG_INDEX, &block_key::<Block>(block_hash));
+		}
+		for (key, blocks) in buckets {
+			if blocks.is_empty() {
+				transaction.remove(columns::LOG_INDEX, &key);
+			} else {
+				transaction.set(columns::LOG_INDEX, &key, &blocks.encode());
+			}
+		}
+
+		self.db.commit(transaction).map_err(|e| e.to_string())
+	}
+
+	/// Filter the logs of the canonical blocks in the inclusive range, the same way as the
+	/// SQL backend does. The blocks below `first_indexed_block` are not filtered.
+	pub fn filter_logs<C: HeaderBackend<Block>>(
+		&self,
+		client: &C,
+		from_block: u64,
+		to_block: u64,
+		addresses: Vec<H160>,
+		topics: Vec<Vec<H256>>,
+	) -> Result<Vec<FilteredLog<Block>>, String> {
+		let from = u32::try_from(from_block).unwrap_or(u32::MAX);
+		let to = u32::try_from(to_block).unwrap_or(u32::MAX);
+		if from > to {
+			return Ok(Vec::new());
+		}
+
+		// Each group matches any of its keys, a log must match all the groups.
+		let mut groups: Vec<Vec<(u8, Vec<u8>)>> = Vec::new();
+		if !addresses.is_empty() {
+			groups.push(
+				addresses
+					.iter()
+					.map(|address| (prefixes::ADDRESS, address.as_bytes().to_vec()))
+					.collect(),
+			);
+		}
+		for (i, options) in topics.iter().take(4).enumerate() {
+			if !options.is_empty() {
+				groups.push(
+					options
+						.iter()
+						.map(|topic| (prefixes::TOPIC + i as u8, topic.as_bytes().to_vec()))
+						.collect(),
+				);
+			}
+		}
+
+		let positions: Vec<LogPosition<Block::Hash>> = if groups.is_empty() {
+			// Without any constraint every log of the canonical blocks matches.
+			let mut positions = Vec::new();
+			for number in from..=to {
+				let Some(hash) = client.hash(number.into()).map_err(|e| format!("{e:?}"))? else {
+					continue;
+				};
+				if let Some(block_logs) = self.block_logs(&hash)? {
+					positions.extend(block_logs.logs.into_iter().map(
+						|(transaction_index, log_index)| LogPosition {
+							block_number: number,
+							substrate_block_hash: hash,
+							transaction_index,
+							log_index,
+						},
+					));
+				}
+				if positions.len() >= MAX_FILTERED_LOGS {
+					break;
+				}
+			}
+			positions
+		} else {
+			let mut matches: Option<BTreeSet<LogPosition<Block::Hash>>> = None;
+			for group in groups {
+				let mut group_matches = BTreeSet::new();
+				for (prefix, value) in group.iter() {
+					for bucket in from / BUCKET_SIZE..=to / BUCKET_SIZE {
+						let key = bucket_key(*prefix, value, bucket);
+						for (block_number, block_hash) in self.bucket_blocks(&key)? {
+							if !(from..=to).contains(&block_number) {
+								continue;
+							}
+							group_matches.extend(
+								self.positions(&block_entry_key::<Block>(&key, &block_hash))?
+									.into_iter()
+									.map(|(transaction_index, log_index)| LogPosition {
+										block_number,
+										substrate_block_hash: block_hash,
+										transaction_index,
+										log_index,
+									}),
+							);
+						}
+					}
+				}
+				let group_matches = match matches {
+					Some(matches) => matches.intersection(&group_matches).copied().collect(),
+					None => group_matches,
+				};
+				let is_empty = group_matches.is_empty();
+				matches = Some(group_matches);
+				if is_empty {
+					break;
+				}
+			}
+			matches.unwrap_or_default().into_iter().collect()
+		};
+
+		let mut canonical_hashes: HashMap<u32, Option<Block::Hash>> = HashMap::new();
+		let mut blocks: HashMap<Block::Hash, BlockLogs> = HashMap::new();
+		let mut out = Vec::new();
+		for position in positions {
+			let canonical_hash = match canonical_hashes.get(&position.block_number) {
+				Some(hash) => *hash,
+				None => {
+					let hash = client
+						.hash(position.block_number.into())
+						.map_err(|e| format!("{e:?}"))?;
+					canonical_hashes.insert(position.block_number, hash);
+					hash
+				}
+			};
+			if canonical_hash != Some(position.substrate_block_hash) {
+				continue;
+			}
+			if !blocks.contains_key(&position.substrate_block_hash) {
+				let Some(block_logs) = self.block_logs(&position.substrate_block_hash)? else {
+					continue;
+				};
+				blocks.insert(position.substrate_block_hash, block_logs);
+			}
+			let block_logs = &blocks[&position.substrate_block_hash];
+			out.push(FilteredLog {
+				substrate_block_hash: position.substrate_block_hash,
+				ethereum_block_hash: block_logs.ethereum_block_hash,
+				block_number: position.block_number,
+				ethereum_storage_schema: block_logs.ethereum_storage_schema,
+				transaction_index: position.transaction_index,
+				log_index: position.log_index,
+			});
+			if out.len() >= MAX_FILTERED_LOGS {
+				break;
+			}
+		}
+
+		Ok(out)
+	}
+
+	fn block_logs(&self, block_hash: &Block::Hash) -> Result<Option<BlockLogs>, String> {
+		match self
+			.db
+			.get(columns::LOG_INDEX, &block_key::<Block>(block_hash))
+		{
+			Some(raw) => Ok(Some(
+				BlockLogs::decode(&mut &raw[..]).map_err(|e| format!("{e:?}"))?,
+			)),
+			None => Ok(None),
+		}
+	}
+
+	fn bucket_blocks(&self, key: &[u8]) -> Result<Vec<(u32, Block::Hash)>, String> {
+		match self.db.get(columns::LOG_INDEX, key) {
+			Some(raw) => {
+				Ok(Vec::<(u32, Block::Hash)>::decode(&mut &raw[..])
+					.map_err(|e| format!("{e:?}"))?)
+			}
+			None => Ok(Vec::new()),
+		}
+	}
+
+	fn positions(&self, key: &[u8]) -> Result<Vec<(u32, u32)>, String> {
+		match self.db.get(columns::LOG_INDEX, key) {
+			Some(raw) => {
+				Ok(Vec::<(u32, u32)>::decode(&mut &raw[..]).map_err(|e| format!("{e:?}"))?)
+			}
+			None => Ok(Vec::new()),
+		}
+	}
+}
+
+fn block_key<Block: BlockT>(block_hash: &Block::Hash) -> Vec<u8> {
+	let mut key = vec![prefixes::BLOCK];
+	key.extend(block_hash.encode());
+	key
+}
+
+fn bucket_key(prefix: u8, value: &[u8], bucket: u32) -> Vec<u8> {
+	let mut key = Vec::with_capacity(1 + value.len() + 4);
+	key.push(prefix);
+	key.extend_from_slice(value);
+	key.extend_from_slice(&bucket.to_be_bytes());
+	key
+}
+
+fn block_entry_key<Block: BlockT>(bucket_key: &[u8], block_hash: &Block::Hash) -> Vec<u8> {
+	let mut key = bucket_key.to_vec();
+	key.extend(block_hash.encode());
+	key
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use futures::executor;
+	// Substrate
+	use sc_block_builder::BlockBuilderBuilder;
+	use sp_consensus::BlockOrigin;
+	use substrate_test_runtime_client::{
+		prelude::*, DefaultTestClientBuilderExt, TestClientBuilder,
+	};
+
+	type TestBlock = substrate_test_runtime_client::runtime::Block;
+
+	fn log(address: H160, topics: Vec<H256>, transaction_index: u32, log_index: u32) -> IndexedLog {
+		IndexedLog {
+			address,
+			topics,
+			transaction_index,
+			log_index,
+		}
+	}
+
+	#[test]
+	fn filter_logs_works() {
+		let (client, _) = TestClientBuilder::new()
+			.build_with_native_executor::<substrate_test_runtime_client::runtime::RuntimeApi, _>(
+			None,
+		);
+		let genesis_hash = client.chain_info().genesis_hash;
+
+		// A canonical block #1 and a fork of it.
+		let mut block_hashes = vec![];
+		for change in [vec![1], vec![2]] {
+			let mut builder = BlockBuilderBuilder::new(&client)
+				.on_parent_block(genesis_hash)
+				.with_parent_block_number(0)
+				.build()
+				.unwrap();
+			builder.push_storage_change(change, None).unwrap();
+			let block = builder.build().unwrap().block;
+			block_hashes.push(block.header.hash());
+			executor::block_on(client.import(BlockOrigin::Own, block)).unwrap();
+		}
+		let (canon_hash, fork_hash) = (block_hashes[0], block_hashes[1]);
+		assert_eq!(client.hash(1).unwrap(), Some(canon_hash));
+
+		let alice = H160::repeat_byte(0x01);
+		let bob = H160::repeat_byte(0x02);
+		let topic_a = H256::repeat_byte(0x0a);
+		let topic_b = H256::repeat_byte(0x0b);
+
+		let log_index = LogIndexDb::<TestBlock>::new(Arc::new(sp_database::MemDb::default()));
+		for (block_hash, block_number, logs) in [
+			(genesis_hash, 0, vec![log(alice, vec![topic_a], 0, 0)]),
+			(
+				canon_hash,
+				1,
+				vec![
+					log(alice, vec![topic_b, topic_a], 0, 0),
+					log(bob, vec![topic_a], 1, 0),
+				],
+			),
+			(fork_hash, 1, vec![log(alice, vec![topic_a], 0, 0)]),
+		] {
+			log_index
+				.write_logs(LogIndexCommitment {
+					block_hash,
+					block_number,
+					ethereum_block_hash: H256::from_slice(block_hash.as_ref()),
+					ethereum_storage_schema: EthereumStorageSchema::V3,
+					logs,
+				})
+				.expect("write logs");
+		}
+		assert!(log_index.is_indexed(&fork_hash));
+		assert_eq!(log_index.first_indexed_block(), Ok(Some(0)));
+
+		let filter = |addresses: Vec<H160>, topics: Vec<Vec<H256>>| {
+			log_index
+				.filter_logs(&client, 0, 1, addresses, topics)
+				.expect("filter logs")
+				.into_iter()
+				.map(|log| (log.substrate_block_hash, log.transaction_index))
+				.collect::<Vec<_>>()
+		};
+
+		// The logs of the fork are never returned.
+		assert_eq!(
+			filter(vec![], vec![]),
+			vec![(genesis_hash, 0), (canon_hash, 0), (canon_hash, 1)]
+		);
+		assert_eq!(
+			filter(vec![alice], vec![]),
+			vec![(genesis_hash, 0), (canon_hash, 0)]
+		);
+		assert_eq!(
+			filter(vec![], vec![vec![topic_a]]),
+			vec![(genesis_hash, 0), (canon_hash, 1)]
+		);
+		assert_eq!(
+			filter(vec![alice, bob], vec![vec![], vec![topic_a]]),
+			vec![(canon_hash, 0)]
+		);
+		assert_eq!(
+			filter(vec![bob], vec![vec![topic_a, topic_b]]),
+			vec![(canon_hash, 1)]
+		);
+		assert_eq!(filter(vec![bob], vec![vec![topic_b]]), vec![]);
+
+		// The pruned blocks are removed from the shared entries.
+		log_index.prune(&[genesis_hash]).expect("prune");
+		assert!(!log_index.is_indexed(&genesis_hash));
+		assert_eq!(filter(vec![alice], vec![vec![topic_a]]), vec![]);
+		assert_eq!(filter(vec![], vec![vec![topic_a]]), vec![(canon_hash, 1)]);
+		log_index.prune(&[canon_hash, fork_hash]).expect("prune");
+		assert_eq!(filter(vec![], vec![]), vec![]);
+		assert_eq!(
+			log_index.bucket_blocks(&bucket_key(prefixes::ADDRESS, alice.as_bytes(), 0)),
+			Ok(vec![])
+		);
+	}
+
+	#[test]
+	fn first_indexed_block_works() {
+		let log_index = LogIndexDb::<TestBlock>::new(Arc::new(sp_database::MemDb::default()));
+		let write = |block_number: u32| {
+			log_index
+				.write_logs(LogIndexCommitment {
+					block_hash: H256::random(),
+					block_number,
+					ethereum_block_hash: H256::random(),
+					ethereum_storage_schema: EthereumStorageSchema::V3,
+					logs: vec![log(H160::repeat_byte(0x01), vec![], 0, 0)],
+				})
+				.expect("write logs")
+		};
+		assert_eq!(log_index.first_indexed_block(), Ok(None));
+
+		write(5);
+		write(6);
+		write(4);
+		assert_eq!(log_index.first_indexed_block(), Ok(Some(5)));
+
+		// Blocks synced while the index was disabled are not indexed.
+		log_index.reset().expect("reset");
+		assert_eq!(log_index.first_indexed_block(), Ok(None));
+		write(9);
+		assert_eq!(log_index.first_indexed_block(), Ok(Some(9)));
+	}
+}
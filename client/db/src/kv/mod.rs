@@ -16,11 +16,15 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+mod log_index;
 mod parity_db_adapter;
 mod upgrade;
 mod utils;
 
+pub use self::log_index::{IndexedLog, LogIndexCommitment, LogIndexDb};
+
 use std::{
+	collections::{hash_map::Entry, HashMap},
 	marker::PhantomData,
 	path::{Path, PathBuf},
 	sync::Arc,
@@ -46,19 +50,25 @@ pub type DbHash = [u8; DB_HASH_LEN];
 pub struct DatabaseSettings {
 	/// Where to find the database.
 	pub source: DatabaseSource,
+	/// Whether to index the logs by address and topic.
+	pub log_index: bool,
 }
 
 pub(crate) mod columns {
-	pub const NUM_COLUMNS: u32 = 4;
+	pub const NUM_COLUMNS: u32 = 5;
 
 	pub const META: u32 = 0;
 	pub const BLOCK_MAPPING: u32 = 1;
 	pub const TRANSACTION_MAPPING: u32 = 2;
 	pub const SYNCED_MAPPING: u32 = 3;
+	pub const LOG_INDEX: u32 = 4;
 }
 
 pub mod static_keys {
 	pub const CURRENT_SYNCING_TIPS: &[u8] = b"CURRENT_SYNCING_TIPS";
+	pub const PRUNING_STATUS: &[u8] = b"PRUNING_STATUS";
+	/// Prefix of the synced blocks of a block number, in the meta column.
+	pub const SYNCED_BLOCKS: &[u8] = b"SYNCED_BLOCKS";
 }
 
 #[derive(Clone)]
@@ -66,7 +76,7 @@ pub struct Backend<Block, C> {
 	client: Arc<C>,
 	meta: Arc<MetaDb<Block>>,
 	mapping: Arc<MappingDb<Block>>,
-	log_indexer: LogIndexerBackend<Block>,
+	log_indexer: LogIndexerBackend<Block, C>,
 }
 
 #[async_trait::async_trait]
@@ -91,7 +101,14 @@ impl<Block: BlockT, C: HeaderBackend<Block>> fc_api::Backend<Block> for Backend<
 	}
 
 	async fn first_block_hash(&self) -> Result<Block::Hash, String> {
-		Ok(self.client.info().genesis_hash)
+		match self.meta().pruning_status()?.lower_bound {
+			0 => Ok(self.client.info().genesis_hash),
+			number => self
+				.client
+				.hash(number.into())
+				.map_err(|e| format!("{e:?}"))?
+				.ok_or_else(|| format!("Block #{number} not found")),
+		}
 	}
 
 	async fn latest_block_hash(&self) -> Result<Block::Hash, String> {
@@ -99,23 +116,40 @@ impl<Block: BlockT, C: HeaderBackend<Block>> fc_api::Backend<Block> for Backend<
 	}
 }
 
-#[derive(Clone, Default)]
-pub struct LogIndexerBackend<Block>(PhantomData<Block>);
+#[derive(Clone)]
+pub struct LogIndexerBackend<Block, C> {
+	client: Arc<C>,
+	log_index: Option<Arc<LogIndexDb<Block>>>,
+}
 
 #[async_trait::async_trait]
-impl<Block: BlockT> fc_api::LogIndexerBackend<Block> for LogIndexerBackend<Block> {
+impl<Block: BlockT, C: HeaderBackend<Block>> fc_api::LogIndexerBackend<Block>
+	for LogIndexerBackend<Block, C>
+{
 	fn is_indexed(&self) -> bool {
-		false
+		self.log_index.is_some()
+	}
+
+	fn first_indexed_block(&self) -> Option<u64> {
+		self.log_index
+			.as_ref()
+			.and_then(|log_index| log_index.first_indexed_block().ok().flatten())
+			.map(Into::into)
 	}
 
 	async fn filter_logs(
 		&self,
-		_from_block: u64,
-		_to_block: u64,
-		_addresses: Vec<H160>,
-		_topics: Vec<Vec<H256>>,
+		from_block: u64,
+		to_block: u64,
+		addresses: Vec<H160>,
+		topics: Vec<Vec<H256>>,
 	) -> Result<Vec<FilteredLog<Block>>, String> {
-		Err("KeyValue db does not index logs".into())
+		match &self.log_index {
+			Some(log_index) => {
+				log_index.filter_logs(&*self.client, from_block, to_block, addresses, topics)
+			}
+			None => Err("KeyValue db does not index logs".into()),
+		}
 	}
 }
 
@@ -129,6 +163,7 @@ impl<Block: BlockT, C: HeaderBackend<Block>> Backend<Block, C> {
 		client: Arc<C>,
 		database: &DatabaseSource,
 		db_config_dir: &Path,
+		log_index: bool,
 	) -> Result<Self, String> {
 		Self::new(
 			client,
@@ -153,6 +188,7 @@ impl<Block: BlockT, C: HeaderBackend<Block>> Backend<Block, C> {
 						)
 					}
 				},
+				log_index,
 			},
 		)
 	}
@@ -160,8 +196,14 @@ impl<Block: BlockT, C: HeaderBackend<Block>> Backend<Block, C> {
 	pub fn new(client: Arc<C>, config: &DatabaseSettings) -> Result<Self, String> {
 		let db = utils::open_database::<Block, C>(client.clone(), config)?;
 
+		let log_index = LogIndexDb::new(db.clone());
+		if !config.log_index {
+			// The blocks synced from now on are not indexed.
+			log_index.reset()?;
+		}
+
 		Ok(Self {
-			client,
+			client: client.clone(),
 			mapping: Arc::new(MappingDb {
 				db: db.clone(),
 				write_lock: Arc::new(Mutex::new(())),
@@ -171,7 +213,10 @@ impl<Block: BlockT, C: HeaderBackend<Block>> Backend<Block, C> {
 				db: db.clone(),
 				_marker: PhantomData,
 			}),
-			log_indexer: LogIndexerBackend(PhantomData),
+			log_indexer: LogIndexerBackend {
+				client,
+				log_index: config.log_index.then(|| Arc::new(log_index)),
+			},
 		})
 	}
 
@@ -182,6 +227,11 @@ impl<Block: BlockT, C: HeaderBackend<Block>> Backend<Block, C> {
 	pub fn meta(&self) -> &Arc<MetaDb<Block>> {
 		&self.meta
 	}
+
+	/// The log index, if enabled in the database settings.
+	pub fn log_index(&self) -> Option<&Arc<LogIndexDb<Block>>> {
+		self.log_indexer.log_index.as_ref()
+	}
 }
 
 pub struct MetaDb<Block> {
@@ -214,6 +264,23 @@ impl<Block: BlockT> MetaDb<Block> {
 		Ok(())
 	}
 
+	pub fn pruning_status(&self) -> Result<PruningStatus, String> {
+		match self.db.get(columns::META, static_keys::PRUNING_STATUS) {
+			Some(raw) => Ok(PruningStatus::decode(&mut &raw[..]).map_err(|e| e.to_string())?),
+			None => Ok(PruningStatus::default()),
+		}
+	}
+
+	pub fn write_pruning_status(&self, status: PruningStatus) -> Result<(), String> {
+		let mut transaction = sp_database::Transaction::new();
+
+		transaction.set(columns::META, static_keys::PRUNING_STATUS, &status.encode());
+
+		self.db.commit(transaction).map_err(|e| e.to_string())?;
+
+		Ok(())
+	}
+
 	pub fn ethereum_schema(&self) -> Result<Option<Vec<(EthereumStorageSchema, H256)>>, String> {
 		match self
 			.db
@@ -244,13 +311,31 @@ impl<Block: BlockT> MetaDb<Block> {
 	}
 }
 
+/// How far the mapping database has been pruned.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Encode, Decode)]
+pub struct PruningStatus {
+	/// Number of the oldest block whose mapping is kept, all the blocks below are pruned.
+	pub lower_bound: u32,
+	/// The non-canonical blocks below this number are pruned.
+	pub canonical_below: u32,
+}
+
 #[derive(Debug)]
 pub struct MappingCommitment<Block: BlockT> {
 	pub block_hash: Block::Hash,
+	pub block_number: u32,
 	pub ethereum_block_hash: H256,
 	pub ethereum_transaction_hashes: Vec<H256>,
 }
 
+/// The mapping written for a synced block, recorded by block number so that it can be pruned.
+#[derive(Clone, Debug, Encode, Decode)]
+pub struct SyncedBlock<Hash> {
+	pub block_hash: Hash,
+	pub ethereum_block_hash: Option<H256>,
+	pub ethereum_transaction_hashes: Vec<H256>,
+}
+
 pub struct MappingDb<Block> {
 	db: Arc<dyn Database<DbHash>>,
 	write_lock: Arc<Mutex<()>>,
@@ -294,11 +379,21 @@ impl<Block: BlockT> MappingDb<Block> {
 		}
 	}
 
-	pub fn write_none(&self, block_hash: Block::Hash) -> Result<(), String> {
+	pub fn write_none(&self, block_hash: Block::Hash, block_number: u32) -> Result<(), String> {
 		let _lock = self.write_lock.lock();
 
 		let mut transaction = sp_database::Transaction::new();
 
+		self.record_synced_block(
+			&mut transaction,
+			block_number,
+			SyncedBlock {
+				block_hash,
+				ethereum_block_hash: None,
+				ethereum_transaction_hashes: Vec::new(),
+			},
+		)?;
+
 		transaction.set(
 			columns::SYNCED_MAPPING,
 			&block_hash.encode(),
@@ -337,6 +432,16 @@ impl<Block: BlockT> MappingDb<Block> {
 			&substrate_hashes.encode(),
 		);
 
+		self.record_synced_block(
+			&mut transaction,
+			commitment.block_number,
+			SyncedBlock {
+				block_hash: commitment.block_hash,
+				ethereum_block_hash: Some(commitment.ethereum_block_hash),
+				ethereum_transaction_hashes: commitment.ethereum_transaction_hashes.clone(),
+			},
+		)?;
+
 		for (i, ethereum_transaction_hash) in commitment
 			.ethereum_transaction_hashes
 			.into_iter()
@@ -365,4 +470,248 @@ impl<Block: BlockT> MappingDb<Block> {
 
 		Ok(())
 	}
+
+	/// Whether the block synced at `block_number` is recorded by number.
+	pub fn is_recorded(&self, block_number: u32, block_hash: &Block::Hash) -> Result<bool, String> {
+		Ok(self
+			.synced_blocks(block_number)?
+			.iter()
+			.any(|block| &block.block_hash == block_hash))
+	}
+
+	/// Removes the mapping of the blocks synced at `block_number`, except for `retain`, and
+	/// returns the hashes of the removed blocks.
+	///
+	/// The blocks synced before the database recorded them by number are only found through
+	/// `unrecorded`, the mapping of one of them, and the blocks sharing its ethereum block.
+	pub fn prune(
+		&self,
+		block_number: u32,
+		retain: Option<Block::Hash>,
+		unrecorded: Option<SyncedBlock<Block::Hash>>,
+	) -> Result<Vec<Block::Hash>, String> {
+		let _lock = self.write_lock.lock();
+
+		let mut synced_blocks = self.synced_blocks(block_number)?;
+		let recorded_len = synced_blocks.len();
+		if let Some(unrecorded) = unrecorded {
+			let mut block_hashes = vec![unrecorded.block_hash];
+			if let Some(ethereum_block_hash) = unrecorded.ethereum_block_hash {
+				block_hashes.extend(self.block_hash(&ethereum_block_hash)?.unwrap_or_default());
+			}
+			for block_hash in block_hashes {
+				if synced_blocks
+					.iter()
+					.all(|block| block.block_hash != block_hash)
+					&& self.is_synced(&block_hash)?
+				{
+					synced_blocks.push(SyncedBlock {
+						block_hash,
+						..unrecorded.clone()
+					});
+				}
+			}
+		}
+
+		let (retained, pruned): (Vec<_>, Vec<_>) = synced_blocks
+			.into_iter()
+			.enumerate()
+			.partition(|(_, block)| Some(block.block_hash) == retain);
+		if pruned.is_empty() {
+			return Ok(Vec::new());
+		}
+		let pruned_hashes = pruned.iter().map(|(_, block)| block.block_hash).collect();
+		// Only the recorded blocks are kept recorded.
+		let retained = retained
+			.into_iter()
+			.filter(|(i, _)| *i < recorded_len)
+			.map(|(_, block)| block)
+			.collect::<Vec<_>>();
+		let pruned = pruned.into_iter().map(|(_, block)| block);
+
+		let mut transaction = sp_database::Transaction::new();
+
+		// Blocks of the same number may share ethereum hashes, so the entries are updated once
+		// all of them are removed.
+		let mut block_mapping: HashMap<H256, Vec<Block::Hash>> = HashMap::new();
+		let mut transaction_mapping: HashMap<H256, Vec<TransactionMetadata<Block>>> =
+			HashMap::new();
+		for block in pruned {
+			if let Some(ethereum_block_hash) = block.ethereum_block_hash {
+				let substrate_hashes = match block_mapping.entry(ethereum_block_hash) {
+					Entry::Occupied(entry) => entry.into_mut(),
+					Entry::Vacant(entry) => {
+						entry.insert(self.block_hash(&ethereum_block_hash)?.unwrap_or_default())
+					}
+				};
+				substrate_hashes.retain(|hash| hash != &block.block_hash);
+			}
+			for ethereum_transaction_hash in block.ethereum_transaction_hashes {
+				let metadata = match transaction_mapping.entry(ethereum_transaction_hash) {
+					Entry::Occupied(entry) => entry.into_mut(),
+					Entry::Vacant(entry) => {
+						entry.insert(self.transaction_metadata(&ethereum_transaction_hash)?)
+					}
+				};
+				metadata.retain(|metadata| metadata.substrate_block_hash != block.block_hash);
+			}
+			transaction.remove(columns::SYNCED_MAPPING, &block.block_hash.encode());
+		}
+
+		for (ethereum_block_hash, substrate_hashes) in block_mapping {
+			if substrate_hashes.is_empty() {
+				transaction.remove(columns::BLOCK_MAPPING, &ethereum_block_hash.encode());
+			} else {
+				transaction.set(
+					columns::BLOCK_MAPPING,
+					&ethereum_block_hash.encode(),
+					&substrate_hashes.encode(),
+				);
+			}
+		}
+		for (ethereum_transaction_hash, metadata) in transaction_mapping {
+			if metadata.is_empty() {
+				transaction.remove(
+					columns::TRANSACTION_MAPPING,
+					&ethereum_transaction_hash.encode(),
+				);
+			} else {
+				transaction.set(
+					columns::TRANSACTION_MAPPING,
+					&ethereum_transaction_hash.encode(),
+					&metadata.encode(),
+				);
+			}
+		}
+
+		let key = synced_blocks_key(block_number);
+		if retained.is_empty() {
+			transaction.remove(columns::META, &key);
+		} else {
+			transaction.set(columns::META, &key, &retained.encode());
+		}
+
+		self.db.commit(transaction).map_err(|e| e.to_string())?;
+
+		Ok(pruned_hashes)
+	}
+
+	fn synced_blocks(&self, block_number: u32) -> Result<Vec<SyncedBlock<Block::Hash>>, String> {
+		match self.db.get(columns::META, &synced_blocks_key(block_number)) {
+			Some(raw) => Ok(Vec::<SyncedBlock<Block::Hash>>::decode(&mut &raw[..])
+				.map_err(|e| format!("{e:?}"))?),
+			None => Ok(Vec::new()),
+		}
+	}
+
+	fn record_synced_block(
+		&self,
+		transaction: &mut sp_database::Transaction<DbHash>,
+		block_number: u32,
+		block: SyncedBlock<Block::Hash>,
+	) -> Result<(), String> {
+		let mut synced_blocks = self.synced_blocks(block_number)?;
+		synced_blocks.retain(|synced| synced.block_hash != block.block_hash);
+		synced_blocks.push(block);
+		transaction.set(
+			columns::META,
+			&synced_blocks_key(block_number),
+			&synced_blocks.encode(),
+		);
+		Ok(())
+	}
+}
+
+fn synced_blocks_key(block_number: u32) -> Vec<u8> {
+	let mut key = static_keys::SYNCED_BLOCKS.to_vec();
+	key.extend_from_slice(&block_number.to_be_bytes());
+	key
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	type TestBlock = substrate_test_runtime_client::runtime::Block;
+
+	#[test]
+	fn prune_finds_unrecorded_blocks() {
+		let mapping = MappingDb::<TestBlock> {
+			db: Arc::new(sp_database::MemDb::default()),
+			write_lock: Arc::new(Mutex::new(())),
+			_marker: PhantomData,
+		};
+		let (canon_hash, sibling_hash, fork_hash) = (
+			H256::repeat_byte(0x01),
+			H256::repeat_byte(0x02),
+			H256::repeat_byte(0x03),
+		);
+		let (ethereum_block_hash, ethereum_transaction_hash) =
+			(H256::repeat_byte(0x0a), H256::repeat_byte(0x0b));
+
+		// Two blocks of the same ethereum block, synced before the blocks were recorded by
+		// number.
+		let metadata = |substrate_block_hash| TransactionMetadata::<TestBlock> {
+			substrate_block_hash,
+			ethereum_block_hash,
+			ethereum_index: 0,
+		};
+		let mut transaction = sp_database::Transaction::new();
+		transaction.set(
+			columns::BLOCK_MAPPING,
+			&ethereum_block_hash.encode(),
+			&vec![canon_hash, sibling_hash].encode(),
+		);
+		transaction.set(
+			columns::TRANSACTION_MAPPING,
+			&ethereum_transaction_hash.encode(),
+			&vec![metadata(canon_hash), metadata(sibling_hash)].encode(),
+		);
+		for hash in [canon_hash, sibling_hash] {
+			transaction.set(columns::SYNCED_MAPPING, &hash.encode(), &true.encode());
+		}
+		mapping.db.commit(transaction).expect("commit");
+		// A recorded fork.
+		mapping
+			.write_hashes(MappingCommitment {
+				block_hash: fork_hash,
+				block_number: 1,
+				ethereum_block_hash: H256::repeat_byte(0x0c),
+				ethereum_transaction_hashes: vec![],
+			})
+			.expect("write hashes");
+		assert_eq!(mapping.is_recorded(1, &canon_hash), Ok(false));
+		assert_eq!(mapping.is_recorded(1, &fork_hash), Ok(true));
+
+		let unrecorded = SyncedBlock {
+			block_hash: canon_hash,
+			ethereum_block_hash: Some(ethereum_block_hash),
+			ethereum_transaction_hashes: vec![ethereum_transaction_hash],
+		};
+		assert_eq!(
+			mapping.prune(1, Some(canon_hash), Some(unrecorded.clone())),
+			Ok(vec![fork_hash, sibling_hash])
+		);
+		assert_eq!(mapping.is_synced(&fork_hash), Ok(false));
+		assert_eq!(mapping.is_synced(&sibling_hash), Ok(false));
+		assert_eq!(
+			mapping.block_hash(&ethereum_block_hash),
+			Ok(Some(vec![canon_hash]))
+		);
+		assert_eq!(
+			mapping.transaction_metadata(&ethereum_transaction_hash),
+			Ok(vec![metadata(canon_hash)])
+		);
+
+		assert_eq!(
+			mapping.prune(1, None, Some(unrecorded)),
+			Ok(vec![canon_hash])
+		);
+		assert_eq!(mapping.is_synced(&canon_hash), Ok(false));
+		assert_eq!(mapping.block_hash(&ethereum_block_hash), Ok(None));
+		assert_eq!(
+			mapping.transaction_metadata(&ethereum_transaction_hash),
+			Ok(vec![])
+		);
+	}
 }
@@ -34,11 +34,12 @@ use sp_runtime::traits::Block as BlockT;
 const VERSION_FILE_NAME: &str = "db_version";
 
 /// Current db version.
-const CURRENT_VERSION: u32 = 2;
+const CURRENT_VERSION: u32 = 3;
 
 /// Number of columns in each version.
 const _V1_NUM_COLUMNS: u32 = 4;
 const V2_NUM_COLUMNS: u32 = 4;
+const _V3_NUM_COLUMNS: u32 = 5;
 
 /// Database upgrade errors.
 #[derive(Debug)]
@@ -115,7 +116,9 @@ pub(crate) fn upgrade_db<Block: BlockT, C: HeaderBackend<Block>>(
 			} else {
 				log::info!("✔️ Successful Frontier DB migration from version 1 to version 2 ({:?} entries).", summary.success);
 			}
+			migrate_2_to_3(db_path, source)?;
 		}
+		2 => migrate_2_to_3(db_path, source)?,
 		CURRENT_VERSION => (),
 		_ => return Err(UpgradeError::FutureDatabaseVersion(db_version)),
 	}
@@ -160,6 +163,38 @@ fn version_file_path(path: &Path) -> PathBuf {
 	file_path
 }
 
+/// Migration from version2 to version3:
+/// - Adds the log index column.
+pub(crate) fn migrate_2_to_3(db_path: &Path, source: &DatabaseSource) -> UpgradeResult<()> {
+	log::info!("🔨 Running Frontier DB migration from version 2 to version 3.");
+	match source {
+		DatabaseSource::ParityDb { .. } => migrate_2_to_3_parity_db(db_path),
+		DatabaseSource::Auto { paritydb_path, .. } if paritydb_path == db_path => {
+			migrate_2_to_3_parity_db(db_path)
+		}
+		#[cfg(feature = "rocksdb")]
+		_ => migrate_2_to_3_rocks_db(db_path),
+		#[cfg(not(feature = "rocksdb"))]
+		_ => Ok(()),
+	}
+}
+
+#[cfg(feature = "rocksdb")]
+fn migrate_2_to_3_rocks_db(db_path: &Path) -> UpgradeResult<()> {
+	let db_cfg = kvdb_rocksdb::DatabaseConfig::with_columns(V2_NUM_COLUMNS);
+	let mut db = kvdb_rocksdb::Database::open(&db_cfg, db_path)?;
+	db.add_column()?;
+	Ok(())
+}
+
+fn migrate_2_to_3_parity_db(db_path: &Path) -> UpgradeResult<()> {
+	let mut db_cfg = parity_db::Options::with_columns(db_path, V2_NUM_COLUMNS as u8);
+	db_cfg.columns[super::columns::BLOCK_MAPPING as usize].btree_index = true;
+	parity_db::Db::add_column(&mut db_cfg, parity_db::ColumnOptions::default())
+		.map_err(|_| io::Error::other("Failed to add the log index column"))?;
+	Ok(())
+}
+
 /// Migration from version1 to version2:
 /// - The format of the Ethereum<>Substrate block mapping changed to support equivocation.
 /// - Migrating schema from One-to-one to One-to-many (EthHash: Vec<SubstrateHash>) relationship.
@@ -367,6 +402,7 @@ mod tests {
 						.to_owned(),
 					cache_size: 0,
 				},
+				log_index: false,
 			},
 			// Parity db
 			crate::kv::DatabaseSettings {
@@ -376,6 +412,7 @@ mod tests {
 						.path()
 						.to_owned(),
 				},
+				log_index: false,
 			},
 		];
 
@@ -406,8 +443,7 @@ mod tests {
 			let mut transaction_hashes = vec![];
 			{
 				// Create a temporary frontier secondary DB.
-				let backend = open_frontier_backend::<OpaqueBlock, _>(client.clone(), &setting)
-					.expect("a temporary db was created");
+				let db = open_v2_database(&setting.source);
 
 				// Fill the tmp db with some data
 				let mut transaction = sp_database::Transaction::new();
@@ -468,7 +504,7 @@ mod tests {
 					previous_canon_block_hash = next_canon_block_hash;
 					previous_canon_block_number = next_canon_block_number;
 				}
-				let _ = backend.mapping().db.commit(transaction);
+				let _ = db.commit(transaction);
 			}
 
 			// Writes version 1 to file.
@@ -509,7 +545,7 @@ mod tests {
 			}
 
 			// Upgrade db version file
-			assert_eq!(super::current_version(path).expect("version"), 2u32);
+			assert_eq!(super::current_version(path).expect("version"), 3u32);
 		}
 	}
 
@@ -529,6 +565,7 @@ mod tests {
 				path: tmp.path().to_owned(),
 				cache_size: 0,
 			},
+			log_index: false,
 		};
 		let path = setting.source.path().unwrap();
 		let _ = super::upgrade_db::<OpaqueBlock, _>(client, path, &setting.source);
@@ -538,6 +575,104 @@ mod tests {
 
 		let mut s = String::new();
 		file.read_to_string(&mut s).expect("read file contents");
-		assert_eq!(s.parse::<u32>().expect("parse file contents"), 2u32);
+		assert_eq!(s.parse::<u32>().expect("parse file contents"), 3u32);
+	}
+
+	#[cfg_attr(not(feature = "rocksdb"), ignore)]
+	#[test]
+	fn upgrade_2_to_3_works() {
+		let settings: Vec<crate::kv::DatabaseSettings> = vec![
+			// Rocks db
+			#[cfg(feature = "rocksdb")]
+			crate::kv::DatabaseSettings {
+				source: sc_client_db::DatabaseSource::RocksDb {
+					path: tempdir()
+						.expect("create a temporary directory")
+						.path()
+						.to_owned(),
+					cache_size: 0,
+				},
+				log_index: true,
+			},
+			// Parity db
+			crate::kv::DatabaseSettings {
+				source: sc_client_db::DatabaseSource::ParityDb {
+					path: tempdir()
+						.expect("create a temporary directory")
+						.path()
+						.to_owned(),
+				},
+				log_index: true,
+			},
+		];
+
+		for setting in settings {
+			let (client, _) = TestClientBuilder::new()
+				.build_with_native_executor::<substrate_test_runtime_client::runtime::RuntimeApi, _>(
+				None,
+			);
+			let client = Arc::new(client);
+			let path = setting.source.path().unwrap();
+
+			// Create a version 2 db holding a block mapping.
+			let ethereum_hash = H256::random();
+			let substrate_hash = client.chain_info().genesis_hash;
+			{
+				let db = open_v2_database(&setting.source);
+				let mut transaction = sp_database::Transaction::new();
+				transaction.set(
+					crate::kv::columns::BLOCK_MAPPING,
+					&ethereum_hash.encode(),
+					&vec![substrate_hash].encode(),
+				);
+				db.commit(transaction).expect("commit");
+			}
+			std::fs::write(path.join("db_version"), "2").expect("write version 2");
+
+			super::upgrade_db::<OpaqueBlock, _>(client.clone(), path, &setting.source)
+				.expect("upgrade succeeds");
+			assert_eq!(super::current_version(path).expect("version"), 3u32);
+
+			// The existing data is kept and the log index column is usable.
+			let backend = open_frontier_backend::<OpaqueBlock, _>(client, &setting)
+				.expect("a temporary db was created");
+			assert_eq!(
+				backend.mapping().block_hash(&ethereum_hash),
+				Ok(Some(vec![substrate_hash]))
+			);
+			let log_index = backend.log_index().expect("log index is enabled");
+			log_index
+				.write_logs(crate::kv::LogIndexCommitment {
+					block_hash: substrate_hash,
+					block_number: 0,
+					ethereum_block_hash: ethereum_hash,
+					ethereum_storage_schema: fp_storage::EthereumStorageSchema::V3,
+					logs: vec![],
+				})
+				.expect("write logs");
+			assert!(log_index.is_indexed(&substrate_hash));
+		}
+	}
+
+	/// Opens a database with the version 2 columns.
+	fn open_v2_database(
+		source: &sc_client_db::DatabaseSource,
+	) -> Arc<dyn sp_database::Database<crate::kv::DbHash>> {
+		match source {
+			#[cfg(feature = "rocksdb")]
+			sc_client_db::DatabaseSource::RocksDb { path, .. } => {
+				let db_cfg = kvdb_rocksdb::DatabaseConfig::with_columns(super::V2_NUM_COLUMNS);
+				let db = kvdb_rocksdb::Database::open(&db_cfg, path).expect("open rocks db");
+				sp_database::as_database(db)
+			}
+			sc_client_db::DatabaseSource::ParityDb { path } => {
+				let mut db_cfg =
+					parity_db::Options::with_columns(path, super::V2_NUM_COLUMNS as u8);
+				db_cfg.columns[crate::kv::columns::BLOCK_MAPPING as usize].btree_index = true;
+				let db = parity_db::Db::open_or_create(&db_cfg).expect("open parity db");
+				Arc::new(crate::kv::parity_db_adapter::DbAdapter(db))
+			}
+			_ => unreachable!("unsupported test db source"),
+		}
 	}
 }
@@ -18,22 +18,29 @@
 
 #![allow(clippy::too_many_arguments)]
 
+mod pruning;
 mod worker;
 
-pub use worker::MappingSyncWorker;
+pub use self::{
+	pruning::{prune_blocks, pruning_task},
+	worker::MappingSyncWorker,
+};
 
 use std::{collections::HashMap, sync::Arc};
 
+use scale_codec::Decode;
 // Substrate
 use sc_client_api::backend::{Backend, StorageProvider};
 use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_blockchain::{Backend as _, HeaderBackend};
 use sp_consensus::SyncOracle;
-use sp_runtime::traits::{Block as BlockT, Header as HeaderT, Zero};
+use sp_core::storage::StorageKey;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, UniqueSaturatedInto, Zero};
 // Frontier
 use fc_storage::StorageOverride;
 use fp_consensus::{FindLogError, Hashes, Log, PostLog, PreLog};
 use fp_rpc::EthereumRuntimeRPCApi;
+use fp_storage::{EthereumStorageSchema, PALLET_ETHEREUM_SCHEMA};
 
 use crate::{
 	emit_block_notification, BlockNotificationContext, EthereumBlockNotification,
@@ -47,11 +54,13 @@ pub fn sync_block<Block: BlockT, C: HeaderBackend<Block>>(
 	header: &Block::Header,
 ) -> Result<(), String> {
 	let substrate_block_hash = header.hash();
+	let block_number = UniqueSaturatedInto::<u32>::unique_saturated_into(*header.number());
 	match fp_consensus::find_log(header.digest()) {
 		Ok(log) => {
 			let gen_from_hashes = |hashes: Hashes| -> fc_db::kv::MappingCommitment<Block> {
 				fc_db::kv::MappingCommitment {
 					block_hash: substrate_block_hash,
+					block_number,
 					ethereum_block_hash: hashes.block_hash,
 					ethereum_transaction_hashes: hashes.transaction_hashes,
 				}
@@ -91,17 +100,70 @@ pub fn sync_block<Block: BlockT, C: HeaderBackend<Block>>(
 									backend.mapping().write_hashes(mapping_commitment)
 								}
 							}
-							None => backend.mapping().write_none(substrate_block_hash),
+							None => backend
+								.mapping()
+								.write_none(substrate_block_hash, block_number),
 						}
 					}
 				},
 			}
 		}
-		Err(FindLogError::NotFound) => backend.mapping().write_none(substrate_block_hash),
+		Err(FindLogError::NotFound) => backend
+			.mapping()
+			.write_none(substrate_block_hash, block_number),
 		Err(FindLogError::MultipleLogs) => Err("Multiple logs found".to_string()),
 	}
 }
 
+/// Index the logs of a block by address and topic.
+pub fn index_block_logs<Block: BlockT, C, BE>(
+	client: &C,
+	storage_override: &dyn StorageOverride<Block>,
+	log_index: &fc_db::kv::LogIndexDb<Block>,
+	header: &Block::Header,
+) -> Result<(), String>
+where
+	C: StorageProvider<Block, BE>,
+	BE: Backend<Block>,
+{
+	let substrate_block_hash = header.hash();
+	let Some(ethereum_block) = storage_override.current_block(substrate_block_hash) else {
+		return Ok(());
+	};
+	let ethereum_storage_schema = client
+		.storage(
+			substrate_block_hash,
+			&StorageKey(PALLET_ETHEREUM_SCHEMA.to_vec()),
+		)
+		.ok()
+		.flatten()
+		.and_then(|data| EthereumStorageSchema::decode(&mut &data.0[..]).ok())
+		.unwrap_or(EthereumStorageSchema::V3);
+
+	let mut logs = Vec::new();
+	for status in storage_override
+		.current_transaction_statuses(substrate_block_hash)
+		.unwrap_or_default()
+	{
+		for (log_index, log) in status.logs.into_iter().enumerate() {
+			logs.push(fc_db::kv::IndexedLog {
+				address: log.address,
+				topics: log.topics,
+				transaction_index: status.transaction_index,
+				log_index: log_index as u32,
+			});
+		}
+	}
+
+	log_index.write_logs(fc_db::kv::LogIndexCommitment {
+		block_hash: substrate_block_hash,
+		block_number: UniqueSaturatedInto::<u32>::unique_saturated_into(*header.number()),
+		ethereum_block_hash: ethereum_block.header.hash(),
+		ethereum_storage_schema,
+		logs,
+	})
+}
+
 pub fn sync_genesis_block<Block: BlockT, C>(
 	client: &C,
 	backend: &fc_db::kv::Backend<Block, C>,
@@ -137,12 +199,13 @@ where
 			.hash();
 		let mapping_commitment = fc_db::kv::MappingCommitment::<Block> {
 			block_hash: substrate_block_hash,
+			block_number: 0,
 			ethereum_block_hash: block_hash,
 			ethereum_transaction_hashes: Vec::new(),
 		};
 		backend.mapping().write_hashes(mapping_commitment)?;
 	} else {
-		backend.mapping().write_none(substrate_block_hash)?;
+		backend.mapping().write_none(substrate_block_hash, 0)?;
 	};
 
 	Ok(())
@@ -214,7 +277,14 @@ where
 		{
 			return Ok(false);
 		}
-		sync_block(storage_override, frontier_backend, &operating_header)?;
+		sync_block(
+			storage_override.clone(),
+			frontier_backend,
+			&operating_header,
+		)?;
+		if let Some(log_index) = frontier_backend.log_index() {
+			index_block_logs(client, &*storage_override, log_index, &operating_header)?;
+		}
 
 		current_syncing_tips.push(*operating_header.parent_hash());
 		frontier_backend
@@ -306,7 +376,15 @@ where
 
 	match substrate_backend.header(checking_tip) {
 		Ok(Some(checking_header)) if checking_header.number() >= &sync_from => {
-			Ok(Some(checking_header))
+			// Pruned blocks are not synced again.
+			let pruning_status = frontier_backend.meta().pruning_status()?;
+			let number = *checking_header.number();
+			let is_pruned = UniqueSaturatedInto::<u32>::unique_saturated_into(number)
+				< pruning_status.lower_bound
+				|| (UniqueSaturatedInto::<u32>::unique_saturated_into(number)
+					< pruning_status.canonical_below
+					&& substrate_backend.hash(number).ok().flatten() != Some(checking_tip));
+			Ok((!is_pruned).then_some(checking_header))
 		}
 		Ok(Some(_)) => Ok(None),
 		Ok(None) | Err(_) => Err("Header not found".to_string()),
@@ -0,0 +1,297 @@
+// This file is part of Frontier.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{sync::Arc, time::Duration};
+
+use futures_timer::Delay;
+// Substrate
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::{Block as BlockT, UniqueSaturatedInto};
+// Frontier
+use fc_storage::StorageOverride;
+use fp_consensus::Hashes;
+
+use crate::RetentionPolicy;
+
+/// Number of block numbers pruned by a run of the pruning task.
+const PRUNING_BATCH_SIZE: u32 = 1_000;
+
+/// Prunes the mapping and the indexed logs of at most `limit` block numbers according to the
+/// retention policy.
+///
+/// Returns whether blocks are left to prune.
+pub fn prune_blocks<Block: BlockT, C: HeaderBackend<Block>>(
+	client: &C,
+	storage_override: &dyn StorageOverride<Block>,
+	frontier_backend: &fc_db::kv::Backend<Block, C>,
+	retention: RetentionPolicy,
+	limit: u32,
+) -> Result<bool, String> {
+	let info = client.info();
+	let best_number = UniqueSaturatedInto::<u32>::unique_saturated_into(info.best_number);
+	let finalized_number = UniqueSaturatedInto::<u32>::unique_saturated_into(info.finalized_number);
+
+	let mut status = frontier_backend.meta().pruning_status()?;
+	let (from, to, keep_canonical) = match retention {
+		RetentionPolicy::Archive => return Ok(false),
+		RetentionPolicy::ArchiveCanonical => (
+			status.lower_bound.max(status.canonical_below),
+			finalized_number.saturating_add(1),
+			true,
+		),
+		RetentionPolicy::KeepLast(_) => {
+			let lower_bound = retention
+				.lower_bound(best_number, finalized_number)
+				.unwrap_or_default();
+			(status.lower_bound, lower_bound, false)
+		}
+	};
+	if from >= to {
+		return Ok(false);
+	}
+	let until = to.min(from.saturating_add(limit));
+
+	for number in from..until {
+		let canonical_hash = client.hash(number.into()).map_err(|e| format!("{e:?}"))?;
+		let retain = if keep_canonical { canonical_hash } else { None };
+		// The blocks synced before the mapping recorded them by number are found from the
+		// canonical one.
+		let unrecorded = match canonical_hash {
+			Some(hash) if !frontier_backend.mapping().is_recorded(number, &hash)? => {
+				Some(synced_block(storage_override, hash))
+			}
+			_ => None,
+		};
+		let pruned = frontier_backend
+			.mapping()
+			.prune(number, retain, unrecorded)?;
+		if let Some(log_index) = frontier_backend.log_index() {
+			log_index.prune(&pruned)?;
+		}
+	}
+
+	if keep_canonical {
+		status.canonical_below = until;
+	} else {
+		status.lower_bound = until;
+	}
+	frontier_backend.meta().write_pruning_status(status)?;
+
+	Ok(until < to)
+}
+
+/// The mapping the sync worker writes for a block.
+fn synced_block<Block: BlockT>(
+	storage_override: &dyn StorageOverride<Block>,
+	block_hash: Block::Hash,
+) -> fc_db::kv::SyncedBlock<Block::Hash> {
+	match storage_override.current_block(block_hash) {
+		Some(ethereum_block) => {
+			let hashes = Hashes::from_block(ethereum_block);
+			fc_db::kv::SyncedBlock {
+				block_hash,
+				ethereum_block_hash: Some(hashes.block_hash),
+				ethereum_transaction_hashes: hashes.transaction_hashes,
+			}
+		}
+		None => fc_db::kv::SyncedBlock {
+			block_hash,
+			ethereum_block_hash: None,
+			ethereum_transaction_hashes: Vec::new(),
+		},
+	}
+}
+
+/// Prunes the mapping database in the background, according to the retention policy.
+pub async fn pruning_task<Block: BlockT, C: HeaderBackend<Block>>(
+	client: Arc<C>,
+	storage_override: Arc<dyn StorageOverride<Block>>,
+	frontier_backend: Arc<fc_db::kv::Backend<Block, C>>,
+	retention: RetentionPolicy,
+	interval: Duration,
+) {
+	loop {
+		match prune_blocks(
+			client.as_ref(),
+			storage_override.as_ref(),
+			frontier_backend.as_ref(),
+			retention,
+			PRUNING_BATCH_SIZE,
+		) {
+			// Keep going while behind, without hogging the database.
+			Ok(true) => Delay::new(Duration::from_millis(100)).await,
+			Ok(false) => Delay::new(interval).await,
+			Err(e) => {
+				log::warn!(target: "mapping-sync", "Pruning failed with error {e:?}, retrying.");
+				Delay::new(interval).await
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use futures::executor;
+	use scale_codec::Encode;
+	use tempfile::tempdir;
+	// Substrate
+	use sc_block_builder::BlockBuilderBuilder;
+	use sp_consensus::BlockOrigin;
+	use sp_core::H256;
+	use substrate_test_runtime_client::{
+		prelude::*, DefaultTestClientBuilderExt, TestClientBuilder,
+	};
+
+	// Frontier
+	use fc_storage::SchemaV3StorageOverride;
+
+	type TestBlock = substrate_test_runtime_client::runtime::Block;
+
+	#[test]
+	fn prune_blocks_works() {
+		let tmp = tempdir().expect("create a temporary directory");
+		let (client, _) = TestClientBuilder::new()
+			.build_with_native_executor::<substrate_test_runtime_client::runtime::RuntimeApi, _>(
+			None,
+		);
+		let client = Arc::new(client);
+		let frontier_backend = fc_db::kv::Backend::<TestBlock, _>::new(
+			client.clone(),
+			&fc_db::kv::DatabaseSettings {
+				source: sc_client_db::DatabaseSource::RocksDb {
+					path: tmp.path().to_path_buf(),
+					cache_size: 0,
+				},
+				log_index: false,
+			},
+		)
+		.expect("frontier backend");
+		let storage_override = SchemaV3StorageOverride::new(client.clone());
+
+		// A chain of 4 blocks, with a fork of block #2.
+		let mut parent_hash = client.chain_info().genesis_hash;
+		let mut canon_hashes = vec![parent_hash];
+		for number in 1..=4u32 {
+			let mut builder = BlockBuilderBuilder::new(&*client)
+				.on_parent_block(parent_hash)
+				.with_parent_block_number((number - 1).into())
+				.build()
+				.unwrap();
+			builder.push_storage_change(number.encode(), None).unwrap();
+			let block = builder.build().unwrap().block;
+			parent_hash = block.header.hash();
+			canon_hashes.push(parent_hash);
+			executor::block_on(client.import(BlockOrigin::Own, block)).unwrap();
+		}
+		let mut builder = BlockBuilderBuilder::new(&*client)
+			.on_parent_block(canon_hashes[1])
+			.with_parent_block_number(1)
+			.build()
+			.unwrap();
+		builder.push_storage_change(vec![0], None).unwrap();
+		let fork = builder.build().unwrap().block;
+		let fork_hash = fork.header.hash();
+		executor::block_on(client.import(BlockOrigin::Own, fork)).unwrap();
+		client.finalize_block(canon_hashes[3], None).unwrap();
+
+		let ethereum_hashes = (0..=4u8).map(H256::repeat_byte).collect::<Vec<_>>();
+		let transaction_hash = |hash: H256| H256::from_low_u64_be(hash.to_low_u64_be());
+		let mut mapped = canon_hashes
+			.iter()
+			.zip(ethereum_hashes.iter())
+			.enumerate()
+			.map(|(number, (hash, ethereum_hash))| (*hash, number as u32, *ethereum_hash))
+			.collect::<Vec<_>>();
+		mapped.push((fork_hash, 2, H256::repeat_byte(0xff)));
+		for (block_hash, block_number, ethereum_block_hash) in mapped.iter().copied() {
+			frontier_backend
+				.mapping()
+				.write_hashes(fc_db::kv::MappingCommitment {
+					block_hash,
+					block_number,
+					ethereum_block_hash,
+					ethereum_transaction_hashes: vec![transaction_hash(ethereum_block_hash)],
+				})
+				.expect("write hashes");
+		}
+		let is_mapped = |block_hash, ethereum_block_hash: H256| {
+			let mapping = frontier_backend.mapping();
+			mapping.is_synced(&block_hash).unwrap()
+				&& mapping.block_hash(&ethereum_block_hash).unwrap() == Some(vec![block_hash])
+				&& !mapping
+					.transaction_metadata(&transaction_hash(ethereum_block_hash))
+					.unwrap()
+					.is_empty()
+		};
+
+		// Only the fork of a finalized number is pruned.
+		assert_eq!(
+			prune_blocks(
+				&*client,
+				&storage_override,
+				&frontier_backend,
+				RetentionPolicy::ArchiveCanonical,
+				2
+			),
+			Ok(true)
+		);
+		assert!(is_mapped(fork_hash, H256::repeat_byte(0xff)));
+		assert_eq!(
+			prune_blocks(
+				&*client,
+				&storage_override,
+				&frontier_backend,
+				RetentionPolicy::ArchiveCanonical,
+				2
+			),
+			Ok(false)
+		);
+		assert!(!is_mapped(fork_hash, H256::repeat_byte(0xff)));
+		for (block_hash, _, ethereum_block_hash) in mapped.iter().take(5) {
+			assert!(is_mapped(*block_hash, *ethereum_block_hash));
+		}
+
+		// The last block below the best one and the unfinalized ones are kept.
+		assert_eq!(
+			prune_blocks(
+				&*client,
+				&storage_override,
+				&frontier_backend,
+				RetentionPolicy::KeepLast(1),
+				10
+			),
+			Ok(false)
+		);
+		for (block_hash, number, ethereum_block_hash) in mapped.iter().take(5) {
+			assert_eq!(is_mapped(*block_hash, *ethereum_block_hash), *number >= 3);
+		}
+		assert_eq!(
+			frontier_backend.meta().pruning_status(),
+			Ok(fc_db::kv::PruningStatus {
+				lower_bound: 3,
+				canonical_below: 4,
+			})
+		);
+		assert_eq!(
+			executor::block_on(fc_api::Backend::first_block_hash(&frontier_backend)),
+			Ok(canon_hashes[3])
+		);
+	}
+}
@@ -302,6 +302,7 @@ mod tests {
 						path: tmp.path().to_path_buf(),
 						cache_size: 0,
 					},
+					log_index: false,
 				},
 			)
 			.expect("frontier backend"),
@@ -444,6 +445,7 @@ mod tests {
 						path: tmp.path().to_path_buf(),
 						cache_size: 0,
 					},
+					log_index: false,
 				},
 			)
 			.expect("frontier backend"),
@@ -33,6 +33,46 @@ pub enum SyncStrategy {
 	Parachain,
 }
 
+/// Which blocks the mapping database keeps the data of.
+///
+/// Blocks are only pruned once finalized, so the policy never drops data of a block that could
+/// still become canonical.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum RetentionPolicy {
+	/// Keep every synced block.
+	#[default]
+	Archive,
+	/// Keep the canonical blocks only.
+	ArchiveCanonical,
+	/// Keep the blocks of the last `n` block numbers below the best block.
+	KeepLast(u32),
+}
+
+impl RetentionPolicy {
+	/// The number of the oldest block to keep, given the best and finalized block numbers.
+	pub fn lower_bound(&self, best_number: u32, finalized_number: u32) -> Option<u32> {
+		match self {
+			Self::KeepLast(n) => Some(best_number.saturating_sub(*n).min(finalized_number)),
+			Self::Archive | Self::ArchiveCanonical => None,
+		}
+	}
+}
+
+impl std::str::FromStr for RetentionPolicy {
+	type Err = String;
+
+	fn from_str(input: &str) -> Result<Self, Self::Err> {
+		match input {
+			"archive" => Ok(Self::Archive),
+			"archive-canonical" => Ok(Self::ArchiveCanonical),
+			n => n.parse().map(Self::KeepLast).map_err(|_| {
+				"Invalid retention policy, expected `archive`, `archive-canonical` or a number"
+					.to_string()
+			}),
+		}
+	}
+}
+
 pub type EthereumBlockNotificationSinks<T> =
 	parking_lot::Mutex<Vec<sc_utils::mpsc::TracingUnboundedSender<T>>>;
 
@@ -31,9 +31,12 @@ use fp_rpc::EthereumRuntimeRPCApi;
 
 use crate::{
 	emit_block_notification, BlockNotificationContext, EthereumBlockNotification,
-	EthereumBlockNotificationSinks, ReorgInfo, SyncStrategy,
+	EthereumBlockNotificationSinks, ReorgInfo, RetentionPolicy, SyncStrategy,
 };
 
+/// Maximum number of block numbers pruned by a `Prune` command.
+const PRUNING_BATCH_SIZE: u32 = 10_000;
+
 /// Defines the commands for the sync worker.
 #[derive(Debug)]
 pub enum WorkerCommand<Block: BlockT<Hash = H256>> {
@@ -60,12 +63,16 @@ pub enum WorkerCommand<Block: BlockT<Hash = H256>> {
 	/// Check for any canon blocks that haven't had their logs indexed.
 	/// Check for any missing parent blocks from the latest canon block.
 	CheckIndexedBlocks,
+	/// Prune the indexed blocks according to the retention policy.
+	Prune(RetentionPolicy),
 }
 
 /// Config parameters for the SyncWorker.
 pub struct SyncWorkerConfig {
 	pub check_indexed_blocks_interval: Duration,
 	pub read_notification_timeout: Duration,
+	/// Blocks to keep in the database, pruning runs along the indexed blocks check.
+	pub retention_policy: RetentionPolicy,
 }
 
 /// Implements an indexer that imports blocks and their transactions.
@@ -93,6 +100,7 @@ where
 		sync_oracle: Arc<dyn SyncOracle + Send + Sync + 'static>,
 	) -> tokio::sync::mpsc::Sender<WorkerCommand<Block>> {
 		let (tx, mut rx) = tokio::sync::mpsc::channel(100);
+		let weak_tx = tx.downgrade();
 		tokio::task::spawn(async move {
 			while let Some(cmd) = rx.recv().await {
 				log::debug!(target: "frontier-sql", "💬 Recv Worker Command {cmd:?}");
@@ -184,6 +192,15 @@ where
 						)
 						.await;
 					}
+					WorkerCommand::Prune(retention_policy) => {
+						let has_more =
+							prune_blocks(client.clone(), indexer_backend.clone(), retention_policy)
+								.await;
+						// Catch up without waiting for the next check, unless the worker is busy.
+						if let Some(tx) = weak_tx.upgrade().filter(|_| has_more) {
+							tx.try_send(WorkerCommand::Prune(retention_policy)).ok();
+						}
+					}
 				}
 			}
 		});
@@ -217,10 +234,16 @@ where
 		tx.send(WorkerCommand::ResumeSync).await.ok();
 		// check missing blocks every interval
 		let tx2 = tx.clone();
+		let check_indexed_blocks_interval = worker_config.check_indexed_blocks_interval;
+		let retention_policy = worker_config.retention_policy;
 		tokio::task::spawn(async move {
 			loop {
-				futures_timer::Delay::new(worker_config.check_indexed_blocks_interval).await;
+				futures_timer::Delay::new(check_indexed_blocks_interval).await;
 				tx2.send(WorkerCommand::CheckIndexedBlocks).await.ok();
+				// Prune along the check, the indexed blocks are consistent by then.
+				if retention_policy != RetentionPolicy::Archive {
+					tx2.send(WorkerCommand::Prune(retention_policy)).await.ok();
+				}
 			}
 		});
 
@@ -408,6 +431,54 @@ async fn canonicalize_blocks<Block: BlockT<Hash = H256>>(
 	}
 }
 
+/// Prunes the indexed blocks according to the retention policy. Returns whether blocks are
+/// left to prune.
+async fn prune_blocks<Block, Client>(
+	client: Arc<Client>,
+	indexer_backend: Arc<fc_db::sql::Backend<Block>>,
+	retention_policy: RetentionPolicy,
+) -> bool
+where
+	Block: BlockT<Hash = H256>,
+	Client: HeaderBackend<Block> + 'static,
+{
+	let info = client.info();
+	let best_number = UniqueSaturatedInto::<u32>::unique_saturated_into(info.best_number);
+	let finalized_number = UniqueSaturatedInto::<u32>::unique_saturated_into(info.finalized_number);
+
+	let (result, has_more) = match retention_policy {
+		RetentionPolicy::Archive => return false,
+		RetentionPolicy::ArchiveCanonical => (
+			indexer_backend
+				.prune_non_canon_blocks(finalized_number)
+				.await,
+			false,
+		),
+		RetentionPolicy::KeepLast(_) => {
+			let lower_bound = retention_policy
+				.lower_bound(best_number, finalized_number)
+				.unwrap_or_default();
+			match indexer_backend.pruning_lower_bound().await {
+				Ok(current) if current < lower_bound => {
+					let until = lower_bound.min(current.saturating_add(PRUNING_BATCH_SIZE));
+					log::debug!(target: "frontier-sql", "🧹 Pruning blocks below #{until}");
+					(
+						indexer_backend.prune_blocks_below(until).await,
+						until < lower_bound,
+					)
+				}
+				Ok(_) => (Ok(()), false),
+				Err(err) => (Err(err), false),
+			}
+		}
+	};
+	if let Err(err) = result {
+		log::error!(target: "frontier-sql", "❌  Pruning failed: {err:?}");
+		return false;
+	}
+	has_more
+}
+
 /// Attempts to index any missing blocks that are in the past. This fixes any gaps that may
 /// be present in the indexing strategy, since the indexer only walks the parent hashes until
 /// it finds the first ancestor that has already been indexed.
@@ -685,6 +756,7 @@ mod test {
 				SyncWorkerConfig {
 					read_notification_timeout: Duration::from_secs(1),
 					check_indexed_blocks_interval: Duration::from_secs(60),
+					retention_policy: RetentionPolicy::Archive,
 				},
 				SyncStrategy::Parachain,
 				Arc::new(test_sync_oracle),
@@ -807,6 +879,7 @@ mod test {
 				SyncWorkerConfig {
 					read_notification_timeout: Duration::from_secs(10),
 					check_indexed_blocks_interval: Duration::from_secs(60),
+					retention_policy: RetentionPolicy::Archive,
 				},
 				SyncStrategy::Parachain,
 				Arc::new(test_sync_oracle),
@@ -1010,6 +1083,7 @@ mod test {
 				SyncWorkerConfig {
 					read_notification_timeout: Duration::from_secs(10),
 					check_indexed_blocks_interval: Duration::from_secs(60),
+					retention_policy: RetentionPolicy::Archive,
 				},
 				SyncStrategy::Parachain,
 				Arc::new(test_sync_oracle),
@@ -1213,6 +1287,7 @@ mod test {
 				SyncWorkerConfig {
 					read_notification_timeout: Duration::from_secs(10),
 					check_indexed_blocks_interval: Duration::from_secs(60),
+					retention_policy: RetentionPolicy::Archive,
 				},
 				SyncStrategy::Parachain,
 				Arc::new(test_sync_oracle),
@@ -1317,6 +1392,7 @@ mod test {
 				SyncWorkerConfig {
 					read_notification_timeout: Duration::from_secs(10),
 					check_indexed_blocks_interval: Duration::from_secs(60),
+					retention_policy: RetentionPolicy::Archive,
 				},
 				SyncStrategy::Normal,
 				Arc::new(sync_oracle),
@@ -1418,6 +1494,7 @@ mod test {
 				SyncWorkerConfig {
 					read_notification_timeout: Duration::from_secs(10),
 					check_indexed_blocks_interval: Duration::from_secs(60),
+					retention_policy: RetentionPolicy::Archive,
 				},
 				SyncStrategy::Normal,
 				Arc::new(sync_oracle),
@@ -1533,6 +1610,7 @@ mod test {
 				SyncWorkerConfig {
 					read_notification_timeout: Duration::from_secs(10),
 					check_indexed_blocks_interval: Duration::from_secs(60),
+					retention_policy: RetentionPolicy::Archive,
 				},
 				SyncStrategy::Parachain,
 				Arc::new(sync_oracle),
@@ -1634,6 +1712,7 @@ mod test {
 				SyncWorkerConfig {
 					read_notification_timeout: Duration::from_secs(10),
 					check_indexed_blocks_interval: Duration::from_secs(60),
+					retention_policy: RetentionPolicy::Archive,
 				},
 				SyncStrategy::Parachain,
 				Arc::new(sync_oracle),
@@ -1749,6 +1828,7 @@ mod test {
 				SyncWorkerConfig {
 					read_notification_timeout: Duration::from_secs(10),
 					check_indexed_blocks_interval: Duration::from_secs(60),
+					retention_policy: RetentionPolicy::Archive,
 				},
 				SyncStrategy::Normal,
 				Arc::new(sync_oracle),
@@ -1850,6 +1930,7 @@ mod test {
 				SyncWorkerConfig {
 					read_notification_timeout: Duration::from_secs(10),
 					check_indexed_blocks_interval: Duration::from_secs(60),
+					retention_policy: RetentionPolicy::Archive,
 				},
 				SyncStrategy::Parachain,
 				Arc::new(sync_oracle),
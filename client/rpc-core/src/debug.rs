@@ -21,7 +21,10 @@
 use ethereum_types::H256;
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 
-use crate::types::{BlockNumberOrHash, Bytes};
+use crate::types::{
+	BlockNumberOrHash, Bytes, Trace, TraceCallParams, TraceParams, TransactionRequest,
+	TransactionTrace,
+};
 
 /// Net rpc interface.
 #[rpc(server)]
@@ -46,4 +49,37 @@ pub trait DebugApi {
 	/// Returns an array of recent bad blocks that the client has seen on the network.
 	#[method(name = "debug_getBadBlocks")]
 	fn bad_blocks(&self, number: BlockNumberOrHash) -> RpcResult<Vec<()>>;
+
+	/// Replays the transaction with the given hash and returns its trace.
+	#[method(name = "debug_traceTransaction")]
+	async fn trace_transaction(
+		&self,
+		transaction_hash: H256,
+		params: Option<TraceParams>,
+	) -> RpcResult<Trace>;
+
+	/// Replays all the transactions of the block with the given number and returns their traces.
+	#[method(name = "debug_traceBlockByNumber")]
+	async fn trace_block_by_number(
+		&self,
+		number: BlockNumberOrHash,
+		params: Option<TraceParams>,
+	) -> RpcResult<Vec<TransactionTrace>>;
+
+	/// Replays all the transactions of the block with the given hash and returns their traces.
+	#[method(name = "debug_traceBlockByHash")]
+	async fn trace_block_by_hash(
+		&self,
+		hash: H256,
+		params: Option<TraceParams>,
+	) -> RpcResult<Vec<TransactionTrace>>;
+
+	/// Executes a call on top of the state of the given block and returns its trace.
+	#[method(name = "debug_traceCall")]
+	async fn trace_call(
+		&self,
+		request: TransactionRequest,
+		number_or_hash: Option<BlockNumberOrHash>,
+		params: Option<TraceCallParams>,
+	) -> RpcResult<Trace>;
 }
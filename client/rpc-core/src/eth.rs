@@ -181,6 +181,16 @@ pub trait EthApi {
 		number_or_hash: Option<BlockNumberOrHash>,
 	) -> RpcResult<Bytes>;
 
+	/// Returns the account and storage values of the given account, along with the Substrate
+	/// storage proofs of these values at given time (block number).
+	#[method(name = "eth_getProof")]
+	async fn proof(
+		&self,
+		address: H160,
+		storage_keys: Vec<H256>,
+		number_or_hash: Option<BlockNumberOrHash>,
+	) -> RpcResult<EthAccount>;
+
 	// ########################################################################
 	// Execute
 	// ########################################################################
@@ -192,6 +202,7 @@ pub trait EthApi {
 		request: TransactionRequest,
 		number_or_hash: Option<BlockNumberOrHash>,
 		state_overrides: Option<BTreeMap<H160, CallStateOverride>>,
+		block_overrides: Option<BlockOverrides>,
 	) -> RpcResult<Bytes>;
 
 	/// Estimate gas needed for execution of given contract.
@@ -200,8 +211,28 @@ pub trait EthApi {
 		&self,
 		request: TransactionRequest,
 		number_or_hash: Option<BlockNumberOrHash>,
+		state_overrides: Option<BTreeMap<H160, CallStateOverride>>,
+		block_overrides: Option<BlockOverrides>,
 	) -> RpcResult<U256>;
 
+	/// Generates the access list of given transaction, along with the gas it uses with that
+	/// access list applied.
+	#[method(name = "eth_createAccessList")]
+	async fn create_access_list(
+		&self,
+		request: TransactionRequest,
+		number_or_hash: Option<BlockNumberOrHash>,
+	) -> RpcResult<AccessListResult>;
+
+	/// Simulates a sequence of calls across virtual blocks built on top of given block, state
+	/// changes carrying over from one call to the next.
+	#[method(name = "eth_simulateV1")]
+	async fn simulate(
+		&self,
+		payload: SimulatePayload,
+		number_or_hash: Option<BlockNumberOrHash>,
+	) -> RpcResult<Vec<SimulatedBlock>>;
+
 	/// Returns all pending transactions.
 	#[method(name = "eth_pendingTransactions")]
 	async fn pending_transactions(&self) -> RpcResult<Vec<Transaction>>;
@@ -252,6 +283,26 @@ pub trait EthApi {
 	#[method(name = "eth_submitWork")]
 	fn submit_work(&self, nonce: H64, pow_hash: H256, mix_digest: H256) -> RpcResult<bool>;
 
+	// ########################################################################
+	// Sign
+	// ########################################################################
+
+	/// Signs data with the given account, prefixed as of EIP-191.
+	#[method(name = "eth_sign")]
+	fn sign(&self, address: H160, data: Bytes) -> RpcResult<Bytes>;
+
+	/// Signs EIP-712 typed data with the given account.
+	#[method(name = "eth_signTypedData_v4")]
+	fn sign_typed_data_v4(&self, address: H160, typed_data: TypedData) -> RpcResult<Bytes>;
+
+	/// Signs a transaction without submitting it, returning the RLP encoded transaction along
+	/// with its details.
+	#[method(name = "eth_signTransaction")]
+	async fn sign_transaction(
+		&self,
+		request: TransactionSigningRequest,
+	) -> RpcResult<RichRawTransaction>;
+
 	// ########################################################################
 	// Submit
 	// ########################################################################
@@ -259,7 +310,7 @@ pub trait EthApi {
 	/// Sends transaction; will block waiting for signer to return the
 	/// transaction hash.
 	#[method(name = "eth_sendTransaction")]
-	async fn send_transaction(&self, request: TransactionRequest) -> RpcResult<H256>;
+	async fn send_transaction(&self, request: TransactionSigningRequest) -> RpcResult<H256>;
 
 	/// Sends signed transaction, returning its hash.
 	#[method(name = "eth_sendRawTransaction")]
@@ -25,6 +25,8 @@ mod debug;
 mod eth;
 mod eth_pubsub;
 mod net;
+mod personal;
+mod trace;
 #[cfg(feature = "txpool")]
 mod txpool;
 mod web3;
@@ -36,5 +38,7 @@ pub use self::{
 	eth::{EthApiServer, EthFilterApiServer},
 	eth_pubsub::EthPubSubApiServer,
 	net::NetApiServer,
+	personal::PersonalApiServer,
+	trace::TraceApiServer,
 	web3::Web3ApiServer,
 };
@@ -0,0 +1,58 @@
+// This file is part of Frontier.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Personal rpc interface.
+
+use ethereum_types::H160;
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+
+use crate::types::Bytes;
+
+/// Personal rpc interface, managing the accounts of a local keystore.
+#[rpc(server)]
+pub trait PersonalApi {
+	/// Returns the addresses of all the accounts in the keystore.
+	#[method(name = "personal_listAccounts")]
+	fn list_accounts(&self) -> RpcResult<Vec<H160>>;
+
+	/// Generates a new account, stores it encrypted with the given passphrase and returns its
+	/// address.
+	#[method(name = "personal_newAccount", blocking)]
+	fn new_account(&self, passphrase: String) -> RpcResult<H160>;
+
+	/// Decrypts the key of the given account, so that it can sign transactions.
+	///
+	/// The account stays unlocked for `duration` seconds (300 by default), or until it is
+	/// locked again if `duration` is zero.
+	#[method(name = "personal_unlockAccount", blocking)]
+	fn unlock_account(
+		&self,
+		address: H160,
+		passphrase: String,
+		duration: Option<u64>,
+	) -> RpcResult<bool>;
+
+	/// Removes the decrypted key of the given account from memory.
+	#[method(name = "personal_lockAccount")]
+	fn lock_account(&self, address: H160) -> RpcResult<bool>;
+
+	/// Signs data with the given account, prefixed as of EIP-191. The key is decrypted with the
+	/// passphrase for this signature only, the account does not need to be unlocked.
+	#[method(name = "personal_sign", blocking)]
+	fn sign(&self, data: Bytes, address: H160, passphrase: String) -> RpcResult<Bytes>;
+}
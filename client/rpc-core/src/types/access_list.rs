@@ -0,0 +1,87 @@
+// This file is part of Frontier.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use ethereum::AccessListItem;
+use ethereum_types::{H160, H256, U256};
+use serde::{ser::SerializeSeq, Serialize, Serializer};
+
+/// Response of `eth_createAccessList`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessListResult {
+	#[serde(serialize_with = "serialize_access_list")]
+	pub access_list: Vec<AccessListItem>,
+	/// Gas used by the transaction with the access list applied.
+	pub gas_used: U256,
+	/// Error of the execution, if it failed.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+}
+
+fn serialize_access_list<S>(
+	access_list: &[AccessListItem],
+	serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+	S: Serializer,
+{
+	#[derive(Serialize)]
+	#[serde(rename_all = "camelCase")]
+	struct Item<'a> {
+		address: &'a H160,
+		storage_keys: &'a [H256],
+	}
+
+	let mut seq = serializer.serialize_seq(Some(access_list.len()))?;
+	for item in access_list {
+		seq.serialize_element(&Item {
+			address: &item.address,
+			storage_keys: &item.storage_keys,
+		})?;
+	}
+	seq.end()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn access_list_result_serialization() {
+		let result = AccessListResult {
+			access_list: vec![AccessListItem {
+				address: H160::from_low_u64_be(1),
+				storage_keys: vec![H256::from_low_u64_be(2)],
+			}],
+			gas_used: U256::from(0x5208),
+			error: None,
+		};
+		assert_eq!(
+			serde_json::to_value(&result).unwrap(),
+			serde_json::json!({
+				"accessList": [{
+					"address": "0x0000000000000000000000000000000000000001",
+					"storageKeys": [
+						"0x0000000000000000000000000000000000000000000000000000000000000002"
+					]
+				}],
+				"gasUsed": "0x5208"
+			})
+		);
+	}
+}
@@ -34,12 +34,16 @@ pub struct AccountInfo {
 #[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct StorageProof {
-	pub key: U256,
+	pub key: H256,
 	pub value: U256,
+	/// Substrate trie nodes proving the `EVM::AccountStorages` entry of the slot.
 	pub proof: Vec<Bytes>,
 }
 
-/// Account information.
+/// Account information, in the layout of EIP-1186.
+///
+/// The EVM state lives in the Substrate state trie, so the proofs are Substrate read proofs
+/// checked against the state root of the block rather than Ethereum Merkle-Patricia proofs.
 #[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct EthAccount {
@@ -47,7 +51,10 @@ pub struct EthAccount {
 	pub balance: U256,
 	pub nonce: U256,
 	pub code_hash: H256,
+	/// Substrate state root, which both the account and the storage proofs are checked against.
 	pub storage_hash: H256,
+	/// Substrate trie nodes proving the `System::Account` and `EVM::AccountCodes` entries of the
+	/// account.
 	pub account_proof: Vec<Bytes>,
 	pub storage_proof: Vec<StorageProof>,
 }
@@ -18,7 +18,7 @@
 
 use std::collections::BTreeMap;
 
-use ethereum_types::{H256, U256};
+use ethereum_types::{H160, H256, U256};
 use serde::Deserialize;
 
 use crate::types::Bytes;
@@ -41,3 +41,60 @@ pub struct CallStateOverride {
 	/// executing the call.
 	pub state_diff: Option<BTreeMap<H256, H256>>,
 }
+
+/// Block environment overrides.
+///
+/// The geth overrides without an equivalent here, such as `difficulty` or `blobBaseFee`, are
+/// accepted and ignored.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockOverrides {
+	/// Fake block number.
+	pub number: Option<U256>,
+	/// Fake block timestamp, in seconds.
+	pub time: Option<U256>,
+	/// Fake block gas limit.
+	pub gas_limit: Option<U256>,
+	/// Fake block author.
+	#[serde(alias = "feeRecipient")]
+	pub coinbase: Option<H160>,
+	/// Fake `PREVRANDAO` value.
+	#[serde(alias = "prevRandao")]
+	pub random: Option<H256>,
+	/// Fake block base fee per gas.
+	#[serde(alias = "baseFeePerGas")]
+	pub base_fee: Option<U256>,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn block_overrides_deserialization() {
+		let overrides: BlockOverrides = serde_json::from_str(
+			r#"{
+				"number": "0x10",
+				"difficulty": "0x1",
+				"time": "0x64",
+				"feeRecipient": "0x0000000000000000000000000000000000000001",
+				"prevRandao": "0x0000000000000000000000000000000000000000000000000000000000000002",
+				"baseFeePerGas": "0x3",
+				"blobBaseFee": "0x4"
+			}"#,
+		)
+		.unwrap();
+
+		assert_eq!(
+			overrides,
+			BlockOverrides {
+				number: Some(U256::from(0x10)),
+				time: Some(U256::from(0x64)),
+				gas_limit: None,
+				coinbase: Some(H160::from_low_u64_be(1)),
+				random: Some(H256::from_low_u64_be(2)),
+				base_fee: Some(U256::from(3)),
+			}
+		);
+	}
+}
@@ -34,6 +34,12 @@ impl Index {
 	}
 }
 
+impl From<usize> for Index {
+	fn from(value: usize) -> Self {
+		Self(value)
+	}
+}
+
 impl<'a> Deserialize<'a> for Index {
 	fn deserialize<D>(deserializer: D) -> Result<Index, D::Error>
 	where
@@ -18,6 +18,7 @@
 
 //! RPC types
 
+mod access_list;
 mod account_info;
 mod block;
 mod block_count;
@@ -28,12 +29,16 @@ mod fee;
 mod filter;
 mod index;
 mod log;
+mod parity_trace;
 mod receipt;
+mod simulate;
 mod sync;
+mod trace;
 mod transaction;
 mod transaction_request;
 #[cfg(feature = "txpool")]
 mod txpool;
+mod typed_data;
 mod work;
 
 pub mod pubsub;
@@ -44,12 +49,13 @@ use ethereum_types::H160;
 #[cfg(feature = "txpool")]
 pub use self::txpool::{Summary, TransactionMap, TxPoolResult};
 pub use self::{
+	access_list::AccessListResult,
 	account_info::{AccountInfo, EthAccount, ExtAccountInfo, RecoveredAccount, StorageProof},
 	block::{Block, BlockTransactions, Header, Rich, RichBlock, RichHeader},
 	block_count::BlockCount,
 	block_number::BlockNumberOrHash,
 	bytes::Bytes,
-	call_request::CallStateOverride,
+	call_request::{BlockOverrides, CallStateOverride},
 	fee::{FeeHistory, FeeHistoryCache, FeeHistoryCacheItem, FeeHistoryCacheLimit},
 	filter::{
 		Filter, FilterAddress, FilterChanges, FilterPool, FilterPoolItem, FilterType,
@@ -57,13 +63,25 @@ pub use self::{
 	},
 	index::Index,
 	log::Log,
+	parity_trace::{
+		CallAction, CallOutput, CreateAction, CreateOutput, LocalizedTrace, SuicideAction,
+		TraceAction, TraceFilter, TraceOutput,
+	},
 	receipt::Receipt,
+	simulate::{
+		SimulateBlock, SimulateCallError, SimulateCallResult, SimulatePayload, SimulatedBlock,
+	},
 	sync::{
 		ChainStatus, EthProtocolInfo, PeerCount, PeerInfo, PeerNetworkInfo, PeerProtocolsInfo,
 		Peers, PipProtocolInfo, SyncInfo, SyncStatus, TransactionStats,
 	},
+	trace::{
+		CallFrame, PrestateAccount, PrestateDiff, StructLog, StructLoggerTrace, Trace,
+		TraceCallParams, TraceParams, TracerConfig, TransactionTrace,
+	},
 	transaction::{LocalTransactionStatus, RichRawTransaction, Transaction},
-	transaction_request::{TransactionMessage, TransactionRequest},
+	transaction_request::{TransactionMessage, TransactionRequest, TransactionSigningRequest},
+	typed_data::{TypedData, TypedDataField},
 	work::Work,
 };
 
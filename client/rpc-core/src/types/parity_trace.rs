@@ -0,0 +1,263 @@
+// This file is part of Frontier.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Parity-style flat traces (`trace_*`).
+
+use ethereum_types::{H160, H256, U256};
+use serde::{Deserialize, Serialize};
+
+use crate::types::{BlockNumberOrHash, Bytes};
+
+/// Filter of `trace_filter`.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceFilter {
+	/// First block to search, the latest block when missing.
+	pub from_block: Option<BlockNumberOrHash>,
+	/// Last block to search, the latest block when missing.
+	pub to_block: Option<BlockNumberOrHash>,
+	/// Only return the traces sent by one of these addresses.
+	pub from_address: Option<Vec<H160>>,
+	/// Only return the traces received by one of these addresses.
+	pub to_address: Option<Vec<H160>>,
+	/// Number of matching traces to skip.
+	pub after: Option<usize>,
+	/// Maximum number of traces to return.
+	pub count: Option<usize>,
+}
+
+impl TraceFilter {
+	/// Whether the trace matches the address filters of this filter.
+	pub fn matches(&self, trace: &LocalizedTrace) -> bool {
+		fn contains(addresses: &Option<Vec<H160>>, address: Option<H160>) -> bool {
+			match addresses {
+				Some(addresses) if !addresses.is_empty() => {
+					address.is_some_and(|address| addresses.contains(&address))
+				}
+				_ => true,
+			}
+		}
+
+		contains(&self.from_address, Some(trace.action.from()))
+			&& contains(&self.to_address, trace.to())
+	}
+}
+
+/// A trace of a single call frame, located in its block and transaction.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalizedTrace {
+	#[serde(flatten)]
+	pub action: TraceAction,
+	/// Output of the frame, `None` when it failed or for self-destructs.
+	pub result: Option<TraceOutput>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+	/// Number of direct sub-calls of the frame.
+	pub subtraces: usize,
+	/// Path of the frame in the call tree of the transaction.
+	pub trace_address: Vec<usize>,
+	pub transaction_hash: Option<H256>,
+	pub transaction_position: Option<usize>,
+	pub block_hash: H256,
+	pub block_number: U256,
+}
+
+impl LocalizedTrace {
+	/// Receiver of the frame, the created contract for creations.
+	pub fn to(&self) -> Option<H160> {
+		match (&self.action, &self.result) {
+			(TraceAction::Call(call), _) => Some(call.to),
+			(TraceAction::Create(_), Some(TraceOutput::Create(result))) => Some(result.address),
+			(TraceAction::Create(_), _) => None,
+			(TraceAction::Suicide(suicide), _) => Some(suicide.refund_address),
+		}
+	}
+}
+
+/// Action of a trace, serialized along with its `type`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(tag = "type", content = "action", rename_all = "lowercase")]
+pub enum TraceAction {
+	Call(CallAction),
+	Create(CreateAction),
+	Suicide(SuicideAction),
+}
+
+impl TraceAction {
+	/// Sender of the frame, the destroyed contract for self-destructs.
+	pub fn from(&self) -> H160 {
+		match self {
+			TraceAction::Call(call) => call.from,
+			TraceAction::Create(create) => create.from,
+			TraceAction::Suicide(suicide) => suicide.address,
+		}
+	}
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallAction {
+	/// Kind of call, e.g. `call` or `delegatecall`.
+	pub call_type: String,
+	pub from: H160,
+	pub to: H160,
+	pub gas: U256,
+	pub input: Bytes,
+	pub value: U256,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateAction {
+	pub from: H160,
+	pub gas: U256,
+	pub init: Bytes,
+	pub value: U256,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SuicideAction {
+	pub address: H160,
+	pub refund_address: H160,
+	pub balance: U256,
+}
+
+/// Output of a successful frame.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum TraceOutput {
+	Call(CallOutput),
+	Create(CreateOutput),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallOutput {
+	pub gas_used: U256,
+	pub output: Bytes,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateOutput {
+	pub address: H160,
+	pub code: Bytes,
+	pub gas_used: U256,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn call_trace(from: H160, to: H160) -> LocalizedTrace {
+		LocalizedTrace {
+			action: TraceAction::Call(CallAction {
+				call_type: "call".into(),
+				from,
+				to,
+				gas: U256::from(0x5208),
+				input: Bytes::default(),
+				value: U256::zero(),
+			}),
+			result: Some(TraceOutput::Call(CallOutput {
+				gas_used: U256::zero(),
+				output: Bytes::default(),
+			})),
+			error: None,
+			subtraces: 0,
+			trace_address: vec![],
+			transaction_hash: Some(H256::zero()),
+			transaction_position: Some(0),
+			block_hash: H256::zero(),
+			block_number: U256::one(),
+		}
+	}
+
+	#[test]
+	fn trace_filter_deserialization() {
+		let filter: TraceFilter = serde_json::from_str(
+			r#"{
+				"fromBlock": "0x1",
+				"toBlock": "latest",
+				"toAddress": ["0x0000000000000000000000000000000000000002"],
+				"after": 1,
+				"count": 10
+			}"#,
+		)
+		.unwrap();
+		assert_eq!(
+			filter,
+			TraceFilter {
+				from_block: Some(BlockNumberOrHash::Num(1)),
+				to_block: Some(BlockNumberOrHash::Latest),
+				from_address: None,
+				to_address: Some(vec![H160::from_low_u64_be(2)]),
+				after: Some(1),
+				count: Some(10),
+			}
+		);
+	}
+
+	#[test]
+	fn trace_filter_matches_addresses() {
+		let trace = call_trace(H160::from_low_u64_be(1), H160::from_low_u64_be(2));
+		let filter = |from: Option<Vec<H160>>, to: Option<Vec<H160>>| TraceFilter {
+			from_address: from,
+			to_address: to,
+			..Default::default()
+		};
+
+		assert!(filter(None, None).matches(&trace));
+		assert!(filter(Some(vec![]), None).matches(&trace));
+		assert!(filter(Some(vec![H160::from_low_u64_be(1)]), None).matches(&trace));
+		assert!(filter(None, Some(vec![H160::from_low_u64_be(2)])).matches(&trace));
+		assert!(!filter(Some(vec![H160::from_low_u64_be(2)]), None).matches(&trace));
+		assert!(!filter(None, Some(vec![H160::from_low_u64_be(1)])).matches(&trace));
+	}
+
+	#[test]
+	fn localized_trace_serialization() {
+		let trace = call_trace(H160::from_low_u64_be(1), H160::from_low_u64_be(2));
+		assert_eq!(
+			serde_json::to_value(&trace).unwrap(),
+			serde_json::json!({
+				"type": "call",
+				"action": {
+					"callType": "call",
+					"from": "0x0000000000000000000000000000000000000001",
+					"to": "0x0000000000000000000000000000000000000002",
+					"gas": "0x5208",
+					"input": "0x",
+					"value": "0x0"
+				},
+				"result": {
+					"gasUsed": "0x0",
+					"output": "0x"
+				},
+				"subtraces": 0,
+				"traceAddress": [],
+				"transactionHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+				"transactionPosition": 0,
+				"blockHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+				"blockNumber": "0x1"
+			})
+		);
+	}
+}
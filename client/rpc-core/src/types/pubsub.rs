@@ -21,13 +21,15 @@
 use std::collections::BTreeMap;
 
 use ethereum::{BlockV3 as EthereumBlock, TransactionV3 as EthereumTransaction};
-use ethereum_types::{H256, U256};
+use ethereum_types::{H160, H256, U256};
 use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::{from_value, Value};
 // Substrate
 use sp_crypto_hashing::keccak_256;
 
-use crate::types::{Bytes, Filter, Header, Log, Rich, RichHeader};
+use crate::types::{
+	Bytes, Filter, FilterAddress, Header, Log, Rich, RichHeader, Transaction, VariadicValue,
+};
 
 /// Subscription kind.
 #[derive(Clone, Debug, Eq, PartialEq, Hash, Deserialize)]
@@ -42,6 +44,10 @@ pub enum Kind {
 	NewPendingTransactions,
 	/// Node syncing status subscription.
 	Syncing,
+	/// New finalized block headers subscription.
+	NewFinalizedHeads,
+	/// New safe block headers subscription.
+	NewSafeHeads,
 }
 
 /// Subscription kind.
@@ -52,6 +58,47 @@ pub enum Params {
 	None,
 	/// Log parameters.
 	Logs(Filter),
+	/// Pending transactions parameters.
+	PendingTransactions(PendingTransactionsParams),
+}
+
+/// Parameters of the pending transactions subscription.
+///
+/// Either the geth `true` flag requesting full transactions, or an object also filtering the
+/// transactions on their sender and recipient.
+#[derive(Clone, Debug, Eq, PartialEq, Default, Hash, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingTransactionsParams {
+	/// Whether full transactions are emitted rather than their hashes.
+	#[serde(default)]
+	pub full_transactions: bool,
+	/// Sender(s) the transactions must be from.
+	pub from: Option<FilterAddress>,
+	/// Recipient(s) the transactions must be to.
+	pub to: Option<FilterAddress>,
+}
+
+impl PendingTransactionsParams {
+	/// Whether a transaction from `from` to `to` passes the filter.
+	pub fn matches(&self, from: H160, to: Option<H160>) -> bool {
+		fn matches(filter: &Option<FilterAddress>, address: Option<H160>) -> bool {
+			match filter {
+				None | Some(VariadicValue::Null) => true,
+				Some(VariadicValue::Single(expected)) => address == Some(*expected),
+				Some(VariadicValue::Multiple(expected)) => {
+					expected.is_empty()
+						|| address.is_some_and(|address| expected.contains(&address))
+				}
+			}
+		}
+		matches(&self.from, Some(from)) && matches(&self.to, to)
+	}
+
+	/// Whether the transactions need to be decoded, rather than only hashed.
+	pub fn needs_transaction(&self) -> bool {
+		self.full_transactions || self.from.is_some() || self.to.is_some()
+	}
 }
 
 impl<'a> Deserialize<'a> for Params {
@@ -65,6 +112,20 @@ impl<'a> Deserialize<'a> for Params {
 			return Ok(Params::None);
 		}
 
+		if let Value::Bool(full_transactions) = v {
+			return Ok(Params::PendingTransactions(PendingTransactionsParams {
+				full_transactions,
+				..Default::default()
+			}));
+		}
+
+		// An empty object is taken as an empty log filter.
+		if v.as_object().is_some_and(|object| !object.is_empty()) {
+			if let Ok(params) = from_value::<PendingTransactionsParams>(v.clone()) {
+				return Ok(Params::PendingTransactions(params));
+			}
+		}
+
 		from_value(v)
 			.map(Params::Logs)
 			.map_err(|e| D::Error::custom(format!("Invalid Pub-Sub parameters: {e}")))
@@ -80,6 +141,8 @@ pub enum PubSubResult {
 	Log(Box<Log>),
 	/// Transaction hash
 	TransactionHash(H256),
+	/// Transaction
+	Transaction(Box<Transaction>),
 	/// SyncStatus
 	SyncingStatus(PubSubSyncing),
 }
@@ -124,6 +187,7 @@ impl Serialize for PubSubResult {
 			Self::Header(ref header) => header.serialize(serializer),
 			Self::Log(ref log) => log.serialize(serializer),
 			Self::TransactionHash(ref hash) => hash.serialize(serializer),
+			Self::Transaction(ref transaction) => transaction.serialize(serializer),
 			Self::SyncingStatus(ref sync) => sync.serialize(serializer),
 		}
 	}
@@ -145,3 +209,48 @@ pub struct SyncingStatus {
 	#[serde(default = "Default::default", skip_serializing_if = "Option::is_none")]
 	pub highest_block: Option<u64>,
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+
+	#[test]
+	fn deserializes_kind() {
+		let kind: Kind = serde_json::from_value(json!("newFinalizedHeads")).unwrap();
+		assert_eq!(kind, Kind::NewFinalizedHeads);
+		let kind: Kind = serde_json::from_value(json!("newSafeHeads")).unwrap();
+		assert_eq!(kind, Kind::NewSafeHeads);
+	}
+
+	#[test]
+	fn deserializes_pending_transactions_params() {
+		let params: Params = serde_json::from_value(json!(true)).unwrap();
+		assert_eq!(
+			params,
+			Params::PendingTransactions(PendingTransactionsParams {
+				full_transactions: true,
+				..Default::default()
+			})
+		);
+
+		let params: Params = serde_json::from_value(json!({
+			"fullTransactions": true,
+			"from": "0x1111111111111111111111111111111111111111",
+			"to": ["0x2222222222222222222222222222222222222222"]
+		}))
+		.unwrap();
+		let Params::PendingTransactions(params) = params else {
+			panic!("expected pending transactions parameters");
+		};
+		assert!(params.full_transactions);
+		assert!(params.matches(H160::repeat_byte(0x11), Some(H160::repeat_byte(0x22))));
+		assert!(!params.matches(H160::repeat_byte(0x11), None));
+		assert!(!params.matches(H160::repeat_byte(0x22), Some(H160::repeat_byte(0x22))));
+
+		let params: Params = serde_json::from_value(json!({})).unwrap();
+		assert_eq!(params, Params::Logs(Filter::default()));
+		let params: Params = serde_json::from_value(json!({ "address": [] })).unwrap();
+		assert!(matches!(params, Params::Logs(_)));
+	}
+}
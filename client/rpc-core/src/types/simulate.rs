@@ -0,0 +1,158 @@
+// This file is part of Frontier.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::collections::BTreeMap;
+
+use ethereum_types::{H160, U256, U64};
+use serde::{Deserialize, Serialize};
+
+use crate::types::{Block, BlockOverrides, Bytes, CallStateOverride, Log, TransactionRequest};
+
+/// Payload of `eth_simulateV1`.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatePayload {
+	/// Virtual blocks to simulate, in order, on top of the requested block.
+	pub block_state_calls: Vec<SimulateBlock>,
+	/// Whether ETH transfers should be reported as logs.
+	#[serde(default)]
+	pub trace_transfers: bool,
+	/// Whether the nonces and the fees of the calls are checked as for transactions.
+	#[serde(default)]
+	pub validation: bool,
+	/// Whether the blocks hold the full transactions rather than their hashes.
+	#[serde(default)]
+	pub return_full_transactions: bool,
+}
+
+/// A virtual block of `eth_simulateV1`.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulateBlock {
+	/// Overrides of the block environment.
+	pub block_overrides: Option<BlockOverrides>,
+	/// State overrides applied before the calls of the block.
+	pub state_overrides: Option<BTreeMap<H160, CallStateOverride>>,
+	/// Calls executed in the block, in order.
+	#[serde(default)]
+	pub calls: Vec<TransactionRequest>,
+}
+
+/// A simulated block along with the results of its calls.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedBlock {
+	#[serde(flatten)]
+	pub inner: Block,
+	pub calls: Vec<SimulateCallResult>,
+}
+
+/// Result of a call of `eth_simulateV1`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulateCallResult {
+	pub return_data: Bytes,
+	pub logs: Vec<Log>,
+	pub gas_used: U256,
+	/// `1` if the call succeeded, `0` otherwise.
+	pub status: U64,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<SimulateCallError>,
+}
+
+/// Error of a failed call of `eth_simulateV1`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct SimulateCallError {
+	pub code: i32,
+	pub message: String,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub data: Option<Bytes>,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn simulate_payload_deserialization() {
+		let payload: SimulatePayload = serde_json::from_str(
+			r#"{
+				"blockStateCalls": [{
+					"blockOverrides": { "number": "0x10", "baseFeePerGas": "0x0" },
+					"stateOverrides": {
+						"0x0000000000000000000000000000000000000001": { "balance": "0x100" }
+					},
+					"calls": [{
+						"from": "0x0000000000000000000000000000000000000001",
+						"to": "0x0000000000000000000000000000000000000002"
+					}]
+				}, {}],
+				"validation": true
+			}"#,
+		)
+		.unwrap();
+
+		assert!(payload.validation);
+		assert!(!payload.trace_transfers);
+		assert_eq!(payload.block_state_calls.len(), 2);
+		let block = &payload.block_state_calls[0];
+		assert_eq!(
+			block.block_overrides,
+			Some(BlockOverrides {
+				number: Some(U256::from(0x10)),
+				base_fee: Some(U256::zero()),
+				..Default::default()
+			})
+		);
+		assert_eq!(
+			block.state_overrides.as_ref().unwrap()[&H160::from_low_u64_be(1)].balance,
+			Some(U256::from(0x100))
+		);
+		assert_eq!(block.calls[0].to, Some(H160::from_low_u64_be(2)));
+		assert_eq!(payload.block_state_calls[1], SimulateBlock::default());
+	}
+
+	#[test]
+	fn simulate_call_result_serialization() {
+		let result = SimulateCallResult {
+			return_data: Bytes(vec![0x08, 0xc3, 0x79, 0xa0]),
+			logs: vec![],
+			gas_used: U256::from(0x5208),
+			status: U64::zero(),
+			error: Some(SimulateCallError {
+				code: 3,
+				message: "execution reverted".into(),
+				data: Some(Bytes(vec![0x08, 0xc3, 0x79, 0xa0])),
+			}),
+		};
+		assert_eq!(
+			serde_json::to_value(&result).unwrap(),
+			serde_json::json!({
+				"returnData": "0x08c379a0",
+				"logs": [],
+				"gasUsed": "0x5208",
+				"status": "0x0",
+				"error": {
+					"code": 3,
+					"message": "execution reverted",
+					"data": "0x08c379a0"
+				}
+			})
+		);
+	}
+}
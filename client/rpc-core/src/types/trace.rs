@@ -0,0 +1,282 @@
+// This file is part of Frontier.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Geth-style transaction traces (`debug_trace*`).
+
+use std::collections::BTreeMap;
+
+use ethereum_types::{H160, H256, U256};
+use rustc_hex::ToHex;
+use serde::{ser::SerializeMap, ser::SerializeSeq, Deserialize, Serialize, Serializer};
+
+use crate::types::{BlockOverrides, Bytes, CallStateOverride};
+
+/// Options of the `debug_trace*` methods.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceParams {
+	/// Name of the tracer to run, the struct logger is used when missing.
+	pub tracer: Option<String>,
+	/// Options of the selected tracer.
+	pub tracer_config: Option<TracerConfig>,
+	/// Struct logger: do not capture the stack.
+	pub disable_stack: Option<bool>,
+	/// Struct logger: do not capture the storage.
+	pub disable_storage: Option<bool>,
+	/// Struct logger: capture the memory.
+	pub enable_memory: Option<bool>,
+	/// Struct logger: capture the return data.
+	pub enable_return_data: Option<bool>,
+	/// Struct logger: maximum number of steps to capture.
+	pub limit: Option<u64>,
+	/// Overrides the default timeout of the tracing, accepted for compatibility only.
+	pub timeout: Option<String>,
+}
+
+/// Options of `debug_traceCall`.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceCallParams {
+	#[serde(flatten)]
+	pub trace: TraceParams,
+	/// State overrides applied before executing the call.
+	pub state_overrides: Option<BTreeMap<H160, CallStateOverride>>,
+	/// Block environment overrides applied while executing the call.
+	pub block_overrides: Option<BlockOverrides>,
+}
+
+/// Options of the built-in tracers.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TracerConfig {
+	/// Call tracer: only trace the top-level call frame.
+	pub only_top_call: Option<bool>,
+	/// Prestate tracer: return the modified accounts before and after the execution.
+	pub diff_mode: Option<bool>,
+}
+
+/// Output of a tracer.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum Trace {
+	StructLogger(StructLoggerTrace),
+	CallTracer(CallFrame),
+	Prestate(BTreeMap<H160, PrestateAccount>),
+	PrestateDiff(PrestateDiff),
+}
+
+/// Trace of a transaction, as returned by the block tracing methods.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionTrace {
+	pub tx_hash: H256,
+	pub result: Trace,
+}
+
+/// Output of the struct logger.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLoggerTrace {
+	pub gas: u64,
+	pub failed: bool,
+	pub return_value: Bytes,
+	pub struct_logs: Vec<StructLog>,
+}
+
+/// A single opcode step.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLog {
+	pub pc: u64,
+	pub op: String,
+	pub gas: u64,
+	pub gas_cost: u64,
+	pub depth: u32,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub stack: Option<Vec<U256>>,
+	/// Memory, split in 32 bytes words.
+	#[serde(
+		skip_serializing_if = "Option::is_none",
+		serialize_with = "serialize_words"
+	)]
+	pub memory: Option<Vec<H256>>,
+	#[serde(
+		skip_serializing_if = "Option::is_none",
+		serialize_with = "serialize_storage"
+	)]
+	pub storage: Option<BTreeMap<H256, H256>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub return_data: Option<Bytes>,
+}
+
+/// A call frame of the call tracer.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallFrame {
+	/// Kind of the frame, e.g. `CALL` or `CREATE2`.
+	#[serde(rename = "type")]
+	pub call_type: String,
+	pub from: H160,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub to: Option<H160>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub value: Option<U256>,
+	pub gas: U256,
+	pub gas_used: U256,
+	pub input: Bytes,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub output: Option<Bytes>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub revert_reason: Option<String>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub calls: Vec<CallFrame>,
+}
+
+/// State of an account returned by the prestate tracer.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrestateAccount {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub balance: Option<U256>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub nonce: Option<u64>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub code: Option<Bytes>,
+	#[serde(skip_serializing_if = "BTreeMap::is_empty")]
+	pub storage: BTreeMap<H256, H256>,
+}
+
+/// Output of the prestate tracer in diff mode.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct PrestateDiff {
+	pub pre: BTreeMap<H160, PrestateAccount>,
+	pub post: BTreeMap<H160, PrestateAccount>,
+}
+
+/// Geth serializes memory words and storage slots as hex strings without `0x` prefix.
+fn unprefixed_hex(value: &H256) -> String {
+	value.as_bytes().to_hex()
+}
+
+fn serialize_words<S>(words: &Option<Vec<H256>>, serializer: S) -> Result<S::Ok, S::Error>
+where
+	S: Serializer,
+{
+	let words = words.as_deref().unwrap_or_default();
+	let mut seq = serializer.serialize_seq(Some(words.len()))?;
+	for word in words {
+		seq.serialize_element(&unprefixed_hex(word))?;
+	}
+	seq.end()
+}
+
+fn serialize_storage<S>(
+	storage: &Option<BTreeMap<H256, H256>>,
+	serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+	S: Serializer,
+{
+	let len = storage.as_ref().map(|storage| storage.len());
+	let mut map = serializer.serialize_map(len)?;
+	for (key, value) in storage.iter().flatten() {
+		map.serialize_entry(&unprefixed_hex(key), &unprefixed_hex(value))?;
+	}
+	map.end()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_trace_params_deserialize() {
+		let params: TraceParams =
+			serde_json::from_str(r#"{"tracer":"callTracer","tracerConfig":{"onlyTopCall":true}}"#)
+				.unwrap();
+		assert_eq!(params.tracer.as_deref(), Some("callTracer"));
+		assert_eq!(
+			params.tracer_config,
+			Some(TracerConfig {
+				only_top_call: Some(true),
+				diff_mode: None,
+			})
+		);
+
+		let params: TraceParams =
+			serde_json::from_str(r#"{"enableMemory":true,"disableStack":true,"limit":10}"#)
+				.unwrap();
+		assert_eq!(params.enable_memory, Some(true));
+		assert_eq!(params.disable_stack, Some(true));
+		assert_eq!(params.limit, Some(10));
+	}
+
+	#[test]
+	fn test_trace_call_params_deserialize() {
+		let params: TraceCallParams = serde_json::from_str(
+			r#"{
+				"tracer": "callTracer",
+				"stateOverrides": {"0x0000000000000000000000000000000000000001": {"balance": "0x1"}},
+				"blockOverrides": {"number": "0x10", "time": "0x20"}
+			}"#,
+		)
+		.unwrap();
+		assert_eq!(params.trace.tracer.as_deref(), Some("callTracer"));
+		assert_eq!(
+			params.state_overrides.unwrap()[&H160::from_low_u64_be(1)].balance,
+			Some(U256::one())
+		);
+		let block_overrides = params.block_overrides.unwrap();
+		assert_eq!(block_overrides.number, Some(U256::from(0x10)));
+		assert_eq!(block_overrides.time, Some(U256::from(0x20)));
+	}
+
+	#[test]
+	fn test_struct_log_serialize() {
+		let log = StructLog {
+			pc: 2,
+			op: "SLOAD".into(),
+			gas: 100,
+			gas_cost: 3,
+			depth: 1,
+			stack: Some(vec![U256::from(1)]),
+			memory: Some(vec![H256::repeat_byte(0x11)]),
+			storage: Some(BTreeMap::from([(H256::zero(), H256::repeat_byte(0x22))])),
+			return_data: None,
+		};
+		let serialized = serde_json::to_value(&log).unwrap();
+		assert_eq!(
+			serialized,
+			serde_json::json!({
+				"pc": 2,
+				"op": "SLOAD",
+				"gas": 100,
+				"gasCost": 3,
+				"depth": 1,
+				"stack": ["0x1"],
+				"memory": ["1111111111111111111111111111111111111111111111111111111111111111"],
+				"storage": {
+					"0000000000000000000000000000000000000000000000000000000000000000":
+						"2222222222222222222222222222222222222222222222222222222222222222"
+				},
+			})
+		);
+	}
+}
@@ -102,7 +102,7 @@ mod access_list_item_camelcase {
 mod authorization_list_item_camelcase {
 	use ethereum::{eip2930::MalleableTransactionSignature, AuthorizationListItem};
 	use ethereum_types::{Address, H256};
-	use serde::{Deserialize, Deserializer};
+	use serde::{de::Error, Deserialize, Deserializer};
 
 	#[derive(Deserialize)]
 	#[serde(rename_all = "camelCase")]
@@ -110,34 +110,111 @@ mod authorization_list_item_camelcase {
 		chain_id: u64,
 		address: Address,
 		nonce: ethereum_types::U256,
-		y_parity: bool,
-		r: H256,
-		s: H256,
+		y_parity: Option<bool>,
+		r: Option<H256>,
+		s: Option<H256>,
 	}
 
 	pub fn deserialize<'de, D>(
 		deserializer: D,
 	) -> Result<Option<Vec<AuthorizationListItem>>, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		deserialize_items(deserializer, false)
+	}
+
+	/// Same as [`deserialize`], but the signatures may be left out for the authorizations to be
+	/// signed by the sender.
+	pub fn deserialize_unsigned<'de, D>(
+		deserializer: D,
+	) -> Result<Option<Vec<AuthorizationListItem>>, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		deserialize_items(deserializer, true)
+	}
+
+	fn deserialize_items<'de, D>(
+		deserializer: D,
+		allow_unsigned: bool,
+	) -> Result<Option<Vec<AuthorizationListItem>>, D::Error>
 	where
 		D: Deserializer<'de>,
 	{
 		let auth_item_defs_opt: Option<Vec<AuthorizationListItemDef>> =
 			Option::deserialize(deserializer)?;
-		Ok(auth_item_defs_opt.map(|auth_item_defs| {
-			auth_item_defs
-				.into_iter()
-				.map(|auth_item_def| AuthorizationListItem {
-					chain_id: auth_item_def.chain_id,
-					address: auth_item_def.address,
-					nonce: auth_item_def.nonce,
-					signature: MalleableTransactionSignature {
-						odd_y_parity: auth_item_def.y_parity,
-						r: auth_item_def.r,
-						s: auth_item_def.s,
-					},
-				})
-				.collect()
-		}))
+		auth_item_defs_opt
+			.map(|auth_item_defs| {
+				auth_item_defs
+					.into_iter()
+					.map(|auth_item_def| {
+						let signature =
+							match (auth_item_def.y_parity, auth_item_def.r, auth_item_def.s) {
+								(Some(odd_y_parity), Some(r), Some(s)) => {
+									MalleableTransactionSignature { odd_y_parity, r, s }
+								}
+								(None, None, None) if allow_unsigned => {
+									MalleableTransactionSignature {
+										odd_y_parity: false,
+										r: H256::zero(),
+										s: H256::zero(),
+									}
+								}
+								(None, _, _) => return Err(D::Error::missing_field("yParity")),
+								(_, None, _) => return Err(D::Error::missing_field("r")),
+								(_, _, None) => return Err(D::Error::missing_field("s")),
+							};
+						Ok(AuthorizationListItem {
+							chain_id: auth_item_def.chain_id,
+							address: auth_item_def.address,
+							nonce: auth_item_def.nonce,
+							signature,
+						})
+					})
+					.collect()
+			})
+			.transpose()
+	}
+}
+
+/// Transaction request to be signed by the node, as of `eth_signTransaction` and
+/// `eth_sendTransaction`.
+///
+/// Unlike a plain [`TransactionRequest`], the signatures of the EIP-7702 authorizations may be
+/// left out, in which case the sender signs them along with the transaction.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TransactionSigningRequest(pub TransactionRequest);
+
+impl<'de> Deserialize<'de> for TransactionSigningRequest {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		#[derive(Deserialize)]
+		#[serde(rename_all = "camelCase")]
+		struct Inner {
+			#[serde(
+				deserialize_with = "authorization_list_item_camelcase::deserialize_unsigned",
+				default
+			)]
+			authorization_list: Option<Vec<AuthorizationListItem>>,
+			#[serde(flatten)]
+			request: TransactionRequest,
+		}
+
+		let Inner {
+			authorization_list,
+			mut request,
+		} = Inner::deserialize(deserializer)?;
+		request.authorization_list = authorization_list;
+		Ok(Self(request))
+	}
+}
+
+impl From<TransactionRequest> for TransactionSigningRequest {
+	fn from(request: TransactionRequest) -> Self {
+		Self(request)
 	}
 }
 
@@ -231,6 +308,7 @@ impl<'de> Deserialize<'de> for Data {
 	}
 }
 
+#[derive(Clone)]
 pub enum TransactionMessage {
 	Legacy(LegacyTransactionMessage),
 	EIP2930(EIP2930TransactionMessage),
@@ -446,4 +524,28 @@ mod tests {
 			}
 		);
 	}
+
+	#[test]
+	fn test_deserialize_unsigned_authorization_list() {
+		let data = json!({
+			"from": "0x60be2d1d3665660d22ff9624b7be0551ee1ac91b",
+			"to": "0x13fe2d1d3665660d22ff9624b7be0551ee1ac91b",
+			"input": "0x123abc",
+			"authorizationList": [{
+				"chainId": 42,
+				"address": "0x13fe2d1d3665660d22ff9624b7be0551ee1ac91b",
+				"nonce": "0x1"
+			}]
+		});
+
+		// Only requests signed by the node may leave out the signatures.
+		assert!(serde_json::from_value::<TransactionRequest>(data.clone()).is_err());
+		let TransactionSigningRequest(args) =
+			serde_json::from_value::<TransactionSigningRequest>(data).unwrap();
+		assert_eq!(args.data(), Some(&Bytes::from(vec![0x12, 0x3a, 0xbc])));
+		let authorization_list = args.authorization_list.unwrap();
+		assert_eq!(authorization_list.len(), 1);
+		assert_eq!(authorization_list[0].nonce, 1.into());
+		assert_eq!(authorization_list[0].signature.r, Default::default());
+	}
 }
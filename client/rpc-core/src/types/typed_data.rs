@@ -0,0 +1,445 @@
+// This file is part of Frontier.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! EIP-712 typed structured data.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use ethereum_types::{H160, H256, U256};
+use rustc_hex::FromHex;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use sp_crypto_hashing::keccak_256;
+
+const DOMAIN_TYPE: &str = "EIP712Domain";
+
+/// Typed structured data, as signed by `eth_signTypedData_v4`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypedData {
+	/// Struct types, by name.
+	pub types: BTreeMap<String, Vec<TypedDataField>>,
+	/// Type of the message.
+	pub primary_type: String,
+	/// Domain separator values, typed by `EIP712Domain`.
+	pub domain: Value,
+	/// Message values, typed by the primary type.
+	pub message: Value,
+}
+
+/// A member of a struct type.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct TypedDataField {
+	pub name: String,
+	#[serde(rename = "type")]
+	pub r#type: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TypedDataObject {
+	types: BTreeMap<String, Vec<TypedDataField>>,
+	primary_type: String,
+	domain: Value,
+	#[serde(default)]
+	message: Value,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TypedDataRepr {
+	Object(TypedDataObject),
+	// Several wallets send the typed data as a JSON encoded string.
+	Json(String),
+}
+
+impl<'de> Deserialize<'de> for TypedData {
+	fn deserialize<D>(deserializer: D) -> Result<TypedData, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		let object = match TypedDataRepr::deserialize(deserializer)? {
+			TypedDataRepr::Object(object) => object,
+			TypedDataRepr::Json(json) => {
+				serde_json::from_str(&json).map_err(serde::de::Error::custom)?
+			}
+		};
+		Ok(TypedData {
+			types: object.types,
+			primary_type: object.primary_type,
+			domain: object.domain,
+			message: object.message,
+		})
+	}
+}
+
+impl TypedData {
+	/// The hash to be signed: `keccak256("\x19\x01" ‖ domainSeparator ‖ hashStruct(message))`.
+	pub fn signing_hash(&self) -> Result<H256, String> {
+		let mut preimage = vec![0x19, 0x01];
+		preimage.extend_from_slice(self.domain_separator()?.as_bytes());
+		if self.primary_type != DOMAIN_TYPE {
+			preimage.extend_from_slice(
+				self.hash_struct(&self.primary_type, &self.message)?
+					.as_bytes(),
+			);
+		}
+		Ok(H256(keccak_256(&preimage)))
+	}
+
+	/// The domain separator, `hashStruct(domain)`.
+	pub fn domain_separator(&self) -> Result<H256, String> {
+		if self.types.contains_key(DOMAIN_TYPE) {
+			return self.hash_struct(DOMAIN_TYPE, &self.domain);
+		}
+		// The domain type is commonly left out, infer it from the provided domain values.
+		let mut types = self.types.clone();
+		let fields = [
+			("name", "string"),
+			("version", "string"),
+			("chainId", "uint256"),
+			("verifyingContract", "address"),
+			("salt", "bytes32"),
+		]
+		.into_iter()
+		.filter(|(name, _)| self.domain.get(name).is_some())
+		.map(|(name, ty)| TypedDataField {
+			name: name.into(),
+			r#type: ty.into(),
+		})
+		.collect();
+		types.insert(DOMAIN_TYPE.into(), fields);
+		TypedData {
+			types,
+			..self.clone()
+		}
+		.hash_struct(DOMAIN_TYPE, &self.domain)
+	}
+
+	/// `hashStruct(s) = keccak256(typeHash ‖ encodeData(s))`.
+	pub fn hash_struct(&self, ty: &str, value: &Value) -> Result<H256, String> {
+		Ok(H256(keccak_256(&self.encode_data(ty, value)?)))
+	}
+
+	/// `typeHash = keccak256(encodeType(typeOf(s)))`.
+	pub fn type_hash(&self, ty: &str) -> Result<H256, String> {
+		Ok(H256(keccak_256(self.encode_type(ty)?.as_bytes())))
+	}
+
+	/// The struct type along with its referenced struct types, sorted by name.
+	pub fn encode_type(&self, ty: &str) -> Result<String, String> {
+		let mut dependencies = BTreeSet::new();
+		self.collect_dependencies(ty, &mut dependencies)?;
+		dependencies.remove(ty);
+
+		let mut encoded = String::new();
+		for name in std::iter::once(ty).chain(dependencies.iter().map(String::as_str)) {
+			let fields = self.struct_fields(name)?;
+			let members = fields
+				.iter()
+				.map(|field| format!("{} {}", field.r#type, field.name))
+				.collect::<Vec<_>>()
+				.join(",");
+			encoded.push_str(&format!("{name}({members})"));
+		}
+		Ok(encoded)
+	}
+
+	fn struct_fields(&self, ty: &str) -> Result<&Vec<TypedDataField>, String> {
+		self.types
+			.get(ty)
+			.ok_or_else(|| format!("unknown type {ty}"))
+	}
+
+	fn collect_dependencies(
+		&self,
+		ty: &str,
+		dependencies: &mut BTreeSet<String>,
+	) -> Result<(), String> {
+		let ty = base_type(ty);
+		if dependencies.contains(ty) || !self.types.contains_key(ty) {
+			return Ok(());
+		}
+		dependencies.insert(ty.into());
+		for field in self.struct_fields(ty)? {
+			self.collect_dependencies(&field.r#type, dependencies)?;
+		}
+		Ok(())
+	}
+
+	fn encode_data(&self, ty: &str, value: &Value) -> Result<Vec<u8>, String> {
+		let object = value
+			.as_object()
+			.ok_or_else(|| format!("expected an object for {ty}"))?;
+		let fields = self.struct_fields(ty)?;
+
+		let mut encoded = Vec::with_capacity(32 * (fields.len() + 1));
+		encoded.extend_from_slice(self.type_hash(ty)?.as_bytes());
+		for field in fields {
+			let value = object
+				.get(&field.name)
+				.ok_or_else(|| format!("missing value of {}.{}", ty, field.name))?;
+			encoded.extend_from_slice(self.encode_field(&field.r#type, value)?.as_bytes());
+		}
+		Ok(encoded)
+	}
+
+	fn encode_field(&self, ty: &str, value: &Value) -> Result<H256, String> {
+		if let Some(inner) = ty.strip_suffix(']') {
+			let (item_type, size) = inner
+				.rsplit_once('[')
+				.ok_or_else(|| format!("invalid array type {ty}"))?;
+			let items = value
+				.as_array()
+				.ok_or_else(|| format!("expected an array for {ty}"))?;
+			if !size.is_empty() {
+				let size = size
+					.parse::<usize>()
+					.map_err(|_| format!("invalid array type {ty}"))?;
+				if items.len() != size {
+					return Err(format!("expected {size} items for {ty}"));
+				}
+			}
+			let mut encoded = Vec::with_capacity(32 * items.len());
+			for item in items {
+				encoded.extend_from_slice(self.encode_field(item_type, item)?.as_bytes());
+			}
+			return Ok(H256(keccak_256(&encoded)));
+		}
+
+		if self.types.contains_key(ty) {
+			return self.hash_struct(ty, value);
+		}
+
+		match ty {
+			"string" => {
+				let value = value
+					.as_str()
+					.ok_or_else(|| format!("expected a string for {ty}"))?;
+				Ok(H256(keccak_256(value.as_bytes())))
+			}
+			"bytes" => Ok(H256(keccak_256(&parse_bytes(ty, value)?))),
+			"bool" => match value {
+				Value::Bool(value) => Ok(H256::from_low_u64_be(*value as u64)),
+				_ => Err(format!("expected a boolean for {ty}")),
+			},
+			"address" => {
+				let value = value
+					.as_str()
+					.and_then(|value| value.parse::<H160>().ok())
+					.ok_or_else(|| format!("expected an address for {ty}"))?;
+				Ok(value.into())
+			}
+			_ => {
+				if let Some(size) = ty.strip_prefix("bytes") {
+					let size = parse_size(ty, size, 1, 32)?;
+					let bytes = parse_bytes(ty, value)?;
+					if bytes.len() > size {
+						return Err(format!("value too long for {ty}"));
+					}
+					let mut encoded = H256::zero();
+					encoded.0[..bytes.len()].copy_from_slice(&bytes);
+					Ok(encoded)
+				} else if let Some(bits) = ty.strip_prefix("uint") {
+					let bits = parse_size(ty, bits, 8, 256)?;
+					let (negative, value) = parse_integer(ty, value)?;
+					if negative || value.bits() > bits {
+						return Err(format!("value out of range for {ty}"));
+					}
+					Ok(H256(value.to_big_endian()))
+				} else if let Some(bits) = ty.strip_prefix("int") {
+					let bits = parse_size(ty, bits, 8, 256)?;
+					let (negative, value) = parse_integer(ty, value)?;
+					let limit = U256::one() << (bits - 1);
+					if (negative && value > limit) || (!negative && value >= limit) {
+						return Err(format!("value out of range for {ty}"));
+					}
+					let value = if negative {
+						(!value).overflowing_add(U256::one()).0
+					} else {
+						value
+					};
+					Ok(H256(value.to_big_endian()))
+				} else {
+					Err(format!("unknown type {ty}"))
+				}
+			}
+		}
+	}
+}
+
+/// The type of the items of a (possibly nested) array type.
+fn base_type(ty: &str) -> &str {
+	ty.split('[').next().unwrap_or(ty)
+}
+
+fn parse_size(ty: &str, size: &str, min: usize, max: usize) -> Result<usize, String> {
+	size.parse::<usize>()
+		.ok()
+		.filter(|size| (min..=max).contains(size) && (min == 1 || size % 8 == 0))
+		.ok_or_else(|| format!("unknown type {ty}"))
+}
+
+fn parse_bytes(ty: &str, value: &Value) -> Result<Vec<u8>, String> {
+	value
+		.as_str()
+		.and_then(|value| value.strip_prefix("0x"))
+		.and_then(|value| value.from_hex().ok())
+		.ok_or_else(|| format!("expected hex encoded bytes for {ty}"))
+}
+
+/// Parse an integer given as a JSON number, or as a decimal or `0x` prefixed hex string.
+fn parse_integer(ty: &str, value: &Value) -> Result<(bool, U256), String> {
+	let invalid = || format!("expected an integer for {ty}");
+	match value {
+		Value::Number(number) => {
+			if let Some(value) = number.as_u64() {
+				Ok((false, value.into()))
+			} else if let Some(value) = number.as_i64() {
+				Ok((true, value.unsigned_abs().into()))
+			} else {
+				Err(invalid())
+			}
+		}
+		Value::String(value) => {
+			let (negative, value) = match value.strip_prefix('-') {
+				Some(value) => (true, value),
+				None => (false, value.as_str()),
+			};
+			let value = match value.strip_prefix("0x") {
+				Some(hex) => U256::from_str_radix(hex, 16).map_err(|_| invalid())?,
+				None => U256::from_dec_str(value).map_err(|_| invalid())?,
+			};
+			Ok((negative && !value.is_zero(), value))
+		}
+		_ => Err(invalid()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// Example of the EIP-712 specification.
+	const MAIL: &str = r#"{
+		"types": {
+			"EIP712Domain": [
+				{ "name": "name", "type": "string" },
+				{ "name": "version", "type": "string" },
+				{ "name": "chainId", "type": "uint256" },
+				{ "name": "verifyingContract", "type": "address" }
+			],
+			"Person": [
+				{ "name": "name", "type": "string" },
+				{ "name": "wallet", "type": "address" }
+			],
+			"Mail": [
+				{ "name": "from", "type": "Person" },
+				{ "name": "to", "type": "Person" },
+				{ "name": "contents", "type": "string" }
+			]
+		},
+		"primaryType": "Mail",
+		"domain": {
+			"name": "Ether Mail",
+			"version": "1",
+			"chainId": 1,
+			"verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+		},
+		"message": {
+			"from": { "name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826" },
+			"to": { "name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB" },
+			"contents": "Hello, Bob!"
+		}
+	}"#;
+
+	fn hash(value: &str) -> H256 {
+		value.parse().unwrap()
+	}
+
+	#[test]
+	fn hashes_specification_example() {
+		let typed_data: TypedData = serde_json::from_str(MAIL).unwrap();
+		assert_eq!(
+			typed_data.encode_type("Mail").unwrap(),
+			"Mail(Person from,Person to,string contents)Person(string name,address wallet)"
+		);
+		assert_eq!(
+			typed_data.domain_separator().unwrap(),
+			hash("0xf2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f")
+		);
+		assert_eq!(
+			typed_data.hash_struct("Mail", &typed_data.message).unwrap(),
+			hash("0xc52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e")
+		);
+		assert_eq!(
+			typed_data.signing_hash().unwrap(),
+			hash("0xbe609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2")
+		);
+	}
+
+	#[test]
+	fn deserializes_json_string_and_infers_domain_type() {
+		let mut object: Value = serde_json::from_str(MAIL).unwrap();
+		object["types"].as_object_mut().unwrap().remove(DOMAIN_TYPE);
+		let typed_data: TypedData =
+			serde_json::from_value(Value::String(object.to_string())).unwrap();
+		assert_eq!(
+			typed_data.signing_hash().unwrap(),
+			hash("0xbe609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2")
+		);
+	}
+
+	#[test]
+	fn encodes_arrays_and_signed_integers() {
+		let typed_data: TypedData = serde_json::from_value(serde_json::json!({
+			"types": {
+				"Values": [
+					{ "name": "items", "type": "int8[2]" },
+					{ "name": "flags", "type": "bool[]" }
+				]
+			},
+			"primaryType": "Values",
+			"domain": {},
+			"message": { "items": [-1, "0x7f"], "flags": [true] }
+		}))
+		.unwrap();
+
+		let mut items = vec![0xff; 32];
+		items.extend_from_slice(H256::from_low_u64_be(0x7f).as_bytes());
+		let flags = H256::from_low_u64_be(1);
+		assert_eq!(
+			typed_data
+				.encode_field("int8[2]", &typed_data.message["items"])
+				.unwrap(),
+			H256(keccak_256(&items))
+		);
+		assert_eq!(
+			typed_data
+				.encode_field("bool[]", &typed_data.message["flags"])
+				.unwrap(),
+			H256(keccak_256(flags.as_bytes()))
+		);
+		assert!(typed_data
+			.encode_field("int8", &serde_json::json!(128))
+			.is_err());
+		assert!(typed_data
+			.encode_field("int8[2]", &serde_json::json!([1]))
+			.is_err());
+	}
+}
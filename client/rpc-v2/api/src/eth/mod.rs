@@ -25,7 +25,7 @@ use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 pub use self::pubsub::*;
 use crate::types::{
 	access_list::AccessListResult,
-	block::Block,
+	block::{Block, BlockOverrides},
 	block_id::{BlockNumberOrTag, BlockNumberOrTagOrHash},
 	bytes::Bytes,
 	fee::FeeHistoryResult,
@@ -175,7 +175,7 @@ pub trait EthExecuteApi {
 		request: TransactionRequest,
 		number_or_hash: Option<BlockNumberOrTagOrHash>,
 		state_overrides: Option<StateOverrides>,
-		// block_overrides: Option<BlockOverrides>,
+		block_overrides: Option<BlockOverrides>,
 	) -> RpcResult<Bytes>;
 
 	/// Generates and returns an estimate of hou much gas is necessary to allow the transaction to complete.
@@ -308,7 +308,7 @@ pub trait EthStateApi {
 	async fn proof(
 		&self,
 		address: Address,
-		storage_keys: H256,
+		storage_keys: Vec<H256>,
 		block: Option<BlockNumberOrTagOrHash>,
 	) -> RpcResult<AccountProof>;
 }
@@ -318,11 +318,11 @@ pub trait EthStateApi {
 #[async_trait]
 pub trait EthSubmitApi {
 	/// Signs and submits a transaction; will block waiting for signer to return the transaction hash.
-	#[method(name = "eth_sendTransaction")]
+	#[method(name = "sendTransaction")]
 	async fn send_transaction(&self, request: TransactionRequest) -> RpcResult<H256>;
 
 	/// Submits a raw signed transaction, returning its hash.
-	#[method(name = "eth_sendRawTransaction")]
+	#[method(name = "sendRawTransaction")]
 	async fn send_raw_transaction(&self, bytes: Bytes) -> RpcResult<H256>;
 }
 
@@ -0,0 +1,568 @@
+// This file is part of Frontier.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Conversions between the `fc-rpc-core` (v1) and `fc-rpc-v2-types` (v2) RPC types.
+//!
+//! Requests are converted from v2 into the v1 types understood by the `fc-rpc` servers, and
+//! the responses are converted back into the v2 types.
+
+use std::collections::BTreeMap;
+
+use ethereum_types::{U256, U64};
+use jsonrpsee::core::RpcResult;
+
+use fc_rpc_core::types as v1;
+use fc_rpc_v2_api::types as v2;
+
+use crate::internal_err;
+
+// ############################################################################
+// Requests (v2 -> v1)
+// ############################################################################
+
+pub fn block_number_or_tag(number: v2::BlockNumberOrTag) -> v1::BlockNumberOrHash {
+	match number {
+		v2::BlockNumberOrTag::Latest => v1::BlockNumberOrHash::Latest,
+		v2::BlockNumberOrTag::Finalized => v1::BlockNumberOrHash::Finalized,
+		v2::BlockNumberOrTag::Safe => v1::BlockNumberOrHash::Safe,
+		v2::BlockNumberOrTag::Earliest => v1::BlockNumberOrHash::Earliest,
+		v2::BlockNumberOrTag::Pending => v1::BlockNumberOrHash::Pending,
+		v2::BlockNumberOrTag::Number(number) => v1::BlockNumberOrHash::Num(number),
+	}
+}
+
+pub fn block_number_or_tag_or_hash(
+	number_or_hash: v2::BlockNumberOrTagOrHash,
+) -> v1::BlockNumberOrHash {
+	match number_or_hash {
+		v2::BlockNumberOrTagOrHash::Number(number) => block_number_or_tag(number),
+		v2::BlockNumberOrTagOrHash::Hash(hash) => v1::BlockNumberOrHash::Hash {
+			hash: hash.block_hash(),
+			require_canonical: hash.require_canonical().unwrap_or(false),
+		},
+	}
+}
+
+pub fn bytes(bytes: v2::Bytes) -> v1::Bytes {
+	v1::Bytes(bytes.into_vec())
+}
+
+pub fn index(index: v2::Index) -> v1::Index {
+	v1::Index::from(usize::from(index))
+}
+
+pub fn transaction_request(request: v2::TransactionRequest) -> v1::TransactionRequest {
+	let mut converted = v1::TransactionRequest {
+		from: request.from,
+		to: request.to,
+		value: request.value,
+		nonce: request.nonce.map(|nonce| U256::from(nonce.as_u64())),
+		gas: request.gas.map(|gas| U256::from(gas.as_u128())),
+		gas_price: request.gas_price.map(|price| U256::from(price.as_u128())),
+		max_fee_per_gas: request.max_fee_per_gas.map(|fee| U256::from(fee.as_u128())),
+		max_priority_fee_per_gas: request
+			.max_priority_fee_per_gas
+			.map(|fee| U256::from(fee.as_u128())),
+		access_list: request.access_list.map(|access_list| {
+			access_list
+				.into_iter()
+				.map(|item| ethereum::AccessListItem {
+					address: item.address,
+					storage_keys: item.storage_keys,
+				})
+				.collect()
+		}),
+		chain_id: request.chain_id,
+		transaction_type: request.tx_type.map(|tx_type| U256::from(tx_type as u8)),
+		..Default::default()
+	};
+	converted.data.input = request.input.input.map(bytes);
+	converted.data.data = request.input.data.map(bytes);
+	converted
+}
+
+pub fn state_overrides(
+	overrides: v2::StateOverrides,
+) -> BTreeMap<ethereum_types::Address, v1::CallStateOverride> {
+	overrides
+		.into_iter()
+		.map(|(address, account)| {
+			let account = v1::CallStateOverride {
+				balance: account.balance,
+				nonce: account.nonce.map(|nonce| U256::from(nonce.as_u64())),
+				code: account.code.map(bytes),
+				state: account.state.map(|state| state.into_iter().collect()),
+				state_diff: account.state_diff.map(|diff| diff.into_iter().collect()),
+			};
+			(address, account)
+		})
+		.collect()
+}
+
+pub fn block_overrides(overrides: v2::BlockOverrides) -> v1::BlockOverrides {
+	// The difficulty is not overridable: it is always zero in Frontier.
+	v1::BlockOverrides {
+		number: overrides.number,
+		time: overrides.time.map(|time| U256::from(time.as_u64())),
+		gas_limit: overrides
+			.gas_limit
+			.map(|gas_limit| U256::from(gas_limit.as_u64())),
+		coinbase: overrides.coinbase,
+		random: overrides.random,
+		base_fee: overrides.base_fee,
+	}
+}
+
+pub fn filter(filter: v2::Filter) -> v1::Filter {
+	let (from_block, to_block, block_hash) = match filter.block_option {
+		v2::FilterBlockOption::BlockNumberRange {
+			from_block,
+			to_block,
+		} => (
+			from_block.map(block_number_or_tag),
+			to_block.map(block_number_or_tag),
+			None,
+		),
+		v2::FilterBlockOption::BlockHashAt { block_hash } => (None, None, Some(block_hash)),
+	};
+
+	let topics = filter.topics.map(|topics| {
+		let mut topics = topics
+			.iter()
+			.map(|topic| variadic_value(topic.to_value_or_array()))
+			.collect::<Vec<_>>();
+		// Trailing wildcards are redundant.
+		while topics.last() == Some(&v1::VariadicValue::Null) {
+			topics.pop();
+		}
+		v1::Topics::truncate_from(topics)
+	});
+
+	v1::Filter {
+		from_block,
+		to_block,
+		block_hash,
+		address: filter
+			.address
+			.and_then(|address| address.to_value_or_array())
+			.map(|address| variadic_value(Some(address))),
+		topics,
+	}
+}
+
+fn variadic_value<T>(value: Option<v2::ValueOrArray<T>>) -> v1::VariadicValue<T>
+where
+	T: serde::de::DeserializeOwned,
+{
+	match value {
+		None => v1::VariadicValue::Null,
+		Some(v2::ValueOrArray::Value(value)) => v1::VariadicValue::Single(value),
+		Some(v2::ValueOrArray::Array(values)) => v1::VariadicValue::Multiple(values),
+	}
+}
+
+pub fn pubsub_kind(kind: v2::PubSubKind) -> v1::pubsub::Kind {
+	match kind {
+		v2::PubSubKind::NewHeads => v1::pubsub::Kind::NewHeads,
+		v2::PubSubKind::Logs => v1::pubsub::Kind::Logs,
+		v2::PubSubKind::NewPendingTransactions => v1::pubsub::Kind::NewPendingTransactions,
+	}
+}
+
+pub fn pubsub_params(params: v2::PubSubParams) -> RpcResult<v1::pubsub::Params> {
+	match params {
+		v2::PubSubParams::None | v2::PubSubParams::Bool(false) => Ok(v1::pubsub::Params::None),
+		v2::PubSubParams::Logs(logs) => Ok(v1::pubsub::Params::Logs(filter(*logs))),
+		v2::PubSubParams::Bool(true) => Ok(v1::pubsub::Params::PendingTransactions(
+			v1::pubsub::PendingTransactionsParams {
+				full_transactions: true,
+				..Default::default()
+			},
+		)),
+	}
+}
+
+// ############################################################################
+// Responses (v1 -> v2)
+// ############################################################################
+
+pub fn bytes_v2(bytes: v1::Bytes) -> v2::Bytes {
+	v2::Bytes::new(bytes.into_vec())
+}
+
+pub fn block(block: v1::RichBlock) -> v2::Block {
+	let v1::Block {
+		header,
+		total_difficulty,
+		uncles,
+		transactions,
+		size,
+		base_fee_per_gas,
+	} = block.inner;
+
+	let mut header = self::header(header);
+	header.total_difficulty = total_difficulty;
+	header.base_fee_per_gas = base_fee_per_gas;
+
+	v2::Block {
+		header,
+		transactions: match transactions {
+			v1::BlockTransactions::Hashes(hashes) => v2::BlockTransactions::Hashes(hashes),
+			v1::BlockTransactions::Full(transactions) => {
+				v2::BlockTransactions::Full(transactions.into_iter().map(transaction).collect())
+			}
+		},
+		uncles,
+		size,
+		withdrawals: None,
+	}
+}
+
+pub fn header(header: v1::Header) -> v2::Header {
+	v2::Header {
+		// The number is only unknown for the pending block, which is the next one.
+		number: header.number.unwrap_or_default(),
+		hash: header.hash,
+		parent_hash: header.parent_hash,
+		uncles_hash: header.uncles_hash,
+		nonce: header
+			.nonce
+			.map(|nonce| U64::from_big_endian(nonce.as_bytes())),
+		author: header.author,
+		state_root: header.state_root,
+		transactions_root: header.transactions_root,
+		receipts_root: header.receipts_root,
+		logs_bloom: header.logs_bloom,
+		gas_limit: header.gas_limit,
+		gas_used: header.gas_used,
+		timestamp: U64::from(header.timestamp.low_u64()),
+		extra_data: bytes_v2(header.extra_data),
+		difficulty: header.difficulty,
+		total_difficulty: None,
+		mix_hash: None,
+		base_fee_per_gas: None,
+		withdrawals_root: None,
+		parent_beacon_block_root: None,
+	}
+}
+
+fn tx_type(tx_type: U256) -> v2::TxType {
+	v2::TxType::try_from(tx_type.low_u32() as u8).unwrap_or_default()
+}
+
+pub fn transaction(transaction: v1::Transaction) -> v2::Transaction {
+	v2::Transaction {
+		tx_type: tx_type(transaction.transaction_type),
+		hash: transaction.hash,
+		nonce: U64::from(transaction.nonce.low_u64()),
+		block_hash: transaction.block_hash,
+		block_number: transaction.block_number,
+		transaction_index: transaction.transaction_index,
+		from: transaction.from,
+		to: transaction.to,
+		value: transaction.value,
+		input: bytes_v2(transaction.input),
+		gas: U64::from(transaction.gas.low_u64()),
+		gas_price: transaction.gas_price,
+		max_fee_per_gas: transaction.max_fee_per_gas,
+		max_priority_fee_per_gas: transaction.max_priority_fee_per_gas,
+		chain_id: transaction.chain_id,
+		signature: v2::TransactionSignature {
+			r: transaction.r,
+			s: transaction.s,
+			v: transaction.v.unwrap_or_default(),
+			y_parity: transaction
+				.y_parity
+				.map(|y_parity| v2::Parity(!y_parity.is_zero())),
+		},
+		access_list: transaction.access_list.map(|access_list| {
+			access_list
+				.into_iter()
+				.map(|item| v2::AccessListItem {
+					address: item.address,
+					storage_keys: item.storage_keys,
+				})
+				.collect()
+		}),
+	}
+}
+
+pub fn receipt(receipt: v1::Receipt) -> v2::TransactionReceipt {
+	v2::TransactionReceipt {
+		block_hash: receipt.block_hash,
+		block_number: receipt
+			.block_number
+			.map(|number| U64::from(number.low_u64())),
+		transaction_hash: receipt.transaction_hash.unwrap_or_default(),
+		transaction_index: receipt
+			.transaction_index
+			.map(|index| U64::from(index.low_u64()))
+			.unwrap_or_default(),
+		tx_type: tx_type(receipt.transaction_type),
+		gas_used: receipt
+			.gas_used
+			.map(|gas_used| U64::from(gas_used.low_u64()))
+			.unwrap_or_default(),
+		from: receipt.from.unwrap_or_default(),
+		to: receipt.to,
+		contract_address: receipt.contract_address,
+		effective_gas_price: receipt.effective_gas_price,
+		status: receipt.status_code.unwrap_or_default(),
+		cumulative_gas_used: U64::from(receipt.cumulative_gas_used.low_u64()),
+		logs: receipt.logs.into_iter().map(log).collect(),
+		logs_bloom: receipt.logs_bloom,
+		state_root: receipt.state_root,
+	}
+}
+
+pub fn log(log: v1::Log) -> v2::Log {
+	v2::Log {
+		address: log.address,
+		topics: log.topics,
+		data: bytes_v2(log.data),
+		block_hash: log.block_hash,
+		block_number: log.block_number,
+		transaction_hash: log.transaction_hash,
+		transaction_index: log.transaction_index,
+		log_index: log.log_index,
+		removed: log.removed,
+	}
+}
+
+pub fn filter_changes(changes: v1::FilterChanges) -> v2::FilterChanges {
+	match changes {
+		v1::FilterChanges::Logs(logs) => {
+			v2::FilterChanges::Logs(logs.into_iter().map(log).collect())
+		}
+		v1::FilterChanges::Hashes(hashes) => v2::FilterChanges::Hashes(hashes),
+		v1::FilterChanges::Empty => v2::FilterChanges::Empty,
+	}
+}
+
+pub fn fee_history(fee_history: v1::FeeHistory) -> v2::FeeHistoryResult {
+	v2::FeeHistoryResult {
+		oldest_block: fee_history.oldest_block,
+		base_fee_per_gas: fee_history.base_fee_per_gas,
+		gas_used_ratio: fee_history.gas_used_ratio,
+		reward: fee_history.reward.unwrap_or_default(),
+	}
+}
+
+pub fn syncing_status(status: v1::SyncStatus) -> v2::SyncingStatus {
+	match status {
+		v1::SyncStatus::Info(info) => v2::SyncingStatus::IsSyncing(v2::SyncingProgress {
+			starting_block: U64::from(info.starting_block.low_u64()),
+			current_block: U64::from(info.current_block.low_u64()),
+			highest_block: U64::from(info.highest_block.low_u64()),
+		}),
+		v1::SyncStatus::None => v2::SyncingStatus::NotSyncing,
+	}
+}
+
+pub fn account_proof(account: v1::EthAccount) -> v2::AccountProof {
+	v2::AccountProof {
+		address: account.address,
+		account_proof: account.account_proof.into_iter().map(bytes_v2).collect(),
+		balance: account.balance,
+		code_hash: account.code_hash,
+		nonce: U64::from(account.nonce.low_u64()),
+		storage_hash: account.storage_hash,
+		storage_proof: account
+			.storage_proof
+			.into_iter()
+			.map(|proof| v2::StorageProof {
+				key: proof.key,
+				value: proof.value,
+				proof: proof.proof.into_iter().map(bytes_v2).collect(),
+			})
+			.collect(),
+	}
+}
+
+pub fn access_list_result(result: v1::AccessListResult) -> v2::AccessListResult {
+	v2::AccessListResult {
+		access_list: result
+			.access_list
+			.into_iter()
+			.map(|item| v2::AccessListItem {
+				address: item.address,
+				storage_keys: item.storage_keys,
+			})
+			.collect(),
+		error: result.error,
+		gas_used: result.gas_used,
+	}
+}
+
+pub fn peer_count(peer_count: v1::PeerCount) -> RpcResult<U64> {
+	match peer_count {
+		v1::PeerCount::U32(count) => Ok(U64::from(count)),
+		v1::PeerCount::String(count) => match count.strip_prefix("0x") {
+			Some(hex) => u64::from_str_radix(hex, 16),
+			None => count.parse::<u64>(),
+		}
+		.map(U64::from)
+		.map_err(|err| internal_err(format!("invalid peer count: {err}"))),
+	}
+}
+
+#[cfg(feature = "txpool")]
+pub fn txpool_map<T, R>(
+	map: v1::TransactionMap<T>,
+	f: impl Fn(T) -> R,
+) -> v2::AddressMapping<v2::NonceMapping<R>> {
+	map.into_iter()
+		.map(|(address, nonces)| {
+			let nonces = nonces
+				.into_iter()
+				.map(|(nonce, item)| (nonce.low_u64(), f(item)))
+				.collect();
+			(address, nonces)
+		})
+		.collect()
+}
+
+#[cfg(feature = "txpool")]
+pub fn txpool_summary(summary: v1::Summary) -> v2::Summary {
+	v2::Summary {
+		to: summary.to,
+		value: summary.value,
+		gas: summary.gas.low_u128(),
+		gas_price: summary.gas_price.low_u128(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use ethereum_types::{H160, H256, H64, U128};
+
+	use super::*;
+
+	#[test]
+	fn block_number_or_tag_or_hash_conversion() {
+		let hash = H256::repeat_byte(0x11);
+		assert_eq!(
+			block_number_or_tag_or_hash(v2::BlockHash::from(hash).into()),
+			v1::BlockNumberOrHash::Hash {
+				hash,
+				require_canonical: false,
+			}
+		);
+		assert_eq!(
+			block_number_or_tag_or_hash(v2::BlockNumberOrTag::Number(7).into()),
+			v1::BlockNumberOrHash::Num(7)
+		);
+		assert_eq!(
+			block_number_or_tag(v2::BlockNumberOrTag::Pending),
+			v1::BlockNumberOrHash::Pending
+		);
+	}
+
+	#[test]
+	fn transaction_request_conversion() {
+		let request = v2::TransactionRequest {
+			tx_type: Some(v2::TxType::EIP1559),
+			from: Some(H160::repeat_byte(0x01)),
+			to: Some(H160::repeat_byte(0x02)),
+			nonce: Some(U64::from(3)),
+			gas: Some(U128::from(21_000)),
+			max_fee_per_gas: Some(U128::from(100)),
+			input: v2::TransactionInput {
+				input: Some(v2::Bytes::new(vec![0xde, 0xad])),
+				data: None,
+			},
+			..Default::default()
+		};
+
+		let converted = transaction_request(request);
+		assert_eq!(converted.transaction_type, Some(U256::from(2)));
+		assert_eq!(converted.nonce, Some(U256::from(3)));
+		assert_eq!(converted.gas, Some(U256::from(21_000)));
+		assert_eq!(converted.max_fee_per_gas, Some(U256::from(100)));
+		assert_eq!(converted.data.input, Some(v1::Bytes(vec![0xde, 0xad])));
+		assert_eq!(converted.data.data, None);
+	}
+
+	#[test]
+	fn filter_conversion_trims_trailing_wildcards() {
+		let topic = H256::repeat_byte(0x22);
+		let filter = v2::Filter::new()
+			.from_block(1u64)
+			.to_block(v2::BlockNumberOrTag::Latest)
+			.address(vec![H160::repeat_byte(0x01), H160::repeat_byte(0x02)])
+			.topic1(topic);
+
+		let converted = self::filter(filter);
+		assert_eq!(converted.from_block, Some(v1::BlockNumberOrHash::Num(1)));
+		assert_eq!(converted.to_block, Some(v1::BlockNumberOrHash::Latest));
+		assert_eq!(converted.block_hash, None);
+		assert_eq!(
+			converted.address,
+			Some(v1::VariadicValue::Multiple(vec![
+				H160::repeat_byte(0x01),
+				H160::repeat_byte(0x02)
+			]))
+		);
+		assert_eq!(
+			converted.topics.map(|topics| topics.into_inner()),
+			Some(vec![
+				v1::VariadicValue::Null,
+				v1::VariadicValue::Single(topic)
+			])
+		);
+	}
+
+	#[test]
+	fn header_conversion() {
+		let header = v1::Header {
+			hash: None,
+			parent_hash: H256::repeat_byte(0x01),
+			uncles_hash: H256::repeat_byte(0x02),
+			author: H160::repeat_byte(0x03),
+			miner: None,
+			state_root: H256::zero(),
+			transactions_root: H256::zero(),
+			receipts_root: H256::zero(),
+			number: None,
+			gas_used: U256::from(21_000),
+			gas_limit: U256::from(30_000_000),
+			extra_data: v1::Bytes(vec![]),
+			logs_bloom: Default::default(),
+			timestamp: U256::from(1_700_000_000u64),
+			difficulty: U256::zero(),
+			nonce: Some(H64::from_low_u64_be(42)),
+			size: None,
+		};
+
+		let converted = self::header(header);
+		assert_eq!(converted.author, H160::repeat_byte(0x03));
+		assert_eq!(converted.number, U256::zero());
+		assert_eq!(converted.nonce, Some(U64::from(42)));
+		assert_eq!(converted.timestamp, U64::from(1_700_000_000u64));
+	}
+
+	#[test]
+	fn peer_count_conversion() {
+		assert_eq!(peer_count(v1::PeerCount::U32(5)).unwrap(), U64::from(5));
+		assert_eq!(
+			peer_count(v1::PeerCount::String("0x10".into())).unwrap(),
+			U64::from(16)
+		);
+		assert!(peer_count(v1::PeerCount::String("many".into())).is_err());
+	}
+}
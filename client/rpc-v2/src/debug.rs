@@ -0,0 +1,74 @@
+// This file is part of Frontier.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::sync::Arc;
+
+use ethereum_types::H256;
+use jsonrpsee::core::{async_trait, RpcResult};
+// Frontier
+use fc_rpc_core::{types::BlockNumberOrHash, DebugApiServer as DebugApiV1Server};
+use fc_rpc_v2_api::{
+	debug::DebugApiServer,
+	types::{BlockNumberOrTagOrHash, Bytes},
+};
+
+use crate::convert;
+
+/// Debug API implementation, backed by an `fc-rpc` debug API implementation
+/// (e.g. `fc_rpc::Debug`).
+pub struct Debug<D> {
+	debug: Arc<D>,
+}
+
+impl<D> Debug<D> {
+	pub fn new(debug: D) -> Self {
+		Self {
+			debug: Arc::new(debug),
+		}
+	}
+}
+
+#[async_trait]
+impl<D: DebugApiV1Server> DebugApiServer for Debug<D> {
+	async fn raw_header(&self, block: BlockNumberOrTagOrHash) -> RpcResult<Option<Bytes>> {
+		let number_or_hash = convert::block_number_or_tag_or_hash(block);
+		let header = DebugApiV1Server::raw_header(&*self.debug, number_or_hash).await?;
+		Ok(header.map(convert::bytes_v2))
+	}
+
+	async fn raw_block(&self, block: BlockNumberOrTagOrHash) -> RpcResult<Option<Bytes>> {
+		let number_or_hash = convert::block_number_or_tag_or_hash(block);
+		let block = DebugApiV1Server::raw_block(&*self.debug, number_or_hash).await?;
+		Ok(block.map(convert::bytes_v2))
+	}
+
+	async fn raw_transaction(&self, transaction_hash: H256) -> RpcResult<Option<Bytes>> {
+		let transaction = DebugApiV1Server::raw_transaction(&*self.debug, transaction_hash).await?;
+		Ok(transaction.map(convert::bytes_v2))
+	}
+
+	async fn raw_receipts(&self, block: BlockNumberOrTagOrHash) -> RpcResult<Vec<Bytes>> {
+		let number_or_hash = convert::block_number_or_tag_or_hash(block);
+		let receipts = DebugApiV1Server::raw_receipts(&*self.debug, number_or_hash).await?;
+		Ok(receipts.into_iter().map(convert::bytes_v2).collect())
+	}
+
+	async fn bad_blocks(&self) -> RpcResult<Vec<()>> {
+		DebugApiV1Server::bad_blocks(&*self.debug, BlockNumberOrHash::Latest)
+	}
+}
@@ -0,0 +1,93 @@
+// This file is part of Frontier.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::sync::Arc;
+
+use ethereum_types::U256;
+use jsonrpsee::core::{async_trait, RpcResult};
+// Frontier
+use fc_rpc_core::EthFilterApiServer as EthFilterApiV1Server;
+use fc_rpc_v2_api::{
+	eth::EthFilterApiServer,
+	types::{Filter, FilterChanges, Index, Log},
+};
+
+use crate::{convert, internal_err};
+
+/// Eth filter API implementation, backed by an `fc-rpc` filter API implementation
+/// (e.g. `fc_rpc::EthFilter`).
+pub struct EthFilter<F> {
+	filter: Arc<F>,
+}
+
+impl<F> Clone for EthFilter<F> {
+	fn clone(&self) -> Self {
+		Self {
+			filter: self.filter.clone(),
+		}
+	}
+}
+
+impl<F> EthFilter<F> {
+	pub fn new(filter: F) -> Self {
+		Self {
+			filter: Arc::new(filter),
+		}
+	}
+}
+
+#[async_trait]
+impl<F: EthFilterApiV1Server> EthFilterApiServer for EthFilter<F> {
+	async fn new_filter(&self, filter: Filter) -> RpcResult<U256> {
+		EthFilterApiV1Server::new_filter(&*self.filter, convert::filter(filter))
+	}
+
+	async fn new_block_filter(&self) -> RpcResult<U256> {
+		EthFilterApiV1Server::new_block_filter(&*self.filter)
+	}
+
+	async fn new_pending_transaction_filter(&self, full: Option<bool>) -> RpcResult<U256> {
+		if full.unwrap_or(false) {
+			return Err(internal_err(
+				"full pending transactions filter is not supported",
+			));
+		}
+		EthFilterApiV1Server::new_pending_transaction_filter(&*self.filter)
+	}
+
+	async fn uninstall_filter(&self, filter_id: Index) -> RpcResult<bool> {
+		EthFilterApiV1Server::uninstall_filter(&*self.filter, convert::index(filter_id))
+	}
+
+	async fn filter_changes(&self, filter_id: Index) -> RpcResult<FilterChanges> {
+		let changes =
+			EthFilterApiV1Server::filter_changes(&*self.filter, convert::index(filter_id)).await?;
+		Ok(convert::filter_changes(changes))
+	}
+
+	async fn filter_logs(&self, filter_id: Index) -> RpcResult<Vec<Log>> {
+		let logs =
+			EthFilterApiV1Server::filter_logs(&*self.filter, convert::index(filter_id)).await?;
+		Ok(logs.into_iter().map(convert::log).collect())
+	}
+
+	async fn logs(&self, filter: Filter) -> RpcResult<Vec<Log>> {
+		let logs = EthFilterApiV1Server::logs(&*self.filter, convert::filter(filter)).await?;
+		Ok(logs.into_iter().map(convert::log).collect())
+	}
+}
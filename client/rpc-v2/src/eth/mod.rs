@@ -0,0 +1,349 @@
+// This file is part of Frontier.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+mod filter;
+mod pubsub;
+
+use std::sync::Arc;
+
+use ethereum_types::{Address, H256, U256, U64};
+use jsonrpsee::{
+	core::{async_trait, RegisterMethodError, RpcResult},
+	RpcModule,
+};
+// Frontier
+use fc_rpc_core::EthApiServer as EthApiV1Server;
+use fc_rpc_v2_api::{eth::*, types::*};
+
+pub use self::{filter::EthFilter, pubsub::EthPubSub};
+use crate::{convert, internal_err};
+
+/// Eth API implementation, backed by an `fc-rpc` Eth API implementation (e.g. `fc_rpc::Eth`).
+pub struct Eth<E> {
+	eth: Arc<E>,
+}
+
+impl<E> Clone for Eth<E> {
+	fn clone(&self) -> Self {
+		Self {
+			eth: self.eth.clone(),
+		}
+	}
+}
+
+impl<E> Eth<E> {
+	pub fn new(eth: E) -> Self {
+		Self { eth: Arc::new(eth) }
+	}
+}
+
+impl<E: EthApiV1Server> Eth<E> {
+	/// Merges all the Eth API namespaces served by this implementation into one module.
+	///
+	/// The filter and pub-sub APIs are served by [`EthFilter`] and [`EthPubSub`].
+	pub fn into_rpc_module(self) -> Result<RpcModule<()>, RegisterMethodError> {
+		let mut module = RpcModule::new(());
+		module.merge(EthBlockApiServer::into_rpc(self.clone()))?;
+		module.merge(EthClientApiServer::into_rpc(self.clone()))?;
+		module.merge(EthExecuteApiServer::into_rpc(self.clone()))?;
+		module.merge(EthFeeMarketApiServer::into_rpc(self.clone()))?;
+		module.merge(EthSignApiServer::into_rpc(self.clone()))?;
+		module.merge(EthStateApiServer::into_rpc(self.clone()))?;
+		module.merge(EthSubmitApiServer::into_rpc(self.clone()))?;
+		module.merge(EthTransactionApiServer::into_rpc(self))?;
+		Ok(module)
+	}
+}
+
+#[async_trait]
+impl<E: EthApiV1Server> EthBlockApiServer for Eth<E> {
+	async fn block_by_hash(&self, hash: H256, full: bool) -> RpcResult<Option<Block>> {
+		let block = EthApiV1Server::block_by_hash(&*self.eth, hash, full).await?;
+		Ok(block.map(convert::block))
+	}
+
+	async fn block_by_number(
+		&self,
+		block: BlockNumberOrTag,
+		full: bool,
+	) -> RpcResult<Option<Block>> {
+		let number = convert::block_number_or_tag(block);
+		let block = EthApiV1Server::block_by_number(&*self.eth, number, full).await?;
+		Ok(block.map(convert::block))
+	}
+
+	async fn block_transaction_count_by_hash(&self, block_hash: H256) -> RpcResult<Option<U256>> {
+		EthApiV1Server::block_transaction_count_by_hash(&*self.eth, block_hash).await
+	}
+
+	async fn block_transaction_count_by_number(
+		&self,
+		block: BlockNumberOrTag,
+	) -> RpcResult<Option<U256>> {
+		let number = convert::block_number_or_tag(block);
+		EthApiV1Server::block_transaction_count_by_number(&*self.eth, number).await
+	}
+
+	async fn block_uncles_count_by_hash(&self, block_hash: H256) -> RpcResult<U256> {
+		EthApiV1Server::block_uncles_count_by_hash(&*self.eth, block_hash)
+	}
+
+	async fn block_uncles_count_by_number(&self, block: BlockNumberOrTag) -> RpcResult<U256> {
+		let number = convert::block_number_or_tag(block);
+		EthApiV1Server::block_uncles_count_by_number(&*self.eth, number)
+	}
+
+	async fn block_transaction_receipts(
+		&self,
+		number_or_hash: BlockNumberOrTagOrHash,
+	) -> RpcResult<Option<Vec<TransactionReceipt>>> {
+		let number_or_hash = convert::block_number_or_tag_or_hash(number_or_hash);
+		let receipts =
+			EthApiV1Server::block_transaction_receipts(&*self.eth, number_or_hash).await?;
+		Ok(receipts.map(|receipts| receipts.into_iter().map(convert::receipt).collect()))
+	}
+}
+
+#[async_trait]
+impl<E: EthApiV1Server> EthClientApiServer for Eth<E> {
+	async fn chain_id(&self) -> RpcResult<U64> {
+		EthApiV1Server::chain_id(&*self.eth)?.ok_or_else(|| internal_err("chain id not available"))
+	}
+
+	async fn syncing(&self) -> RpcResult<SyncingStatus> {
+		let status = EthApiV1Server::syncing(&*self.eth).await?;
+		Ok(convert::syncing_status(status))
+	}
+
+	async fn author(&self) -> RpcResult<Address> {
+		EthApiV1Server::author(&*self.eth)
+	}
+
+	async fn accounts(&self) -> RpcResult<Vec<Address>> {
+		EthApiV1Server::accounts(&*self.eth)
+	}
+
+	async fn block_number(&self) -> RpcResult<U64> {
+		let number = EthApiV1Server::block_number(&*self.eth)?;
+		Ok(U64::from(number.low_u64()))
+	}
+}
+
+#[async_trait]
+impl<E: EthApiV1Server> EthExecuteApiServer for Eth<E> {
+	async fn call(
+		&self,
+		request: TransactionRequest,
+		number_or_hash: Option<BlockNumberOrTagOrHash>,
+		state_overrides: Option<StateOverrides>,
+		block_overrides: Option<BlockOverrides>,
+	) -> RpcResult<Bytes> {
+		let bytes = EthApiV1Server::call(
+			&*self.eth,
+			convert::transaction_request(request),
+			number_or_hash.map(convert::block_number_or_tag_or_hash),
+			state_overrides.map(convert::state_overrides),
+			block_overrides.map(convert::block_overrides),
+		)
+		.await?;
+		Ok(convert::bytes_v2(bytes))
+	}
+
+	async fn estimate_gas(
+		&self,
+		request: TransactionRequest,
+		number_or_hash: Option<BlockNumberOrTag>,
+		state_overrides: Option<StateOverrides>,
+	) -> RpcResult<U256> {
+		EthApiV1Server::estimate_gas(
+			&*self.eth,
+			convert::transaction_request(request),
+			number_or_hash.map(convert::block_number_or_tag),
+			state_overrides.map(convert::state_overrides),
+			None,
+		)
+		.await
+	}
+
+	async fn create_access_list(
+		&self,
+		request: TransactionRequest,
+		number_or_hash: Option<BlockNumberOrTag>,
+	) -> RpcResult<AccessListResult> {
+		let result = EthApiV1Server::create_access_list(
+			&*self.eth,
+			convert::transaction_request(request),
+			number_or_hash.map(convert::block_number_or_tag),
+		)
+		.await?;
+		Ok(convert::access_list_result(result))
+	}
+}
+
+#[async_trait]
+impl<E: EthApiV1Server> EthFeeMarketApiServer for Eth<E> {
+	async fn gas_price(&self) -> RpcResult<U256> {
+		EthApiV1Server::gas_price(&*self.eth)
+	}
+
+	async fn max_priority_fee_per_gas(&self) -> RpcResult<U256> {
+		EthApiV1Server::max_priority_fee_per_gas(&*self.eth)
+	}
+
+	async fn fee_history(
+		&self,
+		block_count: U256,
+		newest_block: BlockNumberOrTag,
+		reward_percentiles: Option<Vec<f64>>,
+	) -> RpcResult<FeeHistoryResult> {
+		let fee_history = EthApiV1Server::fee_history(
+			&*self.eth,
+			fc_rpc_core::types::BlockCount::U256(block_count),
+			convert::block_number_or_tag(newest_block),
+			reward_percentiles,
+		)
+		.await?;
+		Ok(convert::fee_history(fee_history))
+	}
+}
+
+#[async_trait]
+impl<E: EthApiV1Server> EthSignApiServer for Eth<E> {
+	async fn sign(&self, address: Address, message: Bytes) -> RpcResult<Bytes> {
+		let signature = EthApiV1Server::sign(&*self.eth, address, convert::bytes(message))?;
+		Ok(convert::bytes_v2(signature))
+	}
+
+	async fn sign_transaction(&self, request: TransactionRequest) -> RpcResult<Bytes> {
+		let signed = EthApiV1Server::sign_transaction(
+			&*self.eth,
+			convert::transaction_request(request).into(),
+		)
+		.await?;
+		Ok(convert::bytes_v2(signed.raw))
+	}
+}
+
+#[async_trait]
+impl<E: EthApiV1Server> EthStateApiServer for Eth<E> {
+	async fn balance(
+		&self,
+		address: Address,
+		block: Option<BlockNumberOrTagOrHash>,
+	) -> RpcResult<U256> {
+		let number_or_hash = block.map(convert::block_number_or_tag_or_hash);
+		EthApiV1Server::balance(&*self.eth, address, number_or_hash).await
+	}
+
+	async fn storage_at(
+		&self,
+		address: Address,
+		slot: U256,
+		block: Option<BlockNumberOrTagOrHash>,
+	) -> RpcResult<H256> {
+		let number_or_hash = block.map(convert::block_number_or_tag_or_hash);
+		EthApiV1Server::storage_at(&*self.eth, address, slot, number_or_hash).await
+	}
+
+	async fn transaction_count(
+		&self,
+		address: Address,
+		block: Option<BlockNumberOrTagOrHash>,
+	) -> RpcResult<U256> {
+		let number_or_hash = block.map(convert::block_number_or_tag_or_hash);
+		EthApiV1Server::transaction_count(&*self.eth, address, number_or_hash).await
+	}
+
+	async fn code(
+		&self,
+		address: Address,
+		block: Option<BlockNumberOrTagOrHash>,
+	) -> RpcResult<Bytes> {
+		let number_or_hash = block.map(convert::block_number_or_tag_or_hash);
+		let code = EthApiV1Server::code_at(&*self.eth, address, number_or_hash).await?;
+		Ok(convert::bytes_v2(code))
+	}
+
+	async fn proof(
+		&self,
+		address: Address,
+		storage_keys: Vec<H256>,
+		block: Option<BlockNumberOrTagOrHash>,
+	) -> RpcResult<AccountProof> {
+		let number_or_hash = block.map(convert::block_number_or_tag_or_hash);
+		let account =
+			EthApiV1Server::proof(&*self.eth, address, storage_keys, number_or_hash).await?;
+		Ok(convert::account_proof(account))
+	}
+}
+
+#[async_trait]
+impl<E: EthApiV1Server> EthSubmitApiServer for Eth<E> {
+	async fn send_transaction(&self, request: TransactionRequest) -> RpcResult<H256> {
+		let request = convert::transaction_request(request);
+		EthApiV1Server::send_transaction(&*self.eth, request.into()).await
+	}
+
+	async fn send_raw_transaction(&self, bytes: Bytes) -> RpcResult<H256> {
+		EthApiV1Server::send_raw_transaction(&*self.eth, convert::bytes(bytes)).await
+	}
+}
+
+#[async_trait]
+impl<E: EthApiV1Server> EthTransactionApiServer for Eth<E> {
+	async fn transaction_by_hash(&self, transaction_hash: H256) -> RpcResult<Option<Transaction>> {
+		let transaction = EthApiV1Server::transaction_by_hash(&*self.eth, transaction_hash).await?;
+		Ok(transaction.map(convert::transaction))
+	}
+
+	async fn transaction_by_block_hash_and_index(
+		&self,
+		block_hash: H256,
+		transaction_index: Index,
+	) -> RpcResult<Option<Transaction>> {
+		let transaction = EthApiV1Server::transaction_by_block_hash_and_index(
+			&*self.eth,
+			block_hash,
+			convert::index(transaction_index),
+		)
+		.await?;
+		Ok(transaction.map(convert::transaction))
+	}
+
+	async fn transaction_by_block_number_and_index(
+		&self,
+		block: BlockNumberOrTag,
+		transaction_index: Index,
+	) -> RpcResult<Option<Transaction>> {
+		let transaction = EthApiV1Server::transaction_by_block_number_and_index(
+			&*self.eth,
+			convert::block_number_or_tag(block),
+			convert::index(transaction_index),
+		)
+		.await?;
+		Ok(transaction.map(convert::transaction))
+	}
+
+	async fn transaction_receipt(
+		&self,
+		transaction_hash: H256,
+	) -> RpcResult<Option<TransactionReceipt>> {
+		let receipt = EthApiV1Server::transaction_receipt(&*self.eth, transaction_hash).await?;
+		Ok(receipt.map(convert::receipt))
+	}
+}
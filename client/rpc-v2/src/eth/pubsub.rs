@@ -0,0 +1,77 @@
+// This file is part of Frontier.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::sync::Arc;
+
+use jsonrpsee::{
+	core::{async_trait, SubscriptionResult},
+	server::PendingSubscriptionSink,
+};
+// Frontier
+use fc_rpc_core::EthPubSubApiServer as EthPubSubApiV1Server;
+use fc_rpc_v2_api::{
+	eth::EthPubSubApiServer,
+	types::{PubSubKind, PubSubParams},
+};
+
+use crate::convert;
+
+/// Eth pub-sub API implementation, backed by an `fc-rpc` pub-sub API implementation
+/// (e.g. `fc_rpc::EthPubSub`).
+///
+/// The subscription sink is handed over to the wrapped implementation, which keeps serving the
+/// notifications of the subscription.
+pub struct EthPubSub<P> {
+	pubsub: Arc<P>,
+}
+
+impl<P> Clone for EthPubSub<P> {
+	fn clone(&self) -> Self {
+		Self {
+			pubsub: self.pubsub.clone(),
+		}
+	}
+}
+
+impl<P> EthPubSub<P> {
+	pub fn new(pubsub: P) -> Self {
+		Self {
+			pubsub: Arc::new(pubsub),
+		}
+	}
+}
+
+#[async_trait]
+impl<P: EthPubSubApiV1Server> EthPubSubApiServer for EthPubSub<P> {
+	async fn sub(
+		&self,
+		pending: PendingSubscriptionSink,
+		kind: PubSubKind,
+		params: Option<PubSubParams>,
+	) -> SubscriptionResult {
+		let params = match params.map(convert::pubsub_params).transpose() {
+			Ok(params) => params,
+			Err(err) => {
+				pending.reject(err).await;
+				return Ok(());
+			}
+		};
+		EthPubSubApiV1Server::subscribe(&*self.pubsub, pending, convert::pubsub_kind(kind), params);
+		Ok(())
+	}
+}
@@ -16,4 +16,48 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+//! Ethereum RPC (web3) servers implementing the `fc-rpc-v2-api` interfaces.
+//!
+//! The servers are thin adapters over the `fc-rpc` servers: every request is converted into the
+//! `fc-rpc-core` types, served by the wrapped `fc-rpc` implementation (sharing its backend, block
+//! data cache and filter/subscription pools), and the response is converted back into the
+//! `fc-rpc-v2-types` types.
+
 #![warn(unused_crate_dependencies)]
+
+mod convert;
+mod debug;
+mod eth;
+mod net;
+#[cfg(feature = "txpool")]
+mod txpool;
+mod web3;
+
+#[cfg(feature = "txpool")]
+pub use self::txpool::TxPool;
+pub use self::{
+	debug::Debug,
+	eth::{Eth, EthFilter, EthPubSub},
+	net::Net,
+	web3::Web3,
+};
+pub use fc_rpc_v2_api::{
+	debug::DebugApiServer,
+	eth::{
+		EthApiServer, EthBlockApiServer, EthClientApiServer, EthExecuteApiServer,
+		EthFeeMarketApiServer, EthFilterApiServer, EthPubSubApiServer, EthSignApiServer,
+		EthStateApiServer, EthSubmitApiServer, EthTransactionApiServer,
+	},
+	net::NetApiServer,
+	txpool::TxPoolApiServer,
+	types,
+	web3::Web3ApiServer,
+};
+
+pub fn internal_err<T: ToString>(message: T) -> jsonrpsee::types::error::ErrorObjectOwned {
+	jsonrpsee::types::error::ErrorObject::owned(
+		jsonrpsee::types::error::INTERNAL_ERROR_CODE,
+		message.to_string(),
+		None::<()>,
+	)
+}
@@ -0,0 +1,53 @@
+// This file is part of Frontier.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::sync::Arc;
+
+use ethereum_types::U64;
+use jsonrpsee::core::{async_trait, RpcResult};
+// Frontier
+use fc_rpc_core::NetApiServer as NetApiV1Server;
+use fc_rpc_v2_api::net::NetApiServer;
+
+use crate::convert;
+
+/// Net API implementation, backed by an `fc-rpc` net API implementation (e.g. `fc_rpc::Net`).
+pub struct Net<N> {
+	net: Arc<N>,
+}
+
+impl<N> Net<N> {
+	pub fn new(net: N) -> Self {
+		Self { net: Arc::new(net) }
+	}
+}
+
+#[async_trait]
+impl<N: NetApiV1Server> NetApiServer for Net<N> {
+	async fn version(&self) -> RpcResult<String> {
+		NetApiV1Server::version(&*self.net)
+	}
+
+	async fn peer_count(&self) -> RpcResult<U64> {
+		convert::peer_count(NetApiV1Server::peer_count(&*self.net)?)
+	}
+
+	async fn listening(&self) -> RpcResult<bool> {
+		NetApiV1Server::is_listening(&*self.net)
+	}
+}
@@ -0,0 +1,79 @@
+// This file is part of Frontier.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::sync::Arc;
+
+use ethereum_types::{Address, U64};
+use jsonrpsee::core::{async_trait, RpcResult};
+// Frontier
+use fc_rpc_core::TxPoolApiServer as TxPoolApiV1Server;
+use fc_rpc_v2_api::{
+	txpool::TxPoolApiServer,
+	types::{TxpoolContent, TxpoolContentFrom, TxpoolInspect, TxpoolResult, TxpoolStatus},
+};
+
+use crate::convert;
+
+/// TxPool API implementation, backed by an `fc-rpc` txpool API implementation
+/// (e.g. `fc_rpc::TxPool`).
+pub struct TxPool<T> {
+	txpool: Arc<T>,
+}
+
+impl<T> TxPool<T> {
+	pub fn new(txpool: T) -> Self {
+		Self {
+			txpool: Arc::new(txpool),
+		}
+	}
+}
+
+#[async_trait]
+impl<T: TxPoolApiV1Server> TxPoolApiServer for TxPool<T> {
+	async fn content(&self) -> RpcResult<TxpoolContent> {
+		let content = TxPoolApiV1Server::content(&*self.txpool)?;
+		Ok(TxpoolResult {
+			pending: convert::txpool_map(content.pending, convert::transaction),
+			queued: convert::txpool_map(content.queued, convert::transaction),
+		})
+	}
+
+	async fn content_from(&self, address: Address) -> RpcResult<TxpoolContentFrom> {
+		let mut content = self.content().await?;
+		Ok(TxpoolResult {
+			pending: content.pending.remove(&address).unwrap_or_default(),
+			queued: content.queued.remove(&address).unwrap_or_default(),
+		})
+	}
+
+	async fn inspect(&self) -> RpcResult<TxpoolInspect> {
+		let inspect = TxPoolApiV1Server::inspect(&*self.txpool)?;
+		Ok(TxpoolResult {
+			pending: convert::txpool_map(inspect.pending, convert::txpool_summary),
+			queued: convert::txpool_map(inspect.queued, convert::txpool_summary),
+		})
+	}
+
+	async fn status(&self) -> RpcResult<TxpoolStatus> {
+		let status = TxPoolApiV1Server::status(&*self.txpool)?;
+		Ok(TxpoolResult {
+			pending: U64::from(status.pending.low_u64()),
+			queued: U64::from(status.queued.low_u64()),
+		})
+	}
+}
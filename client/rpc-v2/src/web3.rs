@@ -0,0 +1,51 @@
+// This file is part of Frontier.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::sync::Arc;
+
+use ethereum_types::H256;
+use jsonrpsee::core::{async_trait, RpcResult};
+// Frontier
+use fc_rpc_core::Web3ApiServer as Web3ApiV1Server;
+use fc_rpc_v2_api::{types::Bytes, web3::Web3ApiServer};
+
+use crate::convert;
+
+/// Web3 API implementation, backed by an `fc-rpc` web3 API implementation (e.g. `fc_rpc::Web3`).
+pub struct Web3<W> {
+	web3: Arc<W>,
+}
+
+impl<W> Web3<W> {
+	pub fn new(web3: W) -> Self {
+		Self {
+			web3: Arc::new(web3),
+		}
+	}
+}
+
+#[async_trait]
+impl<W: Web3ApiV1Server> Web3ApiServer for Web3<W> {
+	async fn client_version(&self) -> RpcResult<String> {
+		Web3ApiV1Server::client_version(&*self.web3)
+	}
+
+	async fn sha3(&self, input: Bytes) -> RpcResult<H256> {
+		Web3ApiV1Server::sha3(&*self.web3, convert::bytes(input))
+	}
+}
@@ -272,6 +272,18 @@ impl From<H256> for BlockHash {
 	}
 }
 
+impl BlockHash {
+	/// Returns the block hash.
+	pub fn block_hash(&self) -> H256 {
+		self.block_hash
+	}
+
+	/// Returns the `requireCanonical` flag, if it was provided.
+	pub fn require_canonical(&self) -> Option<bool> {
+		self.require_canonical
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
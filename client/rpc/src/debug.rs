@@ -16,34 +16,54 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use std::{marker::PhantomData, sync::Arc};
+pub(crate) mod format;
+
+use std::{cell::RefCell, marker::PhantomData, sync::Arc};
 
 use ethereum::EnvelopedEncodable;
-use ethereum_types::H256;
+use ethereum_types::{H160, H256};
 use jsonrpsee::core::{async_trait, RpcResult};
 use rlp::Encodable;
+use scale_codec::{Decode, Encode};
 // Substrate
-use sc_client_api::backend::{Backend, StorageProvider};
-use sp_api::ProvideRuntimeApi;
+use sc_client_api::{
+	backend::{Backend, StorageProvider},
+	BlockBackend,
+};
+use sp_api::{
+	ApiExt, CallApiAt, CallApiAtParams, CallContext, OverlayedChanges, ProvideRuntimeApi,
+};
 use sp_blockchain::HeaderBackend;
-use sp_runtime::traits::Block as BlockT;
+use sp_externalities::Extensions;
+use sp_runtime::{
+	traits::{Block as BlockT, HashingFor, Header as HeaderT},
+	DispatchError,
+};
 // Frontier
 use fc_rpc_core::{types::*, DebugApiServer};
 use fc_storage::StorageOverride;
-use fp_rpc::EthereumRuntimeRPCApi;
+use fp_evm::tracing::TraceResult;
+use fp_rpc::{DebugRuntimeApi, EthereumRuntimeRPCApi};
 
-use crate::{cache::EthBlockDataCacheTask, frontier_backend_client, internal_err};
+use crate::{
+	cache::EthBlockDataCacheTask,
+	eth::{
+		execute::{create_overrides_overlay, evm_block_overrides, fee_details},
+		EthConfig,
+	},
+	frontier_backend_client, internal_err,
+};
 
 /// Debug API implementation.
-pub struct Debug<B: BlockT, C, BE> {
+pub struct Debug<B: BlockT, C, BE, EC> {
 	client: Arc<C>,
 	backend: Arc<dyn fc_api::Backend<B>>,
 	storage_override: Arc<dyn StorageOverride<B>>,
 	block_data_cache: Arc<EthBlockDataCacheTask<B>>,
-	_marker: PhantomData<BE>,
+	_marker: PhantomData<(BE, EC)>,
 }
 
-impl<B: BlockT, C, BE> Debug<B, C, BE> {
+impl<B: BlockT, C, BE, EC> Debug<B, C, BE, EC> {
 	pub fn new(
 		client: Arc<C>,
 		backend: Arc<dyn fc_api::Backend<B>>,
@@ -59,7 +79,7 @@ impl<B: BlockT, C, BE> Debug<B, C, BE> {
 		}
 	}
 
-	async fn block_by(&self, number: BlockNumberOrHash) -> RpcResult<Option<ethereum::BlockV3>>
+	async fn substrate_hash_by(&self, number: BlockNumberOrHash) -> RpcResult<Option<B::Hash>>
 	where
 		C: HeaderBackend<B> + StorageProvider<B, BE> + 'static,
 		BE: Backend<B>,
@@ -79,6 +99,18 @@ impl<B: BlockT, C, BE> Debug<B, C, BE> {
 			.client
 			.expect_block_hash_from_id(&id)
 			.map_err(|_| internal_err(format!("Expect block number from id: {id}")))?;
+		Ok(Some(substrate_hash))
+	}
+
+	async fn block_by(&self, number: BlockNumberOrHash) -> RpcResult<Option<ethereum::BlockV3>>
+	where
+		C: HeaderBackend<B> + StorageProvider<B, BE> + 'static,
+		BE: Backend<B>,
+	{
+		let substrate_hash = match self.substrate_hash_by(number).await? {
+			Some(hash) => hash,
+			None => return Ok(None),
+		};
 		let block = self.block_data_cache.current_block(substrate_hash).await;
 		Ok(block)
 	}
@@ -130,36 +162,328 @@ impl<B: BlockT, C, BE> Debug<B, C, BE> {
 		C: HeaderBackend<B> + StorageProvider<B, BE> + 'static,
 		BE: Backend<B>,
 	{
-		let id = match frontier_backend_client::native_block_id::<B, C>(
+		let substrate_hash = match self.substrate_hash_by(number).await? {
+			Some(hash) => hash,
+			None => return Ok(None),
+		};
+
+		// TODO: use data cache in the future
+		let receipts = self.storage_override.current_receipts(substrate_hash);
+		Ok(receipts)
+	}
+
+	async fn trace_transaction_by(
+		&self,
+		transaction_hash: H256,
+		params: Option<TraceParams>,
+	) -> RpcResult<Trace>
+	where
+		C: CallApiAt<B> + ProvideRuntimeApi<B>,
+		C: HeaderBackend<B> + BlockBackend<B> + StorageProvider<B, BE> + 'static,
+		BE: Backend<B>,
+	{
+		let config = format::tracer_config(params).map_err(internal_err)?;
+
+		let eth_block_hash = match frontier_backend_client::load_transactions::<B, C>(
 			self.client.as_ref(),
 			self.backend.as_ref(),
-			Some(number),
+			transaction_hash,
+			true,
 		)
 		.await?
 		{
-			Some(id) => id,
-			None => return Ok(None),
+			Some((hash, _)) => hash,
+			None => return Err(internal_err("transaction not found")),
 		};
 
-		let substrate_hash = self
+		let substrate_hash = match frontier_backend_client::load_hash::<B, C>(
+			self.client.as_ref(),
+			self.backend.as_ref(),
+			eth_block_hash,
+		)
+		.await?
+		{
+			Some(hash) => hash,
+			None => return Err(internal_err("block not found")),
+		};
+
+		let result = replay_block::<B, C, TraceResult>(
+			self.client.as_ref(),
+			substrate_hash,
+			"DebugRuntimeApi_trace_transaction",
+			(transaction_hash, config),
+		)?
+		.map_err(|err| internal_err(format!("failed to trace transaction: {err:?}")))?;
+
+		Ok(format::trace(result))
+	}
+
+	async fn trace_block_by(
+		&self,
+		number: BlockNumberOrHash,
+		params: Option<TraceParams>,
+	) -> RpcResult<Vec<TransactionTrace>>
+	where
+		C: CallApiAt<B> + ProvideRuntimeApi<B>,
+		C: HeaderBackend<B> + BlockBackend<B> + StorageProvider<B, BE> + 'static,
+		BE: Backend<B>,
+	{
+		let config = format::tracer_config(params).map_err(internal_err)?;
+
+		let substrate_hash = match self.substrate_hash_by(number).await? {
+			Some(hash) => hash,
+			None => return Err(internal_err("block not found")),
+		};
+
+		// Line up the traces, which follow the order of the Ethereum transactions in the block,
+		// with the transaction hashes.
+		let transaction_hashes = match self
+			.storage_override
+			.current_transaction_statuses(substrate_hash)
+		{
+			Some(statuses) => statuses
+				.into_iter()
+				.map(|status| status.transaction_hash)
+				.collect::<Vec<_>>(),
+			None => match self.block_data_cache.current_block(substrate_hash).await {
+				Some(block) => block.transactions.iter().map(|t| t.hash()).collect(),
+				None => return Err(internal_err("block not found")),
+			},
+		};
+		if transaction_hashes.is_empty() {
+			return Ok(vec![]);
+		}
+
+		let results = replay_block::<B, C, Vec<TraceResult>>(
+			self.client.as_ref(),
+			substrate_hash,
+			"DebugRuntimeApi_trace_block",
+			config,
+		)?
+		.map_err(|err| internal_err(format!("failed to trace block: {err:?}")))?;
+		if results.len() != transaction_hashes.len() {
+			return Err(internal_err(format!(
+				"expected {} traces, got {}",
+				transaction_hashes.len(),
+				results.len()
+			)));
+		}
+
+		Ok(transaction_hashes
+			.into_iter()
+			.zip(results)
+			.map(|(tx_hash, result)| TransactionTrace {
+				tx_hash,
+				result: format::trace(result),
+			})
+			.collect())
+	}
+
+	async fn trace_call_by(
+		&self,
+		request: TransactionRequest,
+		number_or_hash: Option<BlockNumberOrHash>,
+		params: Option<TraceCallParams>,
+	) -> RpcResult<Trace>
+	where
+		C: CallApiAt<B> + ProvideRuntimeApi<B>,
+		C::Api: EthereumRuntimeRPCApi<B>,
+		C: HeaderBackend<B> + StorageProvider<B, BE> + 'static,
+		BE: Backend<B>,
+		EC: EthConfig<B, C>,
+	{
+		let TraceCallParams {
+			trace,
+			state_overrides,
+			block_overrides,
+		} = params.unwrap_or_default();
+		let config = format::tracer_config(Some(trace)).map_err(internal_err)?;
+
+		let TransactionRequest {
+			from,
+			to,
+			gas_price,
+			max_fee_per_gas,
+			max_priority_fee_per_gas,
+			gas,
+			value,
+			data,
+			nonce,
+			access_list,
+			authorization_list,
+			..
+		} = request;
+		let details = fee_details(gas_price, max_fee_per_gas, max_priority_fee_per_gas)?;
+
+		let substrate_hash = match self
+			.substrate_hash_by(number_or_hash.unwrap_or_default())
+			.await?
+		{
+			Some(hash) => hash,
+			None => return Err(internal_err("block not found")),
+		};
+
+		ensure_tracing_supported::<B, C>(self.client.as_ref(), substrate_hash)?;
+		let api = self.client.runtime_api();
+		let Ok(Some(eth_api_version)) =
+			api.api_version::<dyn EthereumRuntimeRPCApi<B>>(substrate_hash)
+		else {
+			return Err(internal_err("failed to retrieve Runtime Api version"));
+		};
+
+		// Use the given gas limit or the block gas limit.
+		let gas_limit = match gas {
+			Some(gas) => gas,
+			None => {
+				self.block_data_cache
+					.current_block(substrate_hash)
+					.await
+					.ok_or_else(|| internal_err("block unavailable, cannot query gas limit"))?
+					.header
+					.gas_limit
+			}
+		};
+
+		let access_list = access_list.map(|access_list| {
+			access_list
+				.into_iter()
+				.map(|item| (item.address, item.storage_keys))
+				.collect::<Vec<(H160, Vec<H256>)>>()
+		});
+		let block_overrides = block_overrides.map(evm_block_overrides);
+		let encoded_params = Encode::encode(&(
+			&from.unwrap_or_default(),
+			&to,
+			&data.into_bytes().map(|d| d.into_vec()).unwrap_or_default(),
+			&value.unwrap_or_default(),
+			&gas_limit,
+			&details.max_fee_per_gas,
+			&details.max_priority_fee_per_gas,
+			&nonce,
+			&access_list,
+			&authorization_list,
+			&block_overrides,
+			&config,
+		));
+		let overlayed_changes = create_overrides_overlay::<B, C, BE, EC::RuntimeStorageOverride>(
+			self.client.as_ref(),
+			substrate_hash,
+			eth_api_version,
+			state_overrides,
+		)?;
+
+		// Enable proof size recording
+		let recorder: sp_trie::recorder::Recorder<HashingFor<B>> = Default::default();
+		let ext = sp_trie::proof_size_extension::ProofSizeExt::new(recorder.clone());
+		let mut exts = Extensions::new();
+		exts.register(ext);
+
+		let params = CallApiAtParams {
+			at: substrate_hash,
+			function: "DebugRuntimeApi_trace_call",
+			arguments: encoded_params,
+			overlayed_changes: &RefCell::new(overlayed_changes),
+			call_context: CallContext::Offchain,
+			recorder: &Some(recorder),
+			extensions: &RefCell::new(exts),
+		};
+		let result = self
 			.client
-			.expect_block_hash_from_id(&id)
-			.map_err(|_| internal_err(format!("Expect block number from id: {id}")))?;
+			.call_api_at(params)
+			.and_then(|r| {
+				<Result<TraceResult, DispatchError> as Decode>::decode(&mut &r[..]).map_err(
+					|error| sp_api::ApiError::FailedToDecodeReturnValue {
+						function: "DebugRuntimeApi_trace_call",
+						error,
+						raw: r,
+					},
+				)
+			})
+			.map_err(|err| internal_err(format!("runtime error: {err}")))?
+			.map_err(|err| internal_err(format!("execution fatal: {err:?}")))?;
 
-		// TODO: use data cache in the future
-		let receipts = self.storage_override.current_receipts(substrate_hash);
-		Ok(receipts)
+		Ok(format::trace(result))
 	}
 }
 
-#[async_trait]
-impl<B, C, BE> DebugApiServer for Debug<B, C, BE>
+/// Returns an error when the runtime at `at` is built without the tracer.
+fn ensure_tracing_supported<B, C>(client: &C, at: B::Hash) -> RpcResult<()>
 where
 	B: BlockT,
 	C: ProvideRuntimeApi<B>,
+{
+	match client.runtime_api().has_api::<dyn DebugRuntimeApi<B>>(at) {
+		Ok(true) => Ok(()),
+		Ok(false) => Err(internal_err("tracing is not supported by the runtime")),
+		Err(err) => Err(internal_err(format!("runtime error: {err}"))),
+	}
+}
+
+/// Calls the `DebugRuntimeApi` function `function` with the extrinsics of the block
+/// `substrate_hash` followed by `arguments`, once the block is initialized on top of its parent
+/// state.
+///
+/// The function is called by name, so that nodes can serve runtimes built without the tracer:
+/// those are only reported as not supporting tracing.
+pub(crate) fn replay_block<B, C, R>(
+	client: &C,
+	substrate_hash: B::Hash,
+	function: &'static str,
+	arguments: impl Encode,
+) -> RpcResult<Result<R, DispatchError>>
+where
+	B: BlockT,
+	C: CallApiAt<B> + ProvideRuntimeApi<B>,
+	C: HeaderBackend<B> + BlockBackend<B>,
+	R: Decode,
+{
+	let header = match client.header(substrate_hash) {
+		Ok(Some(header)) => header,
+		_ => return Err(internal_err("block header not found")),
+	};
+	let extrinsics = match client.block_body(substrate_hash) {
+		Ok(Some(extrinsics)) => extrinsics,
+		_ => return Err(internal_err("block body not found")),
+	};
+	let parent_hash = *header.parent_hash();
+
+	ensure_tracing_supported::<B, C>(client, parent_hash)?;
+
+	// Both calls share the same changes, as a runtime API instance would.
+	let overlayed_changes = RefCell::new(OverlayedChanges::<HashingFor<B>>::default());
+	let extensions = RefCell::new(Extensions::new());
+	let call = |function: &'static str, arguments: Vec<u8>| {
+		client
+			.call_api_at(CallApiAtParams {
+				at: parent_hash,
+				function,
+				arguments,
+				overlayed_changes: &overlayed_changes,
+				call_context: CallContext::Offchain,
+				recorder: &None,
+				extensions: &extensions,
+			})
+			.map_err(|err| internal_err(format!("runtime error: {err}")))
+	};
+
+	// Replay the block on top of its parent state.
+	call("Core_initialize_block", header.encode())?;
+	let mut encoded = extrinsics.encode();
+	arguments.encode_to(&mut encoded);
+	let result = call(function, encoded)?;
+	<Result<R, DispatchError> as Decode>::decode(&mut &result[..])
+		.map_err(|err| internal_err(format!("runtime error: failed to decode {function}: {err}")))
+}
+
+#[async_trait]
+impl<B, C, BE, EC> DebugApiServer for Debug<B, C, BE, EC>
+where
+	B: BlockT,
+	C: CallApiAt<B> + ProvideRuntimeApi<B>,
 	C::Api: EthereumRuntimeRPCApi<B>,
-	C: HeaderBackend<B> + StorageProvider<B, BE> + 'static,
+	C: HeaderBackend<B> + BlockBackend<B> + StorageProvider<B, BE> + 'static,
 	BE: Backend<B> + 'static,
+	EC: EthConfig<B, C>,
 {
 	async fn raw_header(&self, number: BlockNumberOrHash) -> RpcResult<Option<Bytes>> {
 		let block = self.block_by(number).await?;
@@ -191,4 +515,44 @@ where
 		// We can simply return empty array for this API.
 		Ok(vec![])
 	}
+
+	async fn trace_transaction(
+		&self,
+		transaction_hash: H256,
+		params: Option<TraceParams>,
+	) -> RpcResult<Trace> {
+		self.trace_transaction_by(transaction_hash, params).await
+	}
+
+	async fn trace_block_by_number(
+		&self,
+		number: BlockNumberOrHash,
+		params: Option<TraceParams>,
+	) -> RpcResult<Vec<TransactionTrace>> {
+		self.trace_block_by(number, params).await
+	}
+
+	async fn trace_block_by_hash(
+		&self,
+		hash: H256,
+		params: Option<TraceParams>,
+	) -> RpcResult<Vec<TransactionTrace>> {
+		self.trace_block_by(
+			BlockNumberOrHash::Hash {
+				hash,
+				require_canonical: false,
+			},
+			params,
+		)
+		.await
+	}
+
+	async fn trace_call(
+		&self,
+		request: TransactionRequest,
+		number_or_hash: Option<BlockNumberOrHash>,
+		params: Option<TraceCallParams>,
+	) -> RpcResult<Trace> {
+		self.trace_call_by(request, number_or_hash, params).await
+	}
 }
@@ -0,0 +1,351 @@
+// This file is part of Frontier.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Conversion of the runtime traces into their geth JSON representation.
+
+use std::collections::BTreeMap;
+
+use ethereum_types::{H160, H256, U256};
+use evm::{ExitError, ExitReason};
+use sp_runtime::traits::UniqueSaturatedInto;
+// Frontier
+use fc_rpc_core::types::{
+	CallFrame, PrestateAccount, PrestateDiff, StructLog, StructLoggerTrace, Trace, TraceParams,
+	TracerConfig,
+};
+use fp_evm::tracing::{self as runtime};
+
+/// Name of the geth call tracer.
+const CALL_TRACER: &str = "callTracer";
+/// Name of the geth prestate tracer.
+const PRESTATE_TRACER: &str = "prestateTracer";
+
+/// Build the runtime tracer configuration from the RPC parameters.
+pub fn tracer_config(params: Option<TraceParams>) -> Result<runtime::TracerConfig, String> {
+	let params = params.unwrap_or_default();
+	match params.tracer.as_deref() {
+		None => Ok(runtime::TracerConfig::StructLogger(
+			runtime::StructLoggerConfig {
+				disable_stack: params.disable_stack.unwrap_or(false),
+				disable_memory: !params.enable_memory.unwrap_or(false),
+				disable_storage: params.disable_storage.unwrap_or(false),
+				enable_return_data: params.enable_return_data.unwrap_or(false),
+				limit: params.limit.unwrap_or(0),
+			},
+		)),
+		Some(CALL_TRACER) => {
+			let TracerConfig { only_top_call, .. } = params.tracer_config.unwrap_or_default();
+			Ok(runtime::TracerConfig::CallTracer(
+				runtime::CallTracerConfig {
+					only_top_call: only_top_call.unwrap_or(false),
+				},
+			))
+		}
+		Some(PRESTATE_TRACER) => {
+			let TracerConfig { diff_mode, .. } = params.tracer_config.unwrap_or_default();
+			Ok(runtime::TracerConfig::PrestateTracer(
+				runtime::PrestateTracerConfig {
+					diff_mode: diff_mode.unwrap_or(false),
+				},
+			))
+		}
+		Some(tracer) => Err(format!("tracer {tracer} is not supported")),
+	}
+}
+
+/// Convert a runtime trace into its RPC representation.
+pub fn trace(result: runtime::TraceResult) -> Trace {
+	match result {
+		runtime::TraceResult::StructLogger(result) => Trace::StructLogger(StructLoggerTrace {
+			gas: result.gas,
+			failed: result.failed,
+			return_value: result.return_value.into(),
+			struct_logs: result.struct_logs.into_iter().map(struct_log).collect(),
+		}),
+		runtime::TraceResult::CallTracer(frame) => Trace::CallTracer(call_frame(frame)),
+		runtime::TraceResult::PrestateTracer(runtime::PrestateResult::Prestate(accounts)) => {
+			Trace::Prestate(prestate_accounts(accounts))
+		}
+		runtime::TraceResult::PrestateTracer(runtime::PrestateResult::Diff { pre, post }) => {
+			Trace::PrestateDiff(PrestateDiff {
+				pre: prestate_accounts(pre),
+				post: prestate_accounts(post),
+			})
+		}
+	}
+}
+
+fn prestate_accounts(
+	accounts: BTreeMap<H160, runtime::AccountState>,
+) -> BTreeMap<H160, PrestateAccount> {
+	accounts
+		.into_iter()
+		.map(|(address, account)| {
+			let account = PrestateAccount {
+				balance: account.balance,
+				// Geth leaves out zero nonces.
+				nonce: account
+					.nonce
+					.filter(|nonce| !nonce.is_zero())
+					.map(|nonce| nonce.unique_saturated_into()),
+				code: account.code.map(Into::into),
+				storage: account.storage,
+			};
+			(address, account)
+		})
+		.collect()
+}
+
+fn struct_log(log: runtime::StructLog) -> StructLog {
+	StructLog {
+		pc: log.pc,
+		op: opcode_name(log.op),
+		gas: log.gas,
+		gas_cost: log.gas_cost,
+		depth: log.depth,
+		stack: log.stack.map(|stack| {
+			stack
+				.into_iter()
+				.map(|item| U256::from_big_endian(item.as_bytes()))
+				.collect()
+		}),
+		memory: log.memory.map(|memory| {
+			memory
+				.chunks(32)
+				.map(|chunk| {
+					let mut word = H256::zero();
+					word.as_bytes_mut()[..chunk.len()].copy_from_slice(chunk);
+					word
+				})
+				.collect()
+		}),
+		storage: log.storage,
+		return_data: log.return_data.map(Into::into),
+	}
+}
+
+fn call_frame(frame: runtime::CallFrame) -> CallFrame {
+	let (error, revert_reason) = match &frame.exit_reason {
+		Some(reason) => (exit_error(reason), revert_reason(reason, &frame.output)),
+		None => (None, None),
+	};
+	let output = match frame.exit_reason {
+		Some(ExitReason::Succeed(_)) | Some(ExitReason::Revert(_)) if !frame.output.is_empty() => {
+			Some(frame.output.into())
+		}
+		_ => None,
+	};
+	CallFrame {
+		call_type: call_type(frame.call_type).into(),
+		from: frame.from,
+		to: frame.to,
+		value: frame.value,
+		gas: frame.gas.into(),
+		gas_used: frame.gas_used.into(),
+		input: frame.input.into(),
+		output,
+		error,
+		revert_reason,
+		calls: frame.calls.into_iter().map(call_frame).collect(),
+	}
+}
+
+fn call_type(call_type: runtime::CallType) -> &'static str {
+	match call_type {
+		runtime::CallType::Call => "CALL",
+		runtime::CallType::CallCode => "CALLCODE",
+		runtime::CallType::DelegateCall => "DELEGATECALL",
+		runtime::CallType::StaticCall => "STATICCALL",
+		runtime::CallType::Create => "CREATE",
+		runtime::CallType::Create2 => "CREATE2",
+		runtime::CallType::SelfDestruct => "SELFDESTRUCT",
+	}
+}
+
+/// Error message of a failed frame, using geth's wording where there is an equivalent.
+pub fn exit_error(reason: &ExitReason) -> Option<String> {
+	let error = match reason {
+		ExitReason::Succeed(_) => return None,
+		ExitReason::Revert(_) => "execution reverted".into(),
+		ExitReason::Error(err) => match err {
+			ExitError::OutOfGas => "out of gas".into(),
+			ExitError::StackUnderflow => "stack underflow".into(),
+			ExitError::StackOverflow => "stack overflow".into(),
+			ExitError::InvalidJump => "invalid jump destination".into(),
+			ExitError::InvalidRange => "return data out of bounds".into(),
+			ExitError::DesignatedInvalid => "invalid opcode: INVALID".into(),
+			ExitError::CallTooDeep => "max call depth exceeded".into(),
+			ExitError::CreateCollision => "contract address collision".into(),
+			ExitError::CreateContractLimit => "max code size exceeded".into(),
+			ExitError::OutOfFund => "insufficient balance for transfer".into(),
+			ExitError::InvalidCode(opcode) => {
+				format!("invalid opcode: {}", opcode_name(opcode.0))
+			}
+			ExitError::Other(message) => message.to_string(),
+			err => format!("{err:?}"),
+		},
+		ExitReason::Fatal(err) => format!("{err:?}"),
+	};
+	Some(error)
+}
+
+/// Decode the `Error(string)` revert reason returned by a reverted frame.
+pub fn revert_reason(reason: &ExitReason, output: &[u8]) -> Option<String> {
+	const SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+	const LEN_START: usize = 36;
+	const MESSAGE_START: usize = 68;
+
+	if !matches!(reason, ExitReason::Revert(_))
+		|| output.len() < MESSAGE_START
+		|| output[..4] != SELECTOR
+	{
+		return None;
+	}
+	let message_len: usize =
+		U256::from_big_endian(&output[LEN_START..MESSAGE_START]).unique_saturated_into();
+	let message = output.get(MESSAGE_START..MESSAGE_START.checked_add(message_len)?)?;
+	String::from_utf8(message.to_vec()).ok()
+}
+
+/// Mnemonic of an opcode, as displayed by geth.
+pub fn opcode_name(opcode: u8) -> String {
+	let name = match opcode {
+		0x00 => "STOP",
+		0x01 => "ADD",
+		0x02 => "MUL",
+		0x03 => "SUB",
+		0x04 => "DIV",
+		0x05 => "SDIV",
+		0x06 => "MOD",
+		0x07 => "SMOD",
+		0x08 => "ADDMOD",
+		0x09 => "MULMOD",
+		0x0a => "EXP",
+		0x0b => "SIGNEXTEND",
+		0x10 => "LT",
+		0x11 => "GT",
+		0x12 => "SLT",
+		0x13 => "SGT",
+		0x14 => "EQ",
+		0x15 => "ISZERO",
+		0x16 => "AND",
+		0x17 => "OR",
+		0x18 => "XOR",
+		0x19 => "NOT",
+		0x1a => "BYTE",
+		0x1b => "SHL",
+		0x1c => "SHR",
+		0x1d => "SAR",
+		0x20 => "KECCAK256",
+		0x30 => "ADDRESS",
+		0x31 => "BALANCE",
+		0x32 => "ORIGIN",
+		0x33 => "CALLER",
+		0x34 => "CALLVALUE",
+		0x35 => "CALLDATALOAD",
+		0x36 => "CALLDATASIZE",
+		0x37 => "CALLDATACOPY",
+		0x38 => "CODESIZE",
+		0x39 => "CODECOPY",
+		0x3a => "GASPRICE",
+		0x3b => "EXTCODESIZE",
+		0x3c => "EXTCODECOPY",
+		0x3d => "RETURNDATASIZE",
+		0x3e => "RETURNDATACOPY",
+		0x3f => "EXTCODEHASH",
+		0x40 => "BLOCKHASH",
+		0x41 => "COINBASE",
+		0x42 => "TIMESTAMP",
+		0x43 => "NUMBER",
+		0x44 => "DIFFICULTY",
+		0x45 => "GASLIMIT",
+		0x46 => "CHAINID",
+		0x47 => "SELFBALANCE",
+		0x48 => "BASEFEE",
+		0x49 => "BLOBHASH",
+		0x4a => "BLOBBASEFEE",
+		0x50 => "POP",
+		0x51 => "MLOAD",
+		0x52 => "MSTORE",
+		0x53 => "MSTORE8",
+		0x54 => "SLOAD",
+		0x55 => "SSTORE",
+		0x56 => "JUMP",
+		0x57 => "JUMPI",
+		0x58 => "PC",
+		0x59 => "MSIZE",
+		0x5a => "GAS",
+		0x5b => "JUMPDEST",
+		0x5c => "TLOAD",
+		0x5d => "TSTORE",
+		0x5e => "MCOPY",
+		0x5f => "PUSH0",
+		0x60..=0x7f => return format!("PUSH{}", opcode - 0x5f),
+		0x80..=0x8f => return format!("DUP{}", opcode - 0x7f),
+		0x90..=0x9f => return format!("SWAP{}", opcode - 0x8f),
+		0xa0..=0xa4 => return format!("LOG{}", opcode - 0xa0),
+		0xf0 => "CREATE",
+		0xf1 => "CALL",
+		0xf2 => "CALLCODE",
+		0xf3 => "RETURN",
+		0xf4 => "DELEGATECALL",
+		0xf5 => "CREATE2",
+		0xfa => "STATICCALL",
+		0xfd => "REVERT",
+		0xfe => "INVALID",
+		0xff => "SELFDESTRUCT",
+		_ => return format!("opcode {opcode:#x} not defined"),
+	};
+	name.into()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use evm::ExitRevert;
+
+	#[test]
+	fn opcode_names() {
+		assert_eq!(opcode_name(0x00), "STOP");
+		assert_eq!(opcode_name(0x60), "PUSH1");
+		assert_eq!(opcode_name(0x7f), "PUSH32");
+		assert_eq!(opcode_name(0x8f), "DUP16");
+		assert_eq!(opcode_name(0x90), "SWAP1");
+		assert_eq!(opcode_name(0xa4), "LOG4");
+		assert_eq!(opcode_name(0x0c), "opcode 0xc not defined");
+	}
+
+	#[test]
+	fn decodes_revert_reason() {
+		// Error("nope")
+		let output = hex::decode(concat!(
+			"08c379a0",
+			"0000000000000000000000000000000000000000000000000000000000000020",
+			"0000000000000000000000000000000000000000000000000000000000000004",
+			"6e6f706500000000000000000000000000000000000000000000000000000000",
+		))
+		.unwrap();
+		let reverted = ExitReason::Revert(ExitRevert::Reverted);
+		assert_eq!(revert_reason(&reverted, &output), Some("nope".into()));
+		assert_eq!(revert_reason(&reverted, &output[..40]), None);
+		assert_eq!(
+			exit_error(&reverted),
+			Some("execution reverted".to_string())
+		);
+	}
+}
@@ -26,7 +26,7 @@ use sc_transaction_pool_api::{InPoolTransaction, TransactionPool};
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
 use sp_core::hashing::keccak_256;
-use sp_runtime::traits::Block as BlockT;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
 // Frontier
 use fc_rpc_core::types::*;
 use fp_rpc::EthereumRuntimeRPCApi;
@@ -98,6 +98,14 @@ where
 		.await?
 		{
 			Some(id) => {
+				if let BlockId::Number(number) = id {
+					frontier_backend_client::ensure_not_pruned(
+						client.as_ref(),
+						backend.as_ref(),
+						number,
+					)
+					.await?;
+				}
 				let substrate_hash = client
 					.expect_block_hash_from_id(&id)
 					.map_err(|_| internal_err(format!("Expect block number from id: {id}")))?;
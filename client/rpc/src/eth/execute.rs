@@ -18,7 +18,7 @@
 
 use std::{cell::RefCell, collections::BTreeMap, sync::Arc};
 
-use ethereum_types::{H160, H256, U256};
+use ethereum_types::{Bloom, BloomInput, H160, H256, H64, U256, U64};
 use evm::{ExitError, ExitReason};
 use jsonrpsee::{core::RpcResult, types::error::CALL_EXECUTION_FAILED_CODE};
 use scale_codec::{Decode, Encode};
@@ -30,7 +30,7 @@ use sp_block_builder::BlockBuilder as BlockBuilderApi;
 use sp_blockchain::HeaderBackend;
 use sp_externalities::Extensions;
 use sp_inherents::CreateInherentDataProviders;
-use sp_io::hashing::{blake2_128, twox_128};
+use sp_io::hashing::{blake2_128, keccak_256, twox_128};
 use sp_runtime::{
 	traits::{Block as BlockT, HashingFor},
 	DispatchError, SaturatedConversion,
@@ -81,6 +81,7 @@ where
 		request: TransactionRequest,
 		number_or_hash: Option<BlockNumberOrHash>,
 		state_overrides: Option<BTreeMap<H160, CallStateOverride>>,
+		block_overrides: Option<BlockOverrides>,
 	) -> RpcResult<Bytes> {
 		let TransactionRequest {
 			from,
@@ -141,6 +142,12 @@ where
 		} else {
 			return Err(internal_err("failed to retrieve Runtime Api version"));
 		};
+		if block_overrides.is_some() && api_version < 9 {
+			return Err(internal_err(
+				"block overrides are not supported by the runtime",
+			));
+		}
+		let block_overrides = block_overrides.map(evm_block_overrides);
 
 		let block = if api_version > 1 {
 			api.current_block(substrate_hash)
@@ -307,7 +314,7 @@ where
 					};
 
 					Ok(Bytes(value))
-				} else if api_version == 6 {
+				} else if api_version >= 6 {
 					// Pectra - authorization list support (EIP-7702)
 					let access_list = access_list
 						.unwrap_or_default()
@@ -315,7 +322,7 @@ where
 						.map(|item| (item.address, item.storage_keys))
 						.collect::<Vec<(sp_core::H160, Vec<H256>)>>();
 
-					let encoded_params = Encode::encode(&(
+					let mut encoded_params = Encode::encode(&(
 						&from.unwrap_or_default(),
 						&to,
 						&data,
@@ -328,6 +335,10 @@ where
 						&Some(access_list),
 						&authorization_list,
 					));
+					// Block overrides are the last argument since version 9.
+					if api_version >= 9 {
+						block_overrides.encode_to(&mut encoded_params);
+					}
 					let overlayed_changes = self.create_overrides_overlay(
 						substrate_hash,
 						api_version,
@@ -479,10 +490,42 @@ where
 						.account_code_at(substrate_hash, info.value)
 						.map_err(|err| internal_err(format!("runtime error: {err}")))?;
 					Ok(Bytes(code))
-				} else if api_version == 6 {
+				} else if api_version >= 6 && api_version < 9 {
 					// Pectra EIP-7702 support
 					let access_list = access_list.unwrap_or_default();
 					let authorization_list = authorization_list.unwrap_or_default();
+					#[allow(deprecated)]
+					let info = api.create_before_version_9(
+						substrate_hash,
+						from.unwrap_or_default(),
+						data,
+						value.unwrap_or_default(),
+						gas_limit,
+						max_fee_per_gas,
+						max_priority_fee_per_gas,
+						nonce,
+						false,
+						Some(
+							access_list
+								.into_iter()
+								.map(|item| (item.address, item.storage_keys))
+								.collect(),
+						),
+						Some(authorization_list),
+					)
+					.map_err(|err| internal_err(format!("runtime error: {err}")))?
+					.map_err(|err| internal_err(format!("execution fatal: {err:?}")))?;
+
+					error_on_execution_failure(&info.exit_reason, &[])?;
+
+					let code = api
+						.account_code_at(substrate_hash, info.value)
+						.map_err(|err| internal_err(format!("runtime error: {err}")))?;
+					Ok(Bytes(code))
+				} else if api_version >= 9 {
+					// Block overrides support
+					let access_list = access_list.unwrap_or_default();
+					let authorization_list = authorization_list.unwrap_or_default();
 					let info = api
 						.create(
 							substrate_hash,
@@ -501,6 +544,7 @@ where
 									.collect(),
 							),
 							Some(authorization_list),
+							block_overrides,
 						)
 						.map_err(|err| internal_err(format!("runtime error: {err}")))?
 						.map_err(|err| internal_err(format!("execution fatal: {err:?}")))?;
@@ -522,6 +566,8 @@ where
 		&self,
 		request: TransactionRequest,
 		number_or_hash: Option<BlockNumberOrHash>,
+		state_overrides: Option<BTreeMap<H160, CallStateOverride>>,
+		block_overrides: Option<BlockOverrides>,
 	) -> RpcResult<U256> {
 		let client = Arc::clone(&self.client);
 		let block_data_cache = Arc::clone(&self.block_data_cache);
@@ -561,7 +607,12 @@ where
 			Some(vec) => vec.0.is_empty(),
 		};
 		if is_simple_transfer {
-			if let Some(to) = request.to {
+			// The code of an overridden account is only known to the runtime.
+			if let Some(to) = request.to.filter(|to| {
+				state_overrides
+					.as_ref()
+					.is_none_or(|overrides| !overrides.contains_key(to))
+			}) {
 				let to_code = api
 					.account_code_at(substrate_hash, to)
 					.map_err(|err| internal_err(format!("runtime error: {err}")))?;
@@ -617,10 +668,18 @@ where
 		// Recap the highest gas allowance with account's balance.
 		if let Some(from) = request.from {
 			if fee_cap > U256::zero() {
-				let balance = api
-					.account_basic(substrate_hash, from)
-					.map_err(|err| internal_err(format!("runtime error: {err}")))?
-					.balance;
+				let balance = match state_overrides
+					.as_ref()
+					.and_then(|overrides| overrides.get(&from))
+					.and_then(|state_override| state_override.balance)
+				{
+					Some(balance) => balance,
+					None => {
+						api.account_basic(substrate_hash, from)
+							.map_err(|err| internal_err(format!("runtime error: {err}")))?
+							.balance
+					}
+				};
 				let mut available = balance;
 				if let Some(value) = request.value {
 					if value > available {
@@ -660,6 +719,29 @@ where
 		// of time, the RPC response time would degrade a lot, as the VersionedRuntime needs to be compiled.
 		//
 		// To solve that, and if we introduce historical gas estimation, we'd need to increase that default.
+		let api_version = if let Ok(Some(api_version)) =
+			client
+				.runtime_api()
+				.api_version::<dyn EthereumRuntimeRPCApi<B>>(substrate_hash)
+		{
+			api_version
+		} else {
+			return Err(internal_err("failed to retrieve Runtime Api version"));
+		};
+
+		if state_overrides.is_some() && api_version < 5 {
+			return Err(internal_err(
+				"state overrides are not supported by the runtime",
+			));
+		}
+		if block_overrides.is_some() && api_version < 9 {
+			return Err(internal_err(
+				"block overrides are not supported by the runtime",
+			));
+		}
+
+		let block_overrides = block_overrides.map(evm_block_overrides);
+
 		#[rustfmt::skip]
 			let executable = move |
 				request, gas_limit, api_version, api: sp_api::ApiRef<'_, C::Api>, estimate_mode
@@ -776,7 +858,11 @@ where
 								at: substrate_hash,
 								function: "EthereumRuntimeRPCApi_call",
 								arguments: encoded_params,
-								overlayed_changes: &RefCell::new(Default::default()),
+								overlayed_changes: &RefCell::new(self.create_overrides_overlay(
+									substrate_hash,
+									api_version,
+									state_overrides.clone(),
+								)?),
 								call_context: CallContext::Offchain,
 								recorder: &Some(recorder),
 								extensions: &RefCell::new(exts),
@@ -799,7 +885,7 @@ where
 								.map_err(|err| internal_err(format!("execution fatal: {err:?}")))?;
 
 							(info.exit_reason, info.value, info.used_gas.effective)
-						} else if api_version == 6 {
+						} else if api_version >= 6 {
 							// Pectra - authorization list support (EIP-7702)
 							let access_list = access_list
 								.unwrap_or_default()
@@ -807,7 +893,7 @@ where
 								.map(|item| (item.address, item.storage_keys))
 								.collect::<Vec<(sp_core::H160, Vec<H256>)>>();
 
-							let encoded_params = Encode::encode(&(
+							let mut encoded_params = Encode::encode(&(
 								&from.unwrap_or_default(),
 								&to,
 								&data,
@@ -822,6 +908,10 @@ where
 								),
 								&authorization_list,
 							));
+							// Block overrides are the last argument since version 9.
+							if api_version >= 9 {
+								block_overrides.encode_to(&mut encoded_params);
+							}
 
 							// Proof size recording
 							let recorder: sp_trie::recorder::Recorder<HashingFor<B>> = Default::default();
@@ -833,7 +923,11 @@ where
 								at: substrate_hash,
 								function: "EthereumRuntimeRPCApi_call",
 								arguments: encoded_params,
-								overlayed_changes: &RefCell::new(Default::default()),
+								overlayed_changes: &RefCell::new(self.create_overrides_overlay(
+									substrate_hash,
+									api_version,
+									state_overrides.clone(),
+								)?),
 								call_context: CallContext::Offchain,
 								recorder: &Some(recorder),
 								extensions: &RefCell::new(exts),
@@ -951,7 +1045,11 @@ where
 								at: substrate_hash,
 								function: "EthereumRuntimeRPCApi_create",
 								arguments: encoded_params,
-								overlayed_changes: &RefCell::new(Default::default()),
+								overlayed_changes: &RefCell::new(self.create_overrides_overlay(
+									substrate_hash,
+									api_version,
+									state_overrides.clone(),
+								)?),
 								call_context: CallContext::Offchain,
 								recorder: &Some(recorder),
 								extensions: &RefCell::new(exts),
@@ -974,7 +1072,7 @@ where
 							.map_err(|err| internal_err(format!("execution fatal: {err:?}")))?;
 
 							(info.exit_reason, Vec::new(), info.used_gas.effective)
-						} else if api_version == 6 {
+						} else if api_version >= 6 {
 							// Pectra - authorization list support (EIP-7702)
 							let access_list = access_list
 								.unwrap_or_default()
@@ -982,7 +1080,7 @@ where
 								.map(|item| (item.address, item.storage_keys))
 								.collect::<Vec<(sp_core::H160, Vec<H256>)>>();
 
-							let encoded_params = Encode::encode(&(
+							let mut encoded_params = Encode::encode(&(
 								&from.unwrap_or_default(),
 								&data,
 								&value.unwrap_or_default(),
@@ -996,6 +1094,10 @@ where
 								),
 								&authorization_list,
 							));
+							// Block overrides are the last argument since version 9.
+							if api_version >= 9 {
+								block_overrides.encode_to(&mut encoded_params);
+							}
 
 							// Enable proof size recording
 							let recorder: sp_trie::recorder::Recorder<HashingFor<B>> = Default::default();
@@ -1007,7 +1109,11 @@ where
 								at: substrate_hash,
 								function: "EthereumRuntimeRPCApi_create",
 								arguments: encoded_params,
-								overlayed_changes: &RefCell::new(Default::default()),
+								overlayed_changes: &RefCell::new(self.create_overrides_overlay(
+									substrate_hash,
+									api_version,
+									state_overrides.clone(),
+								)?),
 								call_context: CallContext::Offchain,
 								recorder: &Some(recorder),
 								extensions: &RefCell::new(exts),
@@ -1041,16 +1147,6 @@ where
 					used_gas,
 				})
 			};
-		let api_version = if let Ok(Some(api_version)) =
-			client
-				.runtime_api()
-				.api_version::<dyn EthereumRuntimeRPCApi<B>>(substrate_hash)
-		{
-			api_version
-		} else {
-			return Err(internal_err("failed to retrieve Runtime Api version"));
-		};
-
 		// Verify that the transaction succeed with the highest capacity
 		let cap = highest;
 		let estimate_mode = !cfg!(feature = "rpc-binary-search-estimate");
@@ -1160,6 +1256,462 @@ where
 		}
 	}
 
+	pub async fn create_access_list(
+		&self,
+		request: TransactionRequest,
+		number_or_hash: Option<BlockNumberOrHash>,
+	) -> RpcResult<AccessListResult> {
+		// Upper bound of the executions needed for the access list to settle.
+		const MAX_ITERATIONS: usize = 16;
+
+		let TransactionRequest {
+			from,
+			to,
+			gas_price,
+			max_fee_per_gas,
+			max_priority_fee_per_gas,
+			gas,
+			value,
+			data,
+			nonce,
+			access_list,
+			authorization_list,
+			..
+		} = request;
+		let details = fee_details(gas_price, max_fee_per_gas, max_priority_fee_per_gas)?;
+
+		let substrate_hash = match frontier_backend_client::native_block_id::<B, C>(
+			self.client.as_ref(),
+			self.backend.as_ref(),
+			number_or_hash,
+		)
+		.await?
+		{
+			Some(id) => self
+				.client
+				.expect_block_hash_from_id(&id)
+				.map_err(|_| crate::err(CALL_EXECUTION_FAILED_CODE, "header not found", None))?,
+			// Not mapped in the db, assume pending and execute on top of the best block.
+			None => self.client.info().best_hash,
+		};
+
+		let api_version = if let Ok(Some(api_version)) =
+			self.client
+				.runtime_api()
+				.api_version::<dyn EthereumRuntimeRPCApi<B>>(substrate_hash)
+		{
+			api_version
+		} else {
+			return Err(internal_err("failed to retrieve Runtime Api version"));
+		};
+		if api_version < 7 {
+			return Err(internal_err(
+				"eth_createAccessList is not supported by the runtime",
+			));
+		}
+
+		let block_gas_limit = self
+			.block_data_cache
+			.current_block(substrate_hash)
+			.await
+			.ok_or_else(|| internal_err("block unavailable, cannot query gas limit"))?
+			.header
+			.gas_limit;
+		let max_gas_limit = block_gas_limit * self.execute_gas_limit_multiplier;
+		let gas_limit = match gas {
+			Some(amount) => {
+				if amount > max_gas_limit {
+					return Err(internal_err(format!(
+						"provided gas limit is too high (can be up to {}x the block gas limit)",
+						self.execute_gas_limit_multiplier
+					)));
+				}
+				amount
+			}
+			None => max_gas_limit,
+		};
+
+		let data = data.into_bytes().map(|d| d.into_vec()).unwrap_or_default();
+		let mut access_list = access_list
+			.unwrap_or_default()
+			.into_iter()
+			.map(|item| (item.address, item.storage_keys))
+			.collect::<Vec<(H160, Vec<H256>)>>();
+
+		// Execute with the accessed state of the previous execution as access list until it no
+		// longer changes, as the accesses may depend on the gas left.
+		for _ in 0..MAX_ITERATIONS {
+			// A new ApiRef instance is used per execution so that the state changes of an
+			// execution do not affect the next one.
+			let (info, accessed) = self
+				.client
+				.runtime_api()
+				.create_access_list(
+					substrate_hash,
+					from.unwrap_or_default(),
+					to,
+					data.clone(),
+					value.unwrap_or_default(),
+					gas_limit,
+					details.max_fee_per_gas,
+					details.max_priority_fee_per_gas,
+					nonce,
+					Some(access_list.clone()),
+					authorization_list.clone(),
+				)
+				.map_err(|err| internal_err(format!("runtime error: {err}")))?
+				.map_err(|err| internal_err(format!("execution fatal: {err:?}")))?;
+
+			if accessed == access_list {
+				let error = error_on_execution_failure(&info.exit_reason, &info.value)
+					.err()
+					.map(|err| err.message().to_string());
+				return Ok(AccessListResult {
+					access_list: access_list
+						.into_iter()
+						.map(|(address, storage_keys)| ethereum::AccessListItem {
+							address,
+							storage_keys,
+						})
+						.collect(),
+					gas_used: info.used_gas.effective,
+					error,
+				});
+			}
+			access_list = accessed;
+		}
+
+		Err(internal_err("access list did not settle"))
+	}
+
+	/// Simulates the calls of `payload` in virtual blocks built on top of given block, with an
+	/// empty block in place of each block number skipped by `payload`.
+	///
+	/// All the calls share a single overlay, so the state changes of a call are seen by the
+	/// following ones. Simulated calls are not signed transactions: their hashes are derived
+	/// from their position, and the state and transactions roots of the virtual blocks are left
+	/// empty.
+	pub async fn simulate(
+		&self,
+		payload: SimulatePayload,
+		number_or_hash: Option<BlockNumberOrHash>,
+	) -> RpcResult<Vec<SimulatedBlock>> {
+		// Upper bound of the virtual blocks of a simulation.
+		const MAX_BLOCKS: usize = 256;
+		// Time between two virtual blocks whose timestamp is not overridden, in seconds.
+		const BLOCK_TIME: u64 = 12;
+		// Error code of calls that reverted.
+		const REVERTED_CODE: i32 = 3;
+		// Error code of calls that failed in the EVM.
+		const VM_ERROR_CODE: i32 = -32015;
+
+		let SimulatePayload {
+			block_state_calls,
+			trace_transfers,
+			validation,
+			return_full_transactions,
+		} = payload;
+		if trace_transfers {
+			return Err(internal_err("traceTransfers is not supported"));
+		}
+		if return_full_transactions {
+			return Err(internal_err("returnFullTransactions is not supported"));
+		}
+		let substrate_hash = match frontier_backend_client::native_block_id::<B, C>(
+			self.client.as_ref(),
+			self.backend.as_ref(),
+			number_or_hash,
+		)
+		.await?
+		{
+			Some(id) => self
+				.client
+				.expect_block_hash_from_id(&id)
+				.map_err(|_| crate::err(CALL_EXECUTION_FAILED_CODE, "header not found", None))?,
+			// Not mapped in the db, assume pending and execute on top of the best block.
+			None => self.client.info().best_hash,
+		};
+
+		let (api_version, base_fee) = {
+			let api = self.client.runtime_api();
+			let api_version = if let Ok(Some(api_version)) =
+				api.api_version::<dyn EthereumRuntimeRPCApi<B>>(substrate_hash)
+			{
+				api_version
+			} else {
+				return Err(internal_err("failed to retrieve Runtime Api version"));
+			};
+			if api_version < 8 {
+				return Err(internal_err(
+					"eth_simulateV1 is not supported by the runtime",
+				));
+			}
+			let base_fee = api
+				.gas_price(substrate_hash)
+				.map_err(|err| internal_err(format!("runtime error: {err}")))?;
+			(api_version, base_fee)
+		};
+
+		let parent = self
+			.block_data_cache
+			.current_block(substrate_hash)
+			.await
+			.ok_or_else(|| internal_err("block unavailable, cannot simulate"))?
+			.header;
+		let mut parent_hash = parent.hash();
+		let mut number = parent.number;
+		let mut timestamp = parent.timestamp / 1000;
+		let block_state_calls = fill_simulated_blocks(block_state_calls, number, MAX_BLOCKS)?;
+
+		let overlayed_changes = RefCell::new(OverlayedChanges::default());
+		let mut blocks = Vec::with_capacity(block_state_calls.len());
+		// Hashes of the blocks simulated so far, returned by `BLOCKHASH`.
+		let mut block_hashes = Vec::with_capacity(block_state_calls.len());
+		for SimulateBlock {
+			block_overrides,
+			state_overrides,
+			calls,
+		} in block_state_calls
+		{
+			let block_overrides = block_overrides.unwrap_or_default();
+			let block_number = block_overrides.number.unwrap_or(number + 1);
+			let block_timestamp = match block_overrides.time {
+				Some(time) => time.saturated_into(),
+				None => timestamp + BLOCK_TIME,
+			};
+			if block_timestamp <= timestamp {
+				return Err(internal_err("block timestamps must be increasing"));
+			}
+			let gas_limit = block_overrides.gas_limit.unwrap_or(parent.gas_limit);
+			let beneficiary = block_overrides.coinbase.unwrap_or(parent.beneficiary);
+			// Without validation calls are free, as with `eth_call`.
+			let base_fee_override = block_overrides.base_fee.or((!validation).then(U256::zero));
+			let evm_block_overrides = fp_evm::BlockOverrides {
+				number: Some(block_number),
+				timestamp: Some(U256::from(block_timestamp)),
+				coinbase: Some(beneficiary),
+				gas_limit: Some(gas_limit),
+				base_fee: base_fee_override,
+				random: block_overrides.random,
+				block_hashes: block_hashes.clone(),
+			};
+
+			apply_state_overrides::<B, C, BE, EC::RuntimeStorageOverride>(
+				self.client.as_ref(),
+				&mut overlayed_changes.borrow_mut(),
+				substrate_hash,
+				api_version,
+				state_overrides,
+			)?;
+
+			let mut gas_used = U256::zero();
+			let mut logs_bloom = Bloom::default();
+			let mut transaction_hashes = Vec::with_capacity(calls.len());
+			let mut receipts = Vec::with_capacity(calls.len());
+			let mut results = Vec::with_capacity(calls.len());
+			for (index, request) in calls.into_iter().enumerate() {
+				let TransactionRequest {
+					from,
+					to,
+					gas_price,
+					max_fee_per_gas,
+					max_priority_fee_per_gas,
+					gas,
+					value,
+					data,
+					nonce,
+					access_list,
+					authorization_list,
+					..
+				} = request;
+				let details = fee_details(gas_price, max_fee_per_gas, max_priority_fee_per_gas)?;
+				let from = from.unwrap_or_default();
+
+				let gas_left = gas_limit.saturating_sub(gas_used);
+				let call_gas_limit = match gas {
+					Some(gas) if gas > gas_left => {
+						return Err(internal_err("block gas limit reached"));
+					}
+					Some(gas) => gas,
+					None => gas_left,
+				};
+
+				let encoded_params = Encode::encode(&(
+					&from,
+					&to,
+					&data.into_bytes().map(|d| d.into_vec()).unwrap_or_default(),
+					&value.unwrap_or_default(),
+					&call_gas_limit,
+					&details.max_fee_per_gas,
+					&details.max_priority_fee_per_gas,
+					&nonce,
+					&access_list.map(|access_list| {
+						access_list
+							.into_iter()
+							.map(|item| (item.address, item.storage_keys))
+							.collect::<Vec<(H160, Vec<H256>)>>()
+					}),
+					&authorization_list,
+					&Some(evm_block_overrides.clone()),
+					&validation,
+				));
+
+				// Enable proof size recording
+				let recorder: sp_trie::recorder::Recorder<HashingFor<B>> = Default::default();
+				let ext = sp_trie::proof_size_extension::ProofSizeExt::new(recorder.clone());
+				let mut exts = Extensions::new();
+				exts.register(ext);
+
+				let params = CallApiAtParams {
+					at: substrate_hash,
+					function: "EthereumRuntimeRPCApi_simulate_call",
+					arguments: encoded_params,
+					overlayed_changes: &overlayed_changes,
+					call_context: CallContext::Offchain,
+					recorder: &Some(recorder),
+					extensions: &RefCell::new(exts),
+				};
+				let info = self
+					.client
+					.call_api_at(params)
+					.and_then(|r| {
+						<Result<ExecutionInfoV2<Vec<u8>>, DispatchError> as Decode>::decode(
+							&mut &r[..],
+						)
+						.map_err(|error| sp_api::ApiError::FailedToDecodeReturnValue {
+							function: "EthereumRuntimeRPCApi_simulate_call",
+							error,
+							raw: r,
+						})
+					})
+					.map_err(|err| internal_err(format!("runtime error: {err}")))?
+					.map_err(|err| internal_err(format!("execution fatal: {err:?}")))?;
+
+				let transaction_hash =
+					H256::from(keccak_256(&(block_number, index as u64, from).encode()));
+				let succeeded = matches!(info.exit_reason, ExitReason::Succeed(_));
+				gas_used = gas_used.saturating_add(info.used_gas.effective);
+				let mut call_logs_bloom = Bloom::default();
+				for log in &info.logs {
+					call_logs_bloom.accrue(BloomInput::Raw(&log.address[..]));
+					for topic in &log.topics {
+						call_logs_bloom.accrue(BloomInput::Raw(&topic[..]));
+					}
+				}
+				logs_bloom.accrue_bloom(&call_logs_bloom);
+				receipts.push(ethereum::ReceiptV4::EIP1559(ethereum::EIP658ReceiptData {
+					status_code: succeeded as u8,
+					used_gas: gas_used,
+					logs_bloom: call_logs_bloom,
+					logs: info.logs.clone(),
+				}));
+
+				let error = error_on_execution_failure(&info.exit_reason, &info.value)
+					.err()
+					.map(|err| SimulateCallError {
+						code: match info.exit_reason {
+							ExitReason::Revert(_) => REVERTED_CODE,
+							_ => VM_ERROR_CODE,
+						},
+						message: err.message().to_string(),
+						data: matches!(info.exit_reason, ExitReason::Revert(_))
+							.then(|| Bytes(info.value.clone())),
+					});
+				results.push(SimulateCallResult {
+					return_data: Bytes(info.value),
+					logs: info
+						.logs
+						.into_iter()
+						.map(|log| Log {
+							address: log.address,
+							topics: log.topics,
+							data: Bytes(log.data),
+							block_hash: None,
+							block_number: Some(block_number),
+							transaction_hash: Some(transaction_hash),
+							transaction_index: Some(U256::from(index)),
+							log_index: None,
+							transaction_log_index: None,
+							removed: false,
+						})
+						.collect(),
+					gas_used: info.used_gas.effective,
+					status: U64::from(succeeded as u8),
+					error,
+				});
+				transaction_hashes.push(transaction_hash);
+			}
+
+			let partial_header = ethereum::PartialHeader {
+				parent_hash,
+				beneficiary,
+				state_root: H256::default(),
+				receipts_root: ethereum::util::ordered_trie_root(
+					receipts.iter().map(ethereum::EnvelopedEncodable::encode),
+				),
+				logs_bloom,
+				difficulty: U256::zero(),
+				number: block_number,
+				gas_limit,
+				gas_used,
+				timestamp: block_timestamp.saturating_mul(1000),
+				extra_data: Vec::new(),
+				mix_hash: block_overrides.random.unwrap_or_default(),
+				nonce: H64::default(),
+			};
+			let block = ethereum::BlockV3::new(partial_header, vec![], vec![]);
+			let hash = block.header.hash();
+
+			// Locate the logs now that the block hash is known.
+			let mut log_index = 0;
+			for result in &mut results {
+				for (transaction_log_index, log) in result.logs.iter_mut().enumerate() {
+					log.block_hash = Some(hash);
+					log.log_index = Some(U256::from(log_index));
+					log.transaction_log_index = Some(U256::from(transaction_log_index));
+					log_index += 1;
+				}
+			}
+
+			blocks.push(SimulatedBlock {
+				inner: Block {
+					header: Header {
+						hash: Some(hash),
+						parent_hash,
+						uncles_hash: block.header.ommers_hash,
+						author: beneficiary,
+						miner: Some(beneficiary),
+						state_root: block.header.state_root,
+						transactions_root: block.header.transactions_root,
+						receipts_root: block.header.receipts_root,
+						number: Some(block_number),
+						gas_used,
+						gas_limit,
+						extra_data: Bytes(Vec::new()),
+						logs_bloom,
+						timestamp: U256::from(block_timestamp),
+						difficulty: U256::zero(),
+						nonce: Some(block.header.nonce),
+						size: Some(U256::from(rlp::encode(&block.header).len() as u32)),
+					},
+					total_difficulty: Some(U256::zero()),
+					uncles: vec![],
+					transactions: BlockTransactions::Hashes(transaction_hashes),
+					size: Some(U256::from(rlp::encode(&block).len() as u32)),
+					base_fee_per_gas: Some(base_fee_override.unwrap_or(base_fee)),
+				},
+				calls: results,
+			});
+
+			block_hashes.push((block_number, hash));
+			parent_hash = hash;
+			number = block_number;
+			timestamp = block_timestamp;
+		}
+
+		Ok(blocks)
+	}
+
 	/// Given an address mapped `CallStateOverride`, creates `OverlayedChanges` to be used for
 	/// `CallApiAt` eth_call.
 	fn create_overrides_overlay(
@@ -1168,77 +1720,175 @@ where
 		api_version: u32,
 		state_overrides: Option<BTreeMap<H160, CallStateOverride>>,
 	) -> RpcResult<OverlayedChanges<HashingFor<B>>> {
-		let mut overlayed_changes = OverlayedChanges::default();
-		if let Some(state_overrides) = state_overrides {
-			for (address, state_override) in state_overrides {
-				if EC::RuntimeStorageOverride::is_enabled() {
-					EC::RuntimeStorageOverride::set_overlayed_changes(
-						self.client.as_ref(),
-						&mut overlayed_changes,
-						block_hash,
-						api_version,
-						address,
-						state_override.balance,
-						state_override.nonce,
-					);
-				} else if state_override.balance.is_some() || state_override.nonce.is_some() {
-					return Err(internal_err(
-						"state override unsupported for balance and nonce",
-					));
-				}
+		create_overrides_overlay::<B, C, BE, EC::RuntimeStorageOverride>(
+			self.client.as_ref(),
+			block_hash,
+			api_version,
+			state_overrides,
+		)
+	}
+}
 
-				if let Some(code) = &state_override.code {
-					let mut key = [twox_128(PALLET_EVM), twox_128(EVM_ACCOUNT_CODES)]
-						.concat()
-						.to_vec();
-					key.extend(blake2_128(address.as_bytes()));
-					key.extend(address.as_bytes());
-					let encoded_code = code.clone().into_vec().encode();
-					overlayed_changes.set_storage(key.clone(), Some(encoded_code));
-				}
+/// Given an address mapped `CallStateOverride`, creates `OverlayedChanges` to be used for
+/// `CallApiAt` calls.
+pub(crate) fn create_overrides_overlay<B, C, BE, SO>(
+	client: &C,
+	block_hash: B::Hash,
+	api_version: u32,
+	state_overrides: Option<BTreeMap<H160, CallStateOverride>>,
+) -> RpcResult<OverlayedChanges<HashingFor<B>>>
+where
+	B: BlockT,
+	C: StorageProvider<B, BE>,
+	BE: Backend<B>,
+	SO: RuntimeStorageOverride<B, C>,
+{
+	let mut overlayed_changes = OverlayedChanges::default();
+	apply_state_overrides::<B, C, BE, SO>(
+		client,
+		&mut overlayed_changes,
+		block_hash,
+		api_version,
+		state_overrides,
+	)?;
+	Ok(overlayed_changes)
+}
 
-				let mut account_storage_key =
-					[twox_128(PALLET_EVM), twox_128(EVM_ACCOUNT_STORAGES)]
-						.concat()
-						.to_vec();
-				account_storage_key.extend(blake2_128(address.as_bytes()));
-				account_storage_key.extend(address.as_bytes());
-
-				// Use `state` first. If `stateDiff` is also present, it resolves consistently
-				if let Some(state) = &state_override.state {
-					// clear all storage
-					if let Ok(all_keys) = self.client.storage_keys(
-						block_hash,
-						Some(&sp_storage::StorageKey(account_storage_key.clone())),
-						None,
-					) {
-						for key in all_keys {
-							overlayed_changes.set_storage(key.0, None);
-						}
-					}
-					// set provided storage
-					for (k, v) in state {
-						let mut slot_key = account_storage_key.clone();
-						slot_key.extend(blake2_128(k.as_bytes()));
-						slot_key.extend(k.as_bytes());
+/// Writes an address mapped `CallStateOverride` into existing `OverlayedChanges`.
+fn apply_state_overrides<B, C, BE, SO>(
+	client: &C,
+	overlayed_changes: &mut OverlayedChanges<HashingFor<B>>,
+	block_hash: B::Hash,
+	api_version: u32,
+	state_overrides: Option<BTreeMap<H160, CallStateOverride>>,
+) -> RpcResult<()>
+where
+	B: BlockT,
+	C: StorageProvider<B, BE>,
+	BE: Backend<B>,
+	SO: RuntimeStorageOverride<B, C>,
+{
+	if let Some(state_overrides) = state_overrides {
+		for (address, state_override) in state_overrides {
+			if SO::is_enabled() {
+				SO::set_overlayed_changes(
+					client,
+					overlayed_changes,
+					block_hash,
+					api_version,
+					address,
+					state_override.balance,
+					state_override.nonce,
+				);
+			} else if state_override.balance.is_some() || state_override.nonce.is_some() {
+				return Err(internal_err(
+					"state override unsupported for balance and nonce",
+				));
+			}
 
-						overlayed_changes.set_storage(slot_key, Some(v.as_bytes().to_owned()));
+			if let Some(code) = &state_override.code {
+				let mut key = [twox_128(PALLET_EVM), twox_128(EVM_ACCOUNT_CODES)]
+					.concat()
+					.to_vec();
+				key.extend(blake2_128(address.as_bytes()));
+				key.extend(address.as_bytes());
+				let encoded_code = code.clone().into_vec().encode();
+				overlayed_changes.set_storage(key.clone(), Some(encoded_code));
+			}
+
+			let mut account_storage_key = [twox_128(PALLET_EVM), twox_128(EVM_ACCOUNT_STORAGES)]
+				.concat()
+				.to_vec();
+			account_storage_key.extend(blake2_128(address.as_bytes()));
+			account_storage_key.extend(address.as_bytes());
+
+			// Use `state` first. If `stateDiff` is also present, it resolves consistently
+			if let Some(state) = &state_override.state {
+				// clear all storage
+				if let Ok(all_keys) = client.storage_keys(
+					block_hash,
+					Some(&sp_storage::StorageKey(account_storage_key.clone())),
+					None,
+				) {
+					for key in all_keys {
+						overlayed_changes.set_storage(key.0, None);
 					}
 				}
+				// set provided storage
+				for (k, v) in state {
+					let mut slot_key = account_storage_key.clone();
+					slot_key.extend(blake2_128(k.as_bytes()));
+					slot_key.extend(k.as_bytes());
+
+					overlayed_changes.set_storage(slot_key, Some(v.as_bytes().to_owned()));
+				}
+			}
 
-				if let Some(state_diff) = &state_override.state_diff {
-					for (k, v) in state_diff {
-						let mut slot_key = account_storage_key.clone();
-						slot_key.extend(blake2_128(k.as_bytes()));
-						slot_key.extend(k.as_bytes());
+			if let Some(state_diff) = &state_override.state_diff {
+				for (k, v) in state_diff {
+					let mut slot_key = account_storage_key.clone();
+					slot_key.extend(blake2_128(k.as_bytes()));
+					slot_key.extend(k.as_bytes());
 
-						overlayed_changes.set_storage(slot_key, Some(v.as_bytes().to_owned()));
-					}
+					overlayed_changes.set_storage(slot_key, Some(v.as_bytes().to_owned()));
 				}
 			}
 		}
+	}
+
+	Ok(())
+}
+
+/// Inserts an empty block wherever the numbers of the `eth_simulateV1` blocks skip ahead, as geth
+/// does, so that the simulated blocks follow each other from `parent_number`. The inserted blocks
+/// count towards `max_blocks`.
+fn fill_simulated_blocks(
+	block_state_calls: Vec<SimulateBlock>,
+	parent_number: U256,
+	max_blocks: usize,
+) -> RpcResult<Vec<SimulateBlock>> {
+	let mut blocks = Vec::with_capacity(block_state_calls.len());
+	let mut number = parent_number;
+	for block in block_state_calls {
+		let block_number = block
+			.block_overrides
+			.as_ref()
+			.and_then(|overrides| overrides.number)
+			.unwrap_or(number.saturating_add(U256::one()));
+		if block_number <= number {
+			return Err(internal_err("block numbers must be increasing"));
+		}
+		if block_number - number > U256::from(max_blocks - blocks.len()) {
+			return Err(internal_err(format!(
+				"too many blocks (can be up to {max_blocks})"
+			)));
+		}
+		while number + 1 < block_number {
+			number += U256::one();
+			blocks.push(SimulateBlock {
+				block_overrides: Some(BlockOverrides {
+					number: Some(number),
+					..Default::default()
+				}),
+				..Default::default()
+			});
+		}
+		blocks.push(block);
+		number = block_number;
+	}
+	Ok(blocks)
+}
 
-		Ok(overlayed_changes)
+/// Converts RPC `BlockOverrides` into the block environment overrides of the runtime.
+pub(crate) fn evm_block_overrides(overrides: BlockOverrides) -> fp_evm::BlockOverrides {
+	fp_evm::BlockOverrides {
+		number: overrides.number,
+		timestamp: overrides.time,
+		coinbase: overrides.coinbase,
+		gas_limit: overrides.gas_limit,
+		base_fee: overrides.base_fee,
+		random: overrides.random,
+		block_hashes: Vec::new(),
 	}
 }
 
@@ -1283,14 +1933,14 @@ pub fn error_on_execution_failure(reason: &ExitReason, data: &[u8]) -> RpcResult
 	}
 }
 
-struct FeeDetails {
-	gas_price: Option<U256>,
-	max_fee_per_gas: Option<U256>,
-	max_priority_fee_per_gas: Option<U256>,
-	fee_cap: U256,
+pub(crate) struct FeeDetails {
+	pub(crate) gas_price: Option<U256>,
+	pub(crate) max_fee_per_gas: Option<U256>,
+	pub(crate) max_priority_fee_per_gas: Option<U256>,
+	pub(crate) fee_cap: U256,
 }
 
-fn fee_details(
+pub(crate) fn fee_details(
 	request_gas_price: Option<U256>,
 	request_max_fee_per_gas: Option<U256>,
 	request_priority_fee_per_gas: Option<U256>,
@@ -1334,3 +1984,67 @@ fn fee_details(
 		}),
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn block(number: Option<u64>) -> SimulateBlock {
+		SimulateBlock {
+			block_overrides: Some(BlockOverrides {
+				number: number.map(U256::from),
+				..Default::default()
+			}),
+			..Default::default()
+		}
+	}
+
+	fn numbers(blocks: &[SimulateBlock]) -> Vec<Option<U256>> {
+		blocks
+			.iter()
+			.map(|block| block.block_overrides.as_ref().and_then(|o| o.number))
+			.collect()
+	}
+
+	#[test]
+	fn fill_simulated_blocks_inserts_empty_blocks() {
+		let blocks = fill_simulated_blocks(
+			vec![block(Some(12)), block(None), block(Some(15))],
+			U256::from(10),
+			256,
+		)
+		.unwrap();
+		assert_eq!(
+			numbers(&blocks),
+			vec![
+				Some(U256::from(11)),
+				Some(U256::from(12)),
+				None,
+				Some(U256::from(14)),
+				Some(U256::from(15)),
+			]
+		);
+		assert!(blocks[0].calls.is_empty() && blocks[3].calls.is_empty());
+	}
+
+	#[test]
+	fn fill_simulated_blocks_counts_the_empty_blocks() {
+		assert!(fill_simulated_blocks(vec![block(Some(266))], U256::from(10), 256).is_ok());
+		assert!(fill_simulated_blocks(vec![block(Some(267))], U256::from(10), 256).is_err());
+		assert!(fill_simulated_blocks(
+			vec![block(None), block(Some(u64::MAX))],
+			U256::from(10),
+			256
+		)
+		.is_err());
+	}
+
+	#[test]
+	fn fill_simulated_blocks_rejects_decreasing_numbers() {
+		assert!(fill_simulated_blocks(vec![block(Some(10))], U256::from(10), 256).is_err());
+		assert!(
+			fill_simulated_blocks(vec![block(Some(12)), block(Some(12))], U256::from(10), 256)
+				.is_err()
+		);
+	}
+}
@@ -339,28 +339,16 @@ where
 				from_number,
 				current_number,
 			} => {
-				let logs = if backend.is_indexed() {
-					filter_range_logs_indexed(
-						client.as_ref(),
-						backend.log_indexer(),
-						&block_data_cache,
-						max_past_logs,
-						&filter,
-						from_number,
-						current_number,
-					)
-					.await?
-				} else {
-					filter_range_logs(
-						client.as_ref(),
-						&block_data_cache,
-						max_past_logs,
-						&filter,
-						from_number,
-						current_number,
-					)
-					.await?
-				};
+				let logs = filter_range_logs_with_backend(
+					client.as_ref(),
+					backend.as_ref(),
+					&block_data_cache,
+					max_past_logs,
+					&filter,
+					from_number,
+					current_number,
+				)
+				.await?;
 
 				Ok(FilterChanges::Logs(logs))
 			}
@@ -414,29 +402,24 @@ where
 			.map(|s| s.unique_saturated_into())
 			.unwrap_or(best_number);
 
-		let logs = if backend.is_indexed() {
-			filter_range_logs_indexed(
-				client.as_ref(),
-				backend.log_indexer(),
-				&block_data_cache,
-				max_past_logs,
-				&filter,
-				from_number,
-				current_number,
-			)
-			.await?
-		} else {
-			filter_range_logs(
+		if backend.is_indexed() {
+			frontier_backend_client::ensure_not_pruned(
 				client.as_ref(),
-				&block_data_cache,
-				max_past_logs,
-				&filter,
+				backend.as_ref(),
 				from_number,
-				current_number,
 			)
-			.await?
-		};
-		Ok(logs)
+			.await?;
+		}
+		filter_range_logs_with_backend(
+			client.as_ref(),
+			backend.as_ref(),
+			&block_data_cache,
+			max_past_logs,
+			&filter,
+			from_number,
+			current_number,
+		)
+		.await
 	}
 
 	fn uninstall_filter(&self, index: Index) -> RpcResult<bool> {
@@ -508,33 +491,92 @@ where
 				)));
 			}
 
-			logs = if backend.is_indexed() {
-				filter_range_logs_indexed(
-					client.as_ref(),
-					backend.log_indexer(),
-					&block_data_cache,
-					max_past_logs,
-					&filter,
-					from_number,
-					current_number,
-				)
-				.await?
-			} else {
-				filter_range_logs(
+			if backend.is_indexed() {
+				frontier_backend_client::ensure_not_pruned(
 					client.as_ref(),
-					&block_data_cache,
-					max_past_logs,
-					&filter,
+					backend.as_ref(),
 					from_number,
-					current_number,
 				)
-				.await?
-			};
+				.await?;
+			}
+			logs = filter_range_logs_with_backend(
+				client.as_ref(),
+				backend.as_ref(),
+				&block_data_cache,
+				max_past_logs,
+				&filter,
+				from_number,
+				current_number,
+			)
+			.await?;
 		}
 		Ok(logs)
 	}
 }
 
+/// Filter the logs of the range with the log index of the backend, scanning the blocks below
+/// the oldest indexed block instead.
+async fn filter_range_logs_with_backend<B, C, BE>(
+	client: &C,
+	backend: &dyn fc_api::Backend<B>,
+	block_data_cache: &EthBlockDataCacheTask<B>,
+	max_past_logs: u32,
+	filter: &Filter,
+	from: NumberFor<B>,
+	to: NumberFor<B>,
+) -> RpcResult<Vec<Log>>
+where
+	B: BlockT,
+	C: ProvideRuntimeApi<B>,
+	C::Api: EthereumRuntimeRPCApi<B>,
+	C: HeaderBackend<B> + StorageProvider<B, BE> + 'static,
+	BE: Backend<B> + 'static,
+{
+	let first_indexed_block = if backend.is_indexed() {
+		backend.log_indexer().first_indexed_block()
+	} else {
+		None
+	};
+	let Some(first_indexed_block) = first_indexed_block else {
+		return filter_range_logs(client, block_data_cache, max_past_logs, filter, from, to).await;
+	};
+	let first_indexed_block: NumberFor<B> = first_indexed_block.unique_saturated_into();
+
+	let mut logs = Vec::new();
+	if from < first_indexed_block {
+		let scanned_to = to.min(first_indexed_block.saturating_sub(One::one()));
+		logs = filter_range_logs(
+			client,
+			block_data_cache,
+			max_past_logs,
+			filter,
+			from,
+			scanned_to,
+		)
+		.await?;
+	}
+	if to >= first_indexed_block {
+		logs.extend(
+			filter_range_logs_indexed(
+				client,
+				backend.log_indexer(),
+				block_data_cache,
+				max_past_logs,
+				filter,
+				from.max(first_indexed_block),
+				to,
+			)
+			.await?,
+		);
+		if logs.len() as u32 > max_past_logs {
+			return Err(internal_err(format!(
+				"query returned more than {max_past_logs} results"
+			)));
+		}
+	}
+	Ok(logs)
+}
+
 async fn filter_range_logs_indexed<B, C, BE>(
 	_client: &C,
 	backend: &dyn fc_api::LogIndexerBackend<B>,
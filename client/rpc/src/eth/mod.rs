@@ -18,12 +18,13 @@
 
 mod block;
 mod client;
-mod execute;
+pub(crate) mod execute;
 mod fee;
 pub(crate) mod filter;
 pub mod format;
 mod mining;
 pub mod pending;
+mod sign;
 mod state;
 mod submit;
 mod transaction;
@@ -34,7 +35,10 @@ use ethereum::{BlockV3 as EthereumBlock, TransactionV3 as EthereumTransaction};
 use ethereum_types::{H160, H256, H64, U256, U64};
 use jsonrpsee::core::{async_trait, RpcResult};
 // Substrate
-use sc_client_api::backend::{Backend, StorageProvider};
+use sc_client_api::{
+	backend::{Backend, StorageProvider},
+	ProofProvider,
+};
 use sc_network_sync::SyncingService;
 use sc_transaction_pool_api::TransactionPool;
 use sp_api::{CallApiAt, ProvideRuntimeApi};
@@ -42,7 +46,10 @@ use sp_block_builder::BlockBuilder as BlockBuilderApi;
 use sp_blockchain::HeaderBackend;
 use sp_core::hashing::keccak_256;
 use sp_inherents::CreateInherentDataProviders;
-use sp_runtime::traits::{Block as BlockT, UniqueSaturatedInto};
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, UniqueSaturatedInto},
+};
 // Frontier
 use fc_rpc_core::{types::*, EthApiServer};
 use fc_storage::StorageOverride;
@@ -151,6 +158,15 @@ where
 			Some(id) => id,
 			None => return Ok(BlockInfo::default()),
 		};
+		// The mapping of the blocks found by hash is not pruned.
+		if let BlockId::Number(number) = id {
+			frontier_backend_client::ensure_not_pruned(
+				self.client.as_ref(),
+				self.backend.as_ref(),
+				number,
+			)
+			.await?;
+		}
 
 		let substrate_hash = self
 			.client
@@ -294,7 +310,7 @@ where
 	B: BlockT,
 	C: CallApiAt<B> + ProvideRuntimeApi<B>,
 	C::Api: BlockBuilderApi<B> + ConvertTransactionRuntimeApi<B> + EthereumRuntimeRPCApi<B>,
-	C: HeaderBackend<B> + StorageProvider<B, BE> + 'static,
+	C: HeaderBackend<B> + ProofProvider<B> + StorageProvider<B, BE> + 'static,
 	BE: Backend<B> + 'static,
 	P: TransactionPool<Block = B, Hash = B::Hash> + 'static,
 	CT: ConvertTransaction<<B as BlockT>::Extrinsic> + Send + Sync + 'static,
@@ -458,6 +474,15 @@ where
 		self.code_at(address, number_or_hash).await
 	}
 
+	async fn proof(
+		&self,
+		address: H160,
+		storage_keys: Vec<H256>,
+		number_or_hash: Option<BlockNumberOrHash>,
+	) -> RpcResult<EthAccount> {
+		self.proof(address, storage_keys, number_or_hash).await
+	}
+
 	// ########################################################################
 	// Execute
 	// ########################################################################
@@ -467,16 +492,37 @@ where
 		request: TransactionRequest,
 		number_or_hash: Option<BlockNumberOrHash>,
 		state_overrides: Option<BTreeMap<H160, CallStateOverride>>,
+		block_overrides: Option<BlockOverrides>,
 	) -> RpcResult<Bytes> {
-		self.call(request, number_or_hash, state_overrides).await
+		self.call(request, number_or_hash, state_overrides, block_overrides)
+			.await
 	}
 
 	async fn estimate_gas(
 		&self,
 		request: TransactionRequest,
 		number_or_hash: Option<BlockNumberOrHash>,
+		state_overrides: Option<BTreeMap<H160, CallStateOverride>>,
+		block_overrides: Option<BlockOverrides>,
 	) -> RpcResult<U256> {
-		self.estimate_gas(request, number_or_hash).await
+		self.estimate_gas(request, number_or_hash, state_overrides, block_overrides)
+			.await
+	}
+
+	async fn create_access_list(
+		&self,
+		request: TransactionRequest,
+		number_or_hash: Option<BlockNumberOrHash>,
+	) -> RpcResult<AccessListResult> {
+		self.create_access_list(request, number_or_hash).await
+	}
+
+	async fn simulate(
+		&self,
+		payload: SimulatePayload,
+		number_or_hash: Option<BlockNumberOrHash>,
+	) -> RpcResult<Vec<SimulatedBlock>> {
+		self.simulate(payload, number_or_hash).await
 	}
 
 	// ########################################################################
@@ -525,12 +571,31 @@ where
 		self.submit_work(nonce, pow_hash, mix_digest)
 	}
 
+	// ########################################################################
+	// Sign
+	// ########################################################################
+
+	fn sign(&self, address: H160, data: Bytes) -> RpcResult<Bytes> {
+		self.sign(address, data)
+	}
+
+	fn sign_typed_data_v4(&self, address: H160, typed_data: TypedData) -> RpcResult<Bytes> {
+		self.sign_typed_data(address, typed_data)
+	}
+
+	async fn sign_transaction(
+		&self,
+		request: TransactionSigningRequest,
+	) -> RpcResult<RichRawTransaction> {
+		self.sign_transaction(request.0).await
+	}
+
 	// ########################################################################
 	// Submit
 	// ########################################################################
 
-	async fn send_transaction(&self, request: TransactionRequest) -> RpcResult<H256> {
-		self.send_transaction(request).await
+	async fn send_transaction(&self, request: TransactionSigningRequest) -> RpcResult<H256> {
+		self.send_transaction(request.0).await
 	}
 
 	async fn send_raw_transaction(&self, bytes: Bytes) -> RpcResult<H256> {
@@ -613,7 +678,7 @@ fn rich_block_build(
 	}
 }
 
-fn transaction_build(
+pub(crate) fn transaction_build(
 	ethereum_transaction: &EthereumTransaction,
 	block: Option<&EthereumBlock>,
 	status: Option<&TransactionStatus>,
@@ -0,0 +1,48 @@
+// This file is part of Frontier.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use ethereum_types::H160;
+use jsonrpsee::core::RpcResult;
+// Substrate
+use sp_runtime::traits::Block as BlockT;
+// Frontier
+use fc_rpc_core::types::*;
+
+use crate::{eth::Eth, internal_err, signer::EthSigner};
+
+impl<B, C, P, CT, BE, CIDP, EC> Eth<B, C, P, CT, BE, CIDP, EC>
+where
+	B: BlockT,
+{
+	pub fn sign(&self, address: H160, data: Bytes) -> RpcResult<Bytes> {
+		self.signer(&address)?.sign_message(&data.0, &address)
+	}
+
+	pub fn sign_typed_data(&self, address: H160, typed_data: TypedData) -> RpcResult<Bytes> {
+		self.signer(&address)?
+			.sign_typed_data(&typed_data, &address)
+	}
+
+	fn signer(&self, address: &H160) -> RpcResult<&dyn EthSigner> {
+		self.signers
+			.iter()
+			.find(|signer| signer.accounts().contains(address))
+			.map(|signer| &**signer)
+			.ok_or_else(|| internal_err("no signer available"))
+	}
+}
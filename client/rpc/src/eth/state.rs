@@ -20,18 +20,26 @@ use ethereum_types::{H160, H256, U256};
 use jsonrpsee::core::RpcResult;
 use scale_codec::Encode;
 // Substrate
-use sc_client_api::backend::{Backend, StorageProvider};
+use sc_client_api::{
+	backend::{Backend, StorageProvider},
+	ProofProvider,
+};
 use sc_transaction_pool_api::{InPoolTransaction, TransactionPool};
 use sp_api::ProvideRuntimeApi;
 use sp_block_builder::BlockBuilder as BlockBuilderApi;
 use sp_blockchain::HeaderBackend;
+use sp_core::hashing::keccak_256;
 use sp_inherents::CreateInherentDataProviders;
-use sp_runtime::traits::Block as BlockT;
+use sp_runtime::traits::{Block as BlockT, HashingFor, Header as HeaderT};
 // Frontier
 use fc_rpc_core::types::*;
-use fp_rpc::EthereumRuntimeRPCApi;
+use fp_proof::StorageProofChecker;
+use fp_rpc::{EthereumRuntimeRPCApi, RuntimeStorageOverride};
 
-use crate::{eth::Eth, frontier_backend_client, internal_err};
+use crate::{
+	eth::{Eth, EthConfig},
+	frontier_backend_client, internal_err,
+};
 
 impl<B, C, P, CT, BE, CIDP, EC> Eth<B, C, P, CT, BE, CIDP, EC>
 where
@@ -202,4 +210,102 @@ where
 			Ok(Bytes(vec![]))
 		}
 	}
+
+	pub async fn proof(
+		&self,
+		address: H160,
+		storage_keys: Vec<H256>,
+		number_or_hash: Option<BlockNumberOrHash>,
+	) -> RpcResult<EthAccount>
+	where
+		C: ProofProvider<B>,
+		EC: EthConfig<B, C>,
+	{
+		// Without a storage override, the account backing the address is unknown and the
+		// proof would be of another `System::Account` entry.
+		if !EC::RuntimeStorageOverride::is_enabled() {
+			return Err(internal_err(
+				"proofs require a runtime storage override mapping addresses to accounts",
+			));
+		}
+		let number_or_hash = number_or_hash.unwrap_or(BlockNumberOrHash::Latest);
+		if number_or_hash == BlockNumberOrHash::Pending {
+			return Err(internal_err(
+				"proofs are not available for the pending block",
+			));
+		}
+		let id = match frontier_backend_client::native_block_id::<B, C>(
+			self.client.as_ref(),
+			self.backend.as_ref(),
+			Some(number_or_hash),
+		)
+		.await?
+		{
+			Some(id) => id,
+			None => return Err(internal_err("block not found")),
+		};
+		let substrate_hash = self
+			.client
+			.expect_block_hash_from_id(&id)
+			.map_err(|_| internal_err(format!("Expect block number from id: {id}")))?;
+		let state_root = match self.client.header(substrate_hash) {
+			Ok(Some(header)) => *header.state_root(),
+			_ => return Err(internal_err("block header not found")),
+		};
+		let invalid_proof = |err: fp_proof::Error| internal_err(format!("Invalid proof: {err:?}"));
+
+		// The returned values are the ones proven, read back from the proofs.
+		let account_id = EC::RuntimeStorageOverride::into_account_id_bytes(address);
+		let account_proof = self.read_proof(
+			substrate_hash,
+			&[
+				fp_proof::keys::system_account(&account_id),
+				fp_proof::keys::account_code(address),
+			],
+		)?;
+		let checker = StorageProofChecker::<HashingFor<B>>::new(state_root, account_proof.clone())
+			.map_err(invalid_proof)?;
+		// Like the storage overrides, assume a `u32` nonce and a `u128` balance.
+		let (nonce, balance) = checker
+			.account_basic::<u32, u128>(&account_id)
+			.map_err(invalid_proof)?;
+		let code = checker.account_code(address).map_err(invalid_proof)?;
+
+		let mut storage_proof = Vec::with_capacity(storage_keys.len());
+		for key in storage_keys {
+			let proof = self.read_proof(
+				substrate_hash,
+				&[fp_proof::keys::account_storage(address, key)],
+			)?;
+			let value = StorageProofChecker::<HashingFor<B>>::new(state_root, proof.clone())
+				.and_then(|checker| checker.account_storage(address, key))
+				.map_err(invalid_proof)?;
+			storage_proof.push(StorageProof {
+				key,
+				value: U256::from_big_endian(value.as_bytes()),
+				proof: proof.into_iter().map(Bytes::new).collect(),
+			});
+		}
+
+		Ok(EthAccount {
+			address,
+			balance: U256::from(balance),
+			nonce: U256::from(nonce),
+			code_hash: H256(keccak_256(&code)),
+			storage_hash: H256::from_slice(state_root.as_ref()),
+			account_proof: account_proof.into_iter().map(Bytes::new).collect(),
+			storage_proof,
+		})
+	}
+
+	fn read_proof(&self, substrate_hash: B::Hash, keys: &[Vec<u8>]) -> RpcResult<Vec<Vec<u8>>>
+	where
+		C: ProofProvider<B>,
+	{
+		let proof = self
+			.client
+			.read_proof(substrate_hash, &mut keys.iter().map(|key| key.as_slice()))
+			.map_err(|err| internal_err(format!("Read proof failed: {err}")))?;
+		Ok(proof.into_iter_nodes().collect())
+	}
 }
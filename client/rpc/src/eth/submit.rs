@@ -48,7 +48,36 @@ where
 	CT: ConvertTransaction<<B as BlockT>::Extrinsic> + 'static,
 	CIDP: CreateInherentDataProviders<B, ()> + Send + 'static,
 {
+	pub async fn sign_transaction(
+		&self,
+		request: TransactionRequest,
+	) -> RpcResult<RichRawTransaction> {
+		let (from, transaction) = self.sign_request(request).await?;
+		Ok(RichRawTransaction {
+			raw: Bytes::new(ethereum::EnvelopedEncodable::encode(&transaction).to_vec()),
+			transaction: Transaction::build_from(from, &transaction),
+		})
+	}
+
 	pub async fn send_transaction(&self, request: TransactionRequest) -> RpcResult<H256> {
+		let (_, transaction) = self.sign_request(request).await?;
+		let transaction_hash = transaction.hash();
+
+		let block_hash = self.client.info().best_hash;
+		let extrinsic = self.convert_transaction(block_hash, transaction)?;
+
+		self.pool
+			.submit_one(block_hash, TransactionSource::Local, extrinsic)
+			.map_ok(move |_| transaction_hash)
+			.map_err(|err| internal_err(format::Geth::pool_error(err)))
+			.await
+	}
+
+	/// Fill in the missing fields of the request and sign it with the sender account.
+	async fn sign_request(
+		&self,
+		request: TransactionRequest,
+	) -> RpcResult<(H160, ethereum::TransactionV3)> {
 		let from = match request.from {
 			Some(from) => from,
 			None => {
@@ -125,6 +154,15 @@ where
 				}
 				TransactionMessage::EIP1559(m)
 			}
+			Some(TransactionMessage::EIP7702(mut m)) => {
+				m.nonce = nonce;
+				m.chain_id = chain_id;
+				m.gas_limit = gas_limit;
+				if max_fee_per_gas.is_none() {
+					m.max_fee_per_gas = self.gas_price().unwrap_or_default();
+				}
+				TransactionMessage::EIP7702(m)
+			}
 			_ => return Err(internal_err("invalid transaction parameters")),
 		};
 
@@ -139,19 +177,10 @@ where
 			}
 		}
 
-		let transaction = match transaction {
-			Some(transaction) => transaction,
-			None => return Err(internal_err("no signer available")),
-		};
-		let transaction_hash = transaction.hash();
-
-		let extrinsic = self.convert_transaction(block_hash, transaction)?;
-
-		self.pool
-			.submit_one(block_hash, TransactionSource::Local, extrinsic)
-			.map_ok(move |_| transaction_hash)
-			.map_err(|err| internal_err(format::Geth::pool_error(err)))
-			.await
+		match transaction {
+			Some(transaction) => Ok((from, transaction)),
+			None => Err(internal_err("no signer available")),
+		}
 	}
 
 	pub async fn send_raw_transaction(&self, bytes: Bytes) -> RpcResult<H256> {
@@ -39,11 +39,13 @@ use sp_blockchain::HeaderBackend;
 use sp_consensus::SyncOracle;
 use sp_runtime::traits::{Block as BlockT, UniqueSaturatedInto};
 // Frontier
-use fc_mapping_sync::{EthereumBlockNotification, EthereumBlockNotificationSinks};
+use fc_mapping_sync::{EthereumBlockNotification, EthereumBlockNotificationSinks, ReorgInfo};
 use fc_rpc_core::{
 	types::{
-		pubsub::{Kind, Params, PubSubResult, PubSubSyncing, SyncingStatus},
-		FilteredParams,
+		pubsub::{
+			Kind, Params, PendingTransactionsParams, PubSubResult, PubSubSyncing, SyncingStatus,
+		},
+		FilteredParams, Log,
 	},
 	EthPubSubApiServer,
 };
@@ -136,37 +138,53 @@ where
 			.collect()
 	}
 
+	/// Get the logs to emit for a new best block.
 	fn notify_logs(
 		&self,
 		notification: EthereumBlockNotification<B>,
 		params: &FilteredParams,
 	) -> future::Ready<Option<impl Iterator<Item = PubSubResult>>> {
-		let res = if notification.is_new_best {
-			let substrate_hash = notification.hash;
-
-			let block = self.storage_override.current_block(substrate_hash);
-			let statuses = self
-				.storage_override
-				.current_transaction_statuses(substrate_hash);
+		if !notification.is_new_best {
+			return future::ready(None);
+		}
 
-			match (block, statuses) {
-				(Some(block), Some(statuses)) => Some((block, statuses)),
-				_ => None,
-			}
-		} else {
-			None
-		};
+		if let Some(ref reorg_info) = notification.reorg_info {
+			debug!(
+				target: "eth-pubsub",
+				"Reorg detected: removing logs of {} retracted blocks",
+				reorg_info.retracted.len(),
+			);
+		}
+		let logs = new_best_logs(
+			notification.hash,
+			notification.reorg_info.as_ref(),
+			|hash| self.block_logs(hash, params),
+		);
+
+		future::ready(Some(
+			logs.into_iter().map(|log| PubSubResult::Log(Box::new(log))),
+		))
+	}
 
-		future::ready(res.map(|(block, statuses)| {
-			let logs = crate::eth::filter::filter_block_logs(&params.filter, block, statuses);
+	fn block_logs(&self, substrate_hash: B::Hash, params: &FilteredParams) -> Vec<Log> {
+		let block = self.storage_override.current_block(substrate_hash);
+		let statuses = self
+			.storage_override
+			.current_transaction_statuses(substrate_hash);
 
-			logs.clone()
-				.into_iter()
-				.map(|log| PubSubResult::Log(Box::new(log.clone())))
-		}))
+		match (block, statuses) {
+			(Some(block), Some(statuses)) => {
+				crate::eth::filter::filter_block_logs(&params.filter, block, statuses)
+			}
+			_ => Vec::new(),
+		}
 	}
 
-	fn pending_transactions(&self, hash: &TxHash<P>) -> future::Ready<Option<PubSubResult>> {
+	fn pending_transactions(
+		&self,
+		hash: &TxHash<P>,
+		params: &PendingTransactionsParams,
+	) -> future::Ready<Option<PubSubResult>> {
 		let res = if let Some(xt) = self.pool.ready_transaction(hash) {
 			let best_block = self.client.info().best_hash;
 
@@ -206,7 +224,20 @@ where
 		} else {
 			None
 		};
-		future::ready(res.map(|tx| PubSubResult::transaction_hash(&tx)))
+		future::ready(res.and_then(|tx| {
+			if !params.needs_transaction() {
+				return Some(PubSubResult::transaction_hash(&tx));
+			}
+			let transaction = crate::eth::transaction_build(&tx, None, None, None);
+			if !params.matches(transaction.from, transaction.to) {
+				return None;
+			}
+			Some(if params.full_transactions {
+				PubSubResult::Transaction(Box::new(transaction))
+			} else {
+				PubSubResult::TransactionHash(transaction.hash)
+			})
+		}))
 	}
 
 	async fn syncing_status(&self) -> PubSubSyncing {
@@ -233,6 +264,33 @@ where
 	}
 }
 
+/// Logs to emit for the new best block `hash`, given the logs of each block.
+///
+/// When the block became best through a reorg, the logs of the retracted blocks are emitted
+/// first with `removed: true`, in the reverse order they were delivered, followed by the logs
+/// of the enacted blocks, as Ethereum clients do.
+fn new_best_logs<B: BlockT>(
+	hash: B::Hash,
+	reorg_info: Option<&ReorgInfo<B>>,
+	block_logs: impl Fn(B::Hash) -> Vec<Log>,
+) -> Vec<Log> {
+	let mut logs = Vec::new();
+	if let Some(reorg_info) = reorg_info {
+		// Retracted blocks are ordered from the old best block down to the common ancestor.
+		for hash in &reorg_info.retracted {
+			logs.extend(block_logs(*hash).into_iter().rev().map(|mut log| {
+				log.removed = true;
+				log
+			}));
+		}
+		for hash in &reorg_info.enacted {
+			logs.extend(block_logs(*hash));
+		}
+	}
+	logs.extend(block_logs(hash));
+	logs
+}
+
 impl<B: BlockT, P, C, BE> EthPubSubApiServer for EthPubSub<B, P, C, BE>
 where
 	B: BlockT,
@@ -244,9 +302,10 @@ where
 	BE: Backend<B> + 'static,
 {
 	fn subscribe(&self, pending: PendingSubscriptionSink, kind: Kind, params: Option<Params>) {
-		let filtered_params = match params {
-			Some(Params::Logs(filter)) => FilteredParams::new(filter),
-			_ => FilteredParams::default(),
+		let (filtered_params, pending_params) = match params {
+			Some(Params::Logs(filter)) => (FilteredParams::new(filter), Default::default()),
+			Some(Params::PendingTransactions(params)) => (FilteredParams::default(), params),
+			_ => (FilteredParams::default(), Default::default()),
 		};
 
 		let pubsub = self.clone();
@@ -314,13 +373,35 @@ where
 				}
 				Kind::NewPendingTransactions => {
 					let pool = pubsub.pool.clone();
-					let stream = pool
-						.import_notification_stream()
-						.filter_map(move |hash| pubsub.pending_transactions(&hash));
+					let stream = pool.import_notification_stream().filter_map(move |hash| {
+						pubsub.pending_transactions(&hash, &pending_params)
+					});
 					PendingSubscription::from(pending)
 						.pipe_from_stream(stream, BoundedVecDeque::new(16))
 						.await;
 				}
+				Kind::NewFinalizedHeads | Kind::NewSafeHeads => {
+					// As for the `safe` block tag, the safe block is the finalized block.
+					//
+					// Blocks can be finalized several at once, in which case all of them are
+					// emitted in ascending order, followed by the new finalized block.
+					let stream = pubsub
+						.client
+						.finality_notification_stream()
+						.map(move |notification| {
+							notification
+								.tree_route
+								.iter()
+								.chain(std::iter::once(&notification.hash))
+								.filter_map(|hash| pubsub.storage_override.current_block(*hash))
+								.map(PubSubResult::header)
+								.collect::<Vec<_>>()
+						})
+						.flat_map(futures::stream::iter);
+					PendingSubscription::from(pending)
+						.pipe_from_stream(stream, BoundedVecDeque::new(16))
+						.await
+				}
 				Kind::Syncing => {
 					let Ok(sink) = pending.accept().await else {
 						return;
@@ -359,3 +440,75 @@ where
 			.spawn("frontier-rpc-subscription", Some("rpc"), fut);
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use ethereum_types::{H256, U256};
+	use substrate_test_runtime_client::runtime::Block;
+
+	use super::*;
+
+	fn block_logs(hash: H256) -> Vec<Log> {
+		(0..2u64)
+			.map(|index| Log {
+				address: Default::default(),
+				topics: vec![],
+				data: Default::default(),
+				block_hash: Some(hash),
+				block_number: None,
+				transaction_hash: None,
+				transaction_index: None,
+				log_index: Some(U256::from(index)),
+				transaction_log_index: None,
+				removed: false,
+			})
+			.collect()
+	}
+
+	fn summary(logs: &[Log]) -> Vec<(H256, u64, bool)> {
+		logs.iter()
+			.map(|log| {
+				(
+					log.block_hash.unwrap(),
+					log.log_index.unwrap().as_u64(),
+					log.removed,
+				)
+			})
+			.collect()
+	}
+
+	#[test]
+	fn new_best_logs_without_reorg() {
+		let best = H256::repeat_byte(1);
+		let logs = new_best_logs::<Block>(best, None, block_logs);
+		assert_eq!(summary(&logs), vec![(best, 0, false), (best, 1, false)]);
+	}
+
+	#[test]
+	fn new_best_logs_replays_reorg() {
+		// Old chain: ancestor <- a1 <- a2, new chain: ancestor <- b1 <- b2.
+		let (a1, a2) = (H256::repeat_byte(0xa1), H256::repeat_byte(0xa2));
+		let (b1, b2) = (H256::repeat_byte(0xb1), H256::repeat_byte(0xb2));
+		let reorg_info = ReorgInfo::<Block> {
+			common_ancestor: H256::zero(),
+			retracted: vec![a2, a1],
+			enacted: vec![b1],
+			new_best: b2,
+		};
+
+		let logs = new_best_logs(b2, Some(&reorg_info), block_logs);
+		assert_eq!(
+			summary(&logs),
+			vec![
+				(a2, 1, true),
+				(a2, 0, true),
+				(a1, 1, true),
+				(a1, 0, true),
+				(b1, 0, false),
+				(b1, 1, false),
+				(b2, 0, false),
+				(b2, 1, false),
+			]
+		);
+	}
+}
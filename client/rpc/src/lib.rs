@@ -31,7 +31,9 @@ mod debug;
 mod eth;
 mod eth_pubsub;
 mod net;
+mod personal;
 mod signer;
+mod trace;
 #[cfg(feature = "txpool")]
 mod txpool;
 mod web3;
@@ -44,7 +46,12 @@ pub use self::{
 	eth::{format, pending, EstimateGasAdapter, Eth, EthConfig, EthFilter},
 	eth_pubsub::{EthPubSub, EthereumSubIdProvider},
 	net::Net,
-	signer::{EthDevSigner, EthSigner},
+	personal::Personal,
+	signer::{
+		EthDevSigner, EthKeystoreSigner, EthRemoteSigner, EthSigner, KeystoreError,
+		RemoteSignerApi, RemoteSignerConfig,
+	},
+	trace::Trace,
 	web3::Web3,
 };
 pub use ethereum::TransactionV3 as EthereumTransaction;
@@ -52,7 +59,7 @@ pub use ethereum::TransactionV3 as EthereumTransaction;
 pub use fc_rpc_core::TxPoolApiServer;
 pub use fc_rpc_core::{
 	DebugApiServer, EthApiServer, EthFilterApiServer, EthPubSubApiServer, NetApiServer,
-	Web3ApiServer,
+	PersonalApiServer, TraceApiServer, Web3ApiServer,
 };
 pub use fc_storage::{overrides::*, StorageOverrideHandler};
 
@@ -71,7 +78,7 @@ pub mod frontier_backend_client {
 	use sp_io::hashing::{blake2_128, twox_128};
 	use sp_runtime::{
 		generic::BlockId,
-		traits::{Block as BlockT, HashingFor, UniqueSaturatedInto},
+		traits::{Block as BlockT, HashingFor, NumberFor, UniqueSaturatedInto},
 	};
 	use sp_state_machine::OverlayedChanges;
 	// Frontier
@@ -219,6 +226,35 @@ pub mod frontier_backend_client {
 		})
 	}
 
+	/// Fails if the frontier backend pruned the data of the block `number`.
+	pub async fn ensure_not_pruned<B, C>(
+		client: &C,
+		backend: &dyn fc_api::Backend<B>,
+		number: NumberFor<B>,
+	) -> RpcResult<()>
+	where
+		B: BlockT,
+		C: HeaderBackend<B> + 'static,
+	{
+		// Same code as geth for the blocks of an expired history.
+		const PRUNED_HISTORY_CODE: i32 = 4444;
+
+		let first_block_number = match backend.first_block_hash().await {
+			Ok(hash) => client.number(hash).ok().flatten(),
+			Err(_) => None,
+		};
+		match first_block_number {
+			Some(first_block_number) if number < first_block_number => Err(crate::err(
+				PRUNED_HISTORY_CODE,
+				format!(
+					"pruned history unavailable, oldest available block is #{first_block_number}"
+				),
+				None,
+			)),
+			_ => Ok(()),
+		}
+	}
+
 	pub async fn load_hash<B, C>(
 		client: &C,
 		backend: &dyn fc_api::Backend<B>,
@@ -383,6 +419,7 @@ mod tests {
 					path,
 					cache_size: 0,
 				},
+				log_index: false,
 			},
 		)?))
 	}
@@ -431,6 +468,7 @@ mod tests {
 		// Map B1
 		let commitment = fc_db::kv::MappingCommitment::<OpaqueBlock> {
 			block_hash: b1_hash,
+			block_number: 2,
 			ethereum_block_hash,
 			ethereum_transaction_hashes: vec![],
 		};
@@ -463,6 +501,7 @@ mod tests {
 		// Map B2 to same ethereum hash
 		let commitment = fc_db::kv::MappingCommitment::<OpaqueBlock> {
 			block_hash: b2_hash,
+			block_number: 2,
 			ethereum_block_hash,
 			ethereum_transaction_hashes: vec![],
 		};
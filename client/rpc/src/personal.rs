@@ -0,0 +1,77 @@
+// This file is part of Frontier.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::time::Duration;
+
+use ethereum_types::H160;
+use jsonrpsee::core::RpcResult;
+// Frontier
+use fc_rpc_core::{types::Bytes, PersonalApiServer};
+
+use crate::{internal_err, signer::EthSigner, EthKeystoreSigner};
+
+/// Default duration an account stays unlocked for, as in geth.
+const DEFAULT_UNLOCK_DURATION: Duration = Duration::from_secs(300);
+
+/// Personal API implementation.
+pub struct Personal {
+	keystore: EthKeystoreSigner,
+}
+
+impl Personal {
+	pub fn new(keystore: EthKeystoreSigner) -> Self {
+		Self { keystore }
+	}
+}
+
+impl PersonalApiServer for Personal {
+	fn list_accounts(&self) -> RpcResult<Vec<H160>> {
+		Ok(self.keystore.accounts())
+	}
+
+	fn new_account(&self, passphrase: String) -> RpcResult<H160> {
+		self.keystore.new_account(&passphrase).map_err(internal_err)
+	}
+
+	fn unlock_account(
+		&self,
+		address: H160,
+		passphrase: String,
+		duration: Option<u64>,
+	) -> RpcResult<bool> {
+		let duration = match duration {
+			None => Some(DEFAULT_UNLOCK_DURATION),
+			Some(0) => None,
+			Some(seconds) => Some(Duration::from_secs(seconds)),
+		};
+		self.keystore
+			.unlock(address, &passphrase, duration)
+			.map_err(internal_err)?;
+		Ok(true)
+	}
+
+	fn lock_account(&self, address: H160) -> RpcResult<bool> {
+		self.keystore.lock(address).map_err(internal_err)?;
+		Ok(true)
+	}
+
+	fn sign(&self, data: Bytes, address: H160, passphrase: String) -> RpcResult<Bytes> {
+		self.keystore
+			.sign_message_with_passphrase(&data.0, &address, &passphrase)
+	}
+}
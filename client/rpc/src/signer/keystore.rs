@@ -0,0 +1,623 @@
+// This file is part of Frontier.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Signer backed by a directory of encrypted JSON key files (Web3 Secret Storage, version 3).
+
+use std::{
+	collections::BTreeMap,
+	fs, io,
+	path::{Path, PathBuf},
+	sync::{Arc, Mutex},
+	time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use aes::cipher::{KeyIvInit, StreamCipher};
+use ethereum::TransactionV3 as EthereumTransaction;
+use ethereum_types::H160;
+use jsonrpsee::types::ErrorObjectOwned;
+use serde::{Deserialize, Serialize};
+// Substrate
+use sp_core::hashing::keccak_256;
+// Frontier
+use fc_rpc_core::types::{Bytes, TransactionMessage, TypedData};
+
+use super::{
+	personal_message_hash, secret_key_address, sign_hash, sign_transaction_message,
+	typed_data_hash, EthSigner,
+};
+use crate::internal_err;
+
+type Aes128Ctr = ctr::Ctr128BE<aes::Aes128>;
+
+/// Scrypt parameters used by geth for new key files (`StandardScryptN`, `StandardScryptP`).
+const STANDARD_SCRYPT_LOG_N: u8 = 18;
+const STANDARD_SCRYPT_P: u32 = 1;
+/// Scrypt parameters used by geth with `--lightkdf` (`LightScryptN`, `LightScryptP`).
+const LIGHT_SCRYPT_LOG_N: u8 = 12;
+const LIGHT_SCRYPT_P: u32 = 6;
+const SCRYPT_R: u32 = 8;
+const DERIVED_KEY_LEN: usize = 32;
+
+#[derive(Debug, thiserror::Error)]
+pub enum KeystoreError {
+	#[error("Keystore I/O error, {0}")]
+	Io(#[from] io::Error),
+	#[error("Invalid key file, {0}")]
+	InvalidKeyFile(String),
+	#[error("Unsupported key file, {0}")]
+	Unsupported(String),
+	#[error("Could not decrypt key with given password")]
+	InvalidPassphrase,
+	#[error("Unknown account {0:?}")]
+	UnknownAccount(H160),
+	#[error("Keystore is not available")]
+	Unavailable,
+}
+
+/// Web3 Secret Storage key file.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct KeyFile {
+	version: u32,
+	id: String,
+	address: String,
+	#[serde(alias = "Crypto")]
+	crypto: Crypto,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Crypto {
+	cipher: String,
+	cipherparams: CipherParams,
+	ciphertext: String,
+	kdf: String,
+	kdfparams: serde_json::Value,
+	mac: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct CipherParams {
+	iv: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct ScryptParams {
+	dklen: usize,
+	n: u64,
+	r: u32,
+	p: u32,
+	salt: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Pbkdf2Params {
+	dklen: usize,
+	c: u32,
+	prf: String,
+	salt: String,
+}
+
+fn decode_hex(field: &str, value: &str) -> Result<Vec<u8>, KeystoreError> {
+	hex::decode(value.trim_start_matches("0x"))
+		.map_err(|err| KeystoreError::InvalidKeyFile(format!("invalid {field}: {err}")))
+}
+
+impl KeyFile {
+	fn address(&self) -> Result<H160, KeystoreError> {
+		let address = decode_hex("address", &self.address)?;
+		if address.len() != 20 {
+			return Err(KeystoreError::InvalidKeyFile("invalid address".into()));
+		}
+		Ok(H160::from_slice(&address))
+	}
+
+	fn derive_key(&self, passphrase: &str) -> Result<Vec<u8>, KeystoreError> {
+		let invalid_params = |err: serde_json::Error| {
+			KeystoreError::InvalidKeyFile(format!("invalid kdfparams: {err}"))
+		};
+		match self.crypto.kdf.as_str() {
+			"scrypt" => {
+				let params: ScryptParams = serde_json::from_value(self.crypto.kdfparams.clone())
+					.map_err(invalid_params)?;
+				if !params.n.is_power_of_two() || params.n < 2 {
+					return Err(KeystoreError::InvalidKeyFile(
+						"scrypt n must be a power of two".into(),
+					));
+				}
+				let scrypt_params = scrypt::Params::new(
+					params.n.trailing_zeros() as u8,
+					params.r,
+					params.p,
+					params.dklen,
+				)
+				.map_err(|err| {
+					KeystoreError::InvalidKeyFile(format!("invalid scrypt params: {err}"))
+				})?;
+				let salt = decode_hex("salt", &params.salt)?;
+				let mut derived_key = vec![0u8; params.dklen];
+				scrypt::scrypt(
+					passphrase.as_bytes(),
+					&salt,
+					&scrypt_params,
+					&mut derived_key,
+				)
+				.map_err(|err| {
+					KeystoreError::InvalidKeyFile(format!("invalid scrypt params: {err}"))
+				})?;
+				Ok(derived_key)
+			}
+			"pbkdf2" => {
+				let params: Pbkdf2Params = serde_json::from_value(self.crypto.kdfparams.clone())
+					.map_err(invalid_params)?;
+				if params.prf != "hmac-sha256" {
+					return Err(KeystoreError::Unsupported(format!("prf {}", params.prf)));
+				}
+				let salt = decode_hex("salt", &params.salt)?;
+				let mut derived_key = vec![0u8; params.dklen];
+				pbkdf2::pbkdf2_hmac::<sha2::Sha256>(
+					passphrase.as_bytes(),
+					&salt,
+					params.c,
+					&mut derived_key,
+				);
+				Ok(derived_key)
+			}
+			kdf => Err(KeystoreError::Unsupported(format!("kdf {kdf}"))),
+		}
+	}
+
+	/// Decrypts the secret key with the given passphrase.
+	fn decrypt(&self, passphrase: &str) -> Result<libsecp256k1::SecretKey, KeystoreError> {
+		if self.crypto.cipher != "aes-128-ctr" {
+			return Err(KeystoreError::Unsupported(format!(
+				"cipher {}",
+				self.crypto.cipher
+			)));
+		}
+
+		let derived_key = self.derive_key(passphrase)?;
+		if derived_key.len() < 32 {
+			return Err(KeystoreError::InvalidKeyFile(
+				"dklen must be at least 32".into(),
+			));
+		}
+		let mut ciphertext = decode_hex("ciphertext", &self.crypto.ciphertext)?;
+		let mac = decode_hex("mac", &self.crypto.mac)?;
+		if keccak_256(&[&derived_key[16..32], &ciphertext[..]].concat())[..] != mac[..] {
+			return Err(KeystoreError::InvalidPassphrase);
+		}
+
+		let iv = decode_hex("iv", &self.crypto.cipherparams.iv)?;
+		let mut cipher = Aes128Ctr::new_from_slices(&derived_key[..16], &iv)
+			.map_err(|_| KeystoreError::InvalidKeyFile("invalid iv".into()))?;
+		cipher.apply_keystream(&mut ciphertext);
+
+		let secret = libsecp256k1::SecretKey::parse_slice(&ciphertext)
+			.map_err(|_| KeystoreError::InvalidKeyFile("invalid secret key".into()))?;
+		if secret_key_address(&secret) != self.address()? {
+			return Err(KeystoreError::InvalidKeyFile(
+				"address does not match the secret key".into(),
+			));
+		}
+		Ok(secret)
+	}
+
+	/// Encrypts the secret key with the given passphrase, using scrypt and AES-128-CTR.
+	fn encrypt(
+		secret: &libsecp256k1::SecretKey,
+		passphrase: &str,
+		light_kdf: bool,
+	) -> Result<Self, KeystoreError> {
+		let (log_n, p) = if light_kdf {
+			(LIGHT_SCRYPT_LOG_N, LIGHT_SCRYPT_P)
+		} else {
+			(STANDARD_SCRYPT_LOG_N, STANDARD_SCRYPT_P)
+		};
+		let salt: [u8; 32] = rand::random();
+		let iv: [u8; 16] = rand::random();
+
+		let scrypt_params = scrypt::Params::new(log_n, SCRYPT_R, p, DERIVED_KEY_LEN)
+			.expect("Scrypt parameters are valid; qed");
+		let mut derived_key = [0u8; DERIVED_KEY_LEN];
+		scrypt::scrypt(
+			passphrase.as_bytes(),
+			&salt,
+			&scrypt_params,
+			&mut derived_key,
+		)
+		.expect("Derived key length is valid; qed");
+
+		let mut ciphertext = secret.serialize().to_vec();
+		let mut cipher = Aes128Ctr::new_from_slices(&derived_key[..16], &iv)
+			.expect("Key and iv lengths are valid; qed");
+		cipher.apply_keystream(&mut ciphertext);
+		let mac = keccak_256(&[&derived_key[16..32], &ciphertext[..]].concat());
+
+		let mut id: [u8; 16] = rand::random();
+		// Random (version 4) UUID.
+		id[6] = (id[6] & 0x0f) | 0x40;
+		id[8] = (id[8] & 0x3f) | 0x80;
+		let id = hex::encode(id);
+
+		Ok(Self {
+			version: 3,
+			id: format!(
+				"{}-{}-{}-{}-{}",
+				&id[0..8],
+				&id[8..12],
+				&id[12..16],
+				&id[16..20],
+				&id[20..32]
+			),
+			address: hex::encode(secret_key_address(secret)),
+			crypto: Crypto {
+				cipher: "aes-128-ctr".into(),
+				cipherparams: CipherParams {
+					iv: hex::encode(iv),
+				},
+				ciphertext: hex::encode(ciphertext),
+				kdf: "scrypt".into(),
+				kdfparams: serde_json::to_value(ScryptParams {
+					dklen: DERIVED_KEY_LEN,
+					n: 1 << log_n,
+					r: SCRYPT_R,
+					p,
+					salt: hex::encode(salt),
+				})
+				.expect("Scrypt parameters are serializable; qed"),
+				mac: hex::encode(mac),
+			},
+		})
+	}
+}
+
+struct UnlockedKey {
+	secret: libsecp256k1::SecretKey,
+	/// `None` if the account stays unlocked until it is explicitly locked.
+	expires_at: Option<Instant>,
+}
+
+struct Inner {
+	directory: PathBuf,
+	light_kdf: bool,
+	keys: Mutex<BTreeMap<H160, KeyFile>>,
+	unlocked: Mutex<BTreeMap<H160, UnlockedKey>>,
+}
+
+/// A signer backed by a directory of encrypted JSON key files (Web3 Secret Storage).
+///
+/// Accounts must be unlocked with their passphrase before they can sign. The signer is cheap to
+/// clone, so the same keystore can be handed to `Eth` and to the `personal` RPC.
+#[derive(Clone)]
+pub struct EthKeystoreSigner {
+	inner: Arc<Inner>,
+}
+
+impl EthKeystoreSigner {
+	/// Opens the keystore in `directory`, creating the directory if it does not exist.
+	///
+	/// If `light_kdf` is set, new key files are encrypted with cheaper scrypt parameters, which
+	/// is only suitable for development networks.
+	pub fn open(directory: impl Into<PathBuf>, light_kdf: bool) -> Result<Self, KeystoreError> {
+		let directory = directory.into();
+		fs::create_dir_all(&directory)?;
+
+		let mut keys = BTreeMap::new();
+		for entry in fs::read_dir(&directory)? {
+			let path = entry?.path();
+			if !path.is_file() {
+				continue;
+			}
+			match Self::read_key_file(&path) {
+				Ok(key_file) => {
+					keys.insert(key_file.address()?, key_file);
+				}
+				Err(err) => {
+					log::warn!(target: "eth-keystore", "Skipping key file {}: {err}", path.display());
+				}
+			}
+		}
+
+		Ok(Self {
+			inner: Arc::new(Inner {
+				directory,
+				light_kdf,
+				keys: Mutex::new(keys),
+				unlocked: Mutex::new(BTreeMap::new()),
+			}),
+		})
+	}
+
+	fn read_key_file(path: &Path) -> Result<KeyFile, KeystoreError> {
+		let key_file: KeyFile = serde_json::from_slice(&fs::read(path)?)
+			.map_err(|err| KeystoreError::InvalidKeyFile(err.to_string()))?;
+		if key_file.version != 3 {
+			return Err(KeystoreError::Unsupported(format!(
+				"version {}",
+				key_file.version
+			)));
+		}
+		key_file.address()?;
+		Ok(key_file)
+	}
+
+	/// Generates a new account, stores it encrypted with `passphrase` and returns its address.
+	pub fn new_account(&self, passphrase: &str) -> Result<H160, KeystoreError> {
+		let secret = loop {
+			if let Ok(secret) = libsecp256k1::SecretKey::parse(&rand::random()) {
+				break secret;
+			}
+		};
+		let key_file = KeyFile::encrypt(&secret, passphrase, self.inner.light_kdf)?;
+		let address = secret_key_address(&secret);
+
+		let timestamp = SystemTime::now()
+			.duration_since(UNIX_EPOCH)
+			.unwrap_or_default()
+			.as_secs();
+		let path = self
+			.inner
+			.directory
+			.join(format!("UTC--{timestamp}--{}", key_file.address));
+		let content = serde_json::to_vec(&key_file)
+			.map_err(|err| KeystoreError::InvalidKeyFile(err.to_string()))?;
+		write_private_file(&path, &content)?;
+
+		self.inner
+			.keys
+			.lock()
+			.map_err(|_| KeystoreError::Unavailable)?
+			.insert(address, key_file);
+		Ok(address)
+	}
+
+	/// Decrypts the key of `address` and keeps it in memory for `duration`, or until it is
+	/// locked again if `duration` is `None` or too long to be represented.
+	pub fn unlock(
+		&self,
+		address: H160,
+		passphrase: &str,
+		duration: Option<Duration>,
+	) -> Result<(), KeystoreError> {
+		let secret = self.decrypt_secret(&address, passphrase)?;
+		self.inner
+			.unlocked
+			.lock()
+			.map_err(|_| KeystoreError::Unavailable)?
+			.insert(
+				address,
+				UnlockedKey {
+					secret,
+					expires_at: duration.and_then(|duration| Instant::now().checked_add(duration)),
+				},
+			);
+		Ok(())
+	}
+
+	/// Removes the decrypted key of `address` from memory.
+	pub fn lock(&self, address: H160) -> Result<(), KeystoreError> {
+		self.inner
+			.unlocked
+			.lock()
+			.map_err(|_| KeystoreError::Unavailable)?
+			.remove(&address);
+		Ok(())
+	}
+
+	/// Signs a message prefixed as of EIP-191 with the key of `address`, decrypted with
+	/// `passphrase` for this signature only.
+	pub fn sign_message_with_passphrase(
+		&self,
+		message: &[u8],
+		address: &H160,
+		passphrase: &str,
+	) -> Result<Bytes, ErrorObjectOwned> {
+		let secret = self
+			.decrypt_secret(address, passphrase)
+			.map_err(internal_err)?;
+		sign_hash(personal_message_hash(message), &secret)
+	}
+
+	fn decrypt_secret(
+		&self,
+		address: &H160,
+		passphrase: &str,
+	) -> Result<libsecp256k1::SecretKey, KeystoreError> {
+		let key_file = self
+			.inner
+			.keys
+			.lock()
+			.map_err(|_| KeystoreError::Unavailable)?
+			.get(address)
+			.cloned()
+			.ok_or(KeystoreError::UnknownAccount(*address))?;
+		key_file.decrypt(passphrase)
+	}
+
+	/// Returns the decrypted key of `address` if it is unlocked.
+	fn unlocked_secret(&self, address: &H160) -> Result<libsecp256k1::SecretKey, ErrorObjectOwned> {
+		let mut unlocked = self
+			.inner
+			.unlocked
+			.lock()
+			.map_err(|_| internal_err("keystore is not available"))?;
+		match unlocked.get(address) {
+			Some(key) if key.expires_at.map_or(true, |at| Instant::now() < at) => Ok(key.secret),
+			Some(_) => {
+				unlocked.remove(address);
+				Err(internal_err("authentication needed: password or unlock"))
+			}
+			None => Err(internal_err("authentication needed: password or unlock")),
+		}
+	}
+}
+
+fn write_private_file(path: &Path, content: &[u8]) -> io::Result<()> {
+	use std::io::Write;
+
+	let mut options = fs::OpenOptions::new();
+	options.write(true).create_new(true);
+	#[cfg(unix)]
+	{
+		use std::os::unix::fs::OpenOptionsExt;
+		options.mode(0o600);
+	}
+	options.open(path)?.write_all(content)
+}
+
+impl EthSigner for EthKeystoreSigner {
+	fn accounts(&self) -> Vec<H160> {
+		self.inner
+			.keys
+			.lock()
+			.map(|keys| keys.keys().copied().collect())
+			.unwrap_or_default()
+	}
+
+	fn sign(
+		&self,
+		message: TransactionMessage,
+		address: &H160,
+	) -> Result<EthereumTransaction, ErrorObjectOwned> {
+		let secret = self.unlocked_secret(address)?;
+		sign_transaction_message(message, &secret)
+	}
+
+	fn sign_message(&self, message: &[u8], address: &H160) -> Result<Bytes, ErrorObjectOwned> {
+		let secret = self.unlocked_secret(address)?;
+		sign_hash(personal_message_hash(message), &secret)
+	}
+
+	fn sign_typed_data(
+		&self,
+		typed_data: &TypedData,
+		address: &H160,
+	) -> Result<Bytes, ErrorObjectOwned> {
+		let secret = self.unlocked_secret(address)?;
+		sign_hash(typed_data_hash(typed_data)?, &secret)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// Test vector from the Web3 Secret Storage definition, with the password "testpassword".
+	const PBKDF2_KEY_FILE: &str = r#"{
+		"crypto": {
+			"cipher": "aes-128-ctr",
+			"cipherparams": { "iv": "6087dab2f9fdbbfaddc31a909735c1e6" },
+			"ciphertext": "5318b4d5bcd28de64ee5559e671353e16f075ecae9f99c7a79a38af5f869aa46",
+			"kdf": "pbkdf2",
+			"kdfparams": {
+				"c": 262144,
+				"dklen": 32,
+				"prf": "hmac-sha256",
+				"salt": "ae3cd4e7013836a3df6bd7241b12db061dbe2c6785853cce422d148a624ce0bd"
+			},
+			"mac": "517ead924a9d0dc3124507e3393d175ce3ff7c1e96529c6c555ce9e51205e9b2"
+		},
+		"id": "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+		"version": 3,
+		"address": "008aeeda4d805471df9b2a5b0f38a0c3bcba786b"
+	}"#;
+
+	#[test]
+	fn decrypts_pbkdf2_key_file() {
+		let key_file: KeyFile = serde_json::from_str(PBKDF2_KEY_FILE).unwrap();
+		let secret = key_file.decrypt("testpassword").unwrap();
+		assert_eq!(
+			hex::encode(secret.serialize()),
+			"7a28b5ba57c53603b0b07b56bba752f7784bf506fa95edc395f5cf6c7514fe9d"
+		);
+		assert!(matches!(
+			key_file.decrypt("wrong"),
+			Err(KeystoreError::InvalidPassphrase)
+		));
+	}
+
+	#[test]
+	fn new_account_can_be_unlocked_after_reopening() {
+		let directory = tempfile::tempdir().unwrap();
+		let keystore = EthKeystoreSigner::open(directory.path(), true).unwrap();
+		let address = keystore.new_account("secret").unwrap();
+		assert_eq!(keystore.accounts(), vec![address]);
+
+		let keystore = EthKeystoreSigner::open(directory.path(), true).unwrap();
+		assert_eq!(keystore.accounts(), vec![address]);
+		assert!(keystore.unlocked_secret(&address).is_err());
+		assert!(matches!(
+			keystore.unlock(address, "wrong", None),
+			Err(KeystoreError::InvalidPassphrase)
+		));
+
+		keystore.unlock(address, "secret", None).unwrap();
+		let secret = keystore.unlocked_secret(&address).unwrap();
+		assert_eq!(secret_key_address(&secret), address);
+
+		keystore.lock(address).unwrap();
+		assert!(keystore.unlocked_secret(&address).is_err());
+	}
+
+	#[test]
+	fn unlock_expires() {
+		let directory = tempfile::tempdir().unwrap();
+		let keystore = EthKeystoreSigner::open(directory.path(), true).unwrap();
+		let address = keystore.new_account("secret").unwrap();
+
+		keystore
+			.unlock(address, "secret", Some(Duration::ZERO))
+			.unwrap();
+		assert!(keystore.unlocked_secret(&address).is_err());
+	}
+
+	#[test]
+	fn unlock_for_the_longest_duration() {
+		let directory = tempfile::tempdir().unwrap();
+		let keystore = EthKeystoreSigner::open(directory.path(), true).unwrap();
+		let address = keystore.new_account("secret").unwrap();
+
+		keystore
+			.unlock(address, "secret", Some(Duration::from_secs(u64::MAX)))
+			.unwrap();
+		assert!(keystore.unlocked_secret(&address).is_ok());
+
+		keystore.lock(address).unwrap();
+		assert!(keystore.unlocked_secret(&address).is_err());
+	}
+
+	#[test]
+	fn signs_with_passphrase_without_unlocking() {
+		let directory = tempfile::tempdir().unwrap();
+		let keystore = EthKeystoreSigner::open(directory.path(), true).unwrap();
+		let address = keystore.new_account("secret").unwrap();
+
+		assert!(keystore
+			.sign_message_with_passphrase(b"hello", &address, "wrong")
+			.is_err());
+		let signature = keystore
+			.sign_message_with_passphrase(b"hello", &address, "secret")
+			.unwrap();
+		assert!(keystore.unlocked_secret(&address).is_err());
+
+		keystore.unlock(address, "secret", None).unwrap();
+		assert_eq!(
+			keystore.sign_message(b"hello", &address).unwrap(),
+			signature
+		);
+	}
+}
@@ -0,0 +1,360 @@
+// This file is part of Frontier.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+mod keystore;
+mod remote;
+
+pub use self::{
+	keystore::{EthKeystoreSigner, KeystoreError},
+	remote::{EthRemoteSigner, RemoteSignerApi, RemoteSignerConfig},
+};
+
+use ethereum::{eip2930, legacy, AuthorizationListItem, TransactionV3 as EthereumTransaction};
+use ethereum_types::{H160, H256};
+use jsonrpsee::types::ErrorObjectOwned;
+// Substrate
+use sp_core::hashing::keccak_256;
+// Frontier
+use fc_rpc_core::types::{Bytes, TransactionMessage, TypedData};
+
+use crate::internal_err;
+
+/// A generic Ethereum signer.
+pub trait EthSigner: Send + Sync {
+	/// Available accounts from this signer.
+	fn accounts(&self) -> Vec<H160>;
+	/// Sign a transaction message using the given account in message.
+	fn sign(
+		&self,
+		message: TransactionMessage,
+		address: &H160,
+	) -> Result<EthereumTransaction, ErrorObjectOwned>;
+	/// Sign a message prefixed as of EIP-191 using the given account.
+	fn sign_message(&self, _message: &[u8], _address: &H160) -> Result<Bytes, ErrorObjectOwned> {
+		Err(internal_err(
+			"signing messages is not supported by this signer",
+		))
+	}
+	/// Sign EIP-712 typed data using the given account.
+	fn sign_typed_data(
+		&self,
+		_typed_data: &TypedData,
+		_address: &H160,
+	) -> Result<Bytes, ErrorObjectOwned> {
+		Err(internal_err(
+			"signing typed data is not supported by this signer",
+		))
+	}
+}
+
+pub struct EthDevSigner {
+	keys: Vec<libsecp256k1::SecretKey>,
+}
+
+impl EthDevSigner {
+	pub fn new() -> Self {
+		Self {
+			keys: vec![libsecp256k1::SecretKey::parse(&[
+				0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11,
+				0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11,
+				0x11, 0x11, 0x11, 0x11,
+			])
+			.expect("Test key is valid; qed")],
+		}
+	}
+
+	fn secret(&self, address: &H160) -> Result<&libsecp256k1::SecretKey, ErrorObjectOwned> {
+		self.keys
+			.iter()
+			.find(|secret| &secret_key_address(secret) == address)
+			.ok_or_else(|| internal_err("signer not available"))
+	}
+}
+
+fn secret_key_address(secret: &libsecp256k1::SecretKey) -> H160 {
+	let public = libsecp256k1::PublicKey::from_secret_key(secret);
+	public_key_address(&public)
+}
+
+fn public_key_address(public: &libsecp256k1::PublicKey) -> H160 {
+	let mut res = [0u8; 64];
+	res.copy_from_slice(&public.serialize()[1..65]);
+	H160::from(H256::from(keccak_256(&res)))
+}
+
+impl EthSigner for EthDevSigner {
+	fn accounts(&self) -> Vec<H160> {
+		self.keys.iter().map(secret_key_address).collect()
+	}
+
+	fn sign(
+		&self,
+		message: TransactionMessage,
+		address: &H160,
+	) -> Result<EthereumTransaction, ErrorObjectOwned> {
+		sign_transaction_message(message, self.secret(address)?)
+	}
+
+	fn sign_message(&self, message: &[u8], address: &H160) -> Result<Bytes, ErrorObjectOwned> {
+		sign_hash(personal_message_hash(message), self.secret(address)?)
+	}
+
+	fn sign_typed_data(
+		&self,
+		typed_data: &TypedData,
+		address: &H160,
+	) -> Result<Bytes, ErrorObjectOwned> {
+		sign_hash(typed_data_hash(typed_data)?, self.secret(address)?)
+	}
+}
+
+/// Sign an EIP-7702 authorization, that is `keccak256(0x05 ‖ rlp([chain_id, address, nonce]))`.
+fn sign_authorization(
+	authorization: &mut AuthorizationListItem,
+	secret: &libsecp256k1::SecretKey,
+) -> Result<(), ErrorObjectOwned> {
+	let mut stream = rlp::RlpStream::new_list(3);
+	stream.append(&authorization.chain_id);
+	stream.append(&authorization.address);
+	stream.append(&authorization.nonce);
+	let mut preimage = vec![0x05];
+	preimage.extend_from_slice(&stream.out());
+
+	let signing_message = libsecp256k1::Message::parse_slice(&keccak_256(&preimage))
+		.map_err(|_| internal_err("invalid signing message"))?;
+	let (signature, recid) = libsecp256k1::sign(&signing_message, secret);
+	let rs = signature.serialize();
+	authorization.signature = eip2930::MalleableTransactionSignature {
+		odd_y_parity: recid.serialize() != 0,
+		r: H256::from_slice(&rs[0..32]),
+		s: H256::from_slice(&rs[32..64]),
+	};
+	Ok(())
+}
+
+/// Hash of a message prefixed with `"\x19Ethereum Signed Message:\n" ‖ len(message)`.
+fn personal_message_hash(message: &[u8]) -> H256 {
+	let mut preimage = format!("\x19Ethereum Signed Message:\n{}", message.len()).into_bytes();
+	preimage.extend_from_slice(message);
+	H256::from(keccak_256(&preimage))
+}
+
+fn typed_data_hash(typed_data: &TypedData) -> Result<H256, ErrorObjectOwned> {
+	typed_data
+		.signing_hash()
+		.map_err(|err| internal_err(format!("invalid typed data: {err}")))
+}
+
+/// Sign a hash, returning the `r ‖ s ‖ v` signature with `v` being 27 or 28.
+fn sign_hash(hash: H256, secret: &libsecp256k1::SecretKey) -> Result<Bytes, ErrorObjectOwned> {
+	let signing_message = libsecp256k1::Message::parse_slice(&hash[..])
+		.map_err(|_| internal_err("invalid signing message"))?;
+	let (signature, recid) = libsecp256k1::sign(&signing_message, secret);
+	let mut bytes = signature.serialize().to_vec();
+	bytes.push(27 + recid.serialize());
+	Ok(Bytes::new(bytes))
+}
+
+/// Sign a transaction message with the given secret key.
+fn sign_transaction_message(
+	message: TransactionMessage,
+	secret: &libsecp256k1::SecretKey,
+) -> Result<EthereumTransaction, ErrorObjectOwned> {
+	match message {
+		TransactionMessage::Legacy(m) => {
+			let signing_message = libsecp256k1::Message::parse_slice(&m.hash()[..])
+				.map_err(|_| internal_err("invalid signing message"))?;
+			let (signature, recid) = libsecp256k1::sign(&signing_message, secret);
+			let v = match m.chain_id {
+				None => 27 + recid.serialize() as u64,
+				Some(chain_id) => 2 * chain_id + 35 + recid.serialize() as u64,
+			};
+			let rs = signature.serialize();
+			let r = H256::from_slice(&rs[0..32]);
+			let s = H256::from_slice(&rs[32..64]);
+			Ok(EthereumTransaction::Legacy(ethereum::LegacyTransaction {
+				nonce: m.nonce,
+				gas_price: m.gas_price,
+				gas_limit: m.gas_limit,
+				action: m.action,
+				value: m.value,
+				input: m.input,
+				signature: legacy::TransactionSignature::new(v, r, s)
+					.ok_or_else(|| internal_err("signer generated invalid signature"))?,
+			}))
+		}
+		TransactionMessage::EIP2930(m) => {
+			let signing_message = libsecp256k1::Message::parse_slice(&m.hash()[..])
+				.map_err(|_| internal_err("invalid signing message"))?;
+			let (signature, recid) = libsecp256k1::sign(&signing_message, secret);
+			let rs = signature.serialize();
+			let r = H256::from_slice(&rs[0..32]);
+			let s = H256::from_slice(&rs[32..64]);
+			Ok(EthereumTransaction::EIP2930(ethereum::EIP2930Transaction {
+				chain_id: m.chain_id,
+				nonce: m.nonce,
+				gas_price: m.gas_price,
+				gas_limit: m.gas_limit,
+				action: m.action,
+				value: m.value,
+				input: m.input.clone(),
+				access_list: m.access_list,
+				signature: eip2930::TransactionSignature::new(recid.serialize() != 0, r, s)
+					.ok_or(internal_err("Invalid transaction signature format"))?,
+			}))
+		}
+		TransactionMessage::EIP1559(m) => {
+			let signing_message = libsecp256k1::Message::parse_slice(&m.hash()[..])
+				.map_err(|_| internal_err("invalid signing message"))?;
+			let (signature, recid) = libsecp256k1::sign(&signing_message, secret);
+			let rs = signature.serialize();
+			let r = H256::from_slice(&rs[0..32]);
+			let s = H256::from_slice(&rs[32..64]);
+			Ok(EthereumTransaction::EIP1559(ethereum::EIP1559Transaction {
+				chain_id: m.chain_id,
+				nonce: m.nonce,
+				max_priority_fee_per_gas: m.max_priority_fee_per_gas,
+				max_fee_per_gas: m.max_fee_per_gas,
+				gas_limit: m.gas_limit,
+				action: m.action,
+				value: m.value,
+				input: m.input.clone(),
+				access_list: m.access_list,
+				signature: eip2930::TransactionSignature::new(recid.serialize() != 0, r, s)
+					.ok_or(internal_err("Invalid transaction signature format"))?,
+			}))
+		}
+		TransactionMessage::EIP7702(mut m) => {
+			// Authorizations left unsigned are signed by the sender.
+			for authorization in m
+				.authorization_list
+				.iter_mut()
+				.filter(|item| item.signature.r.is_zero() && item.signature.s.is_zero())
+			{
+				sign_authorization(authorization, secret)?;
+			}
+			let signing_message = libsecp256k1::Message::parse_slice(&m.hash()[..])
+				.map_err(|_| internal_err("invalid signing message"))?;
+			let (signature, recid) = libsecp256k1::sign(&signing_message, secret);
+			let rs = signature.serialize();
+			let r = H256::from_slice(&rs[0..32]);
+			let s = H256::from_slice(&rs[32..64]);
+			Ok(EthereumTransaction::EIP7702(ethereum::EIP7702Transaction {
+				chain_id: m.chain_id,
+				nonce: m.nonce,
+				max_priority_fee_per_gas: m.max_priority_fee_per_gas,
+				max_fee_per_gas: m.max_fee_per_gas,
+				gas_limit: m.gas_limit,
+				destination: m.destination,
+				value: m.value,
+				data: m.data.clone(),
+				access_list: m.access_list,
+				authorization_list: m.authorization_list,
+				signature: eip2930::TransactionSignature::new(recid.serialize() != 0, r, s)
+					.ok_or(internal_err("Invalid transaction signature format"))?,
+			}))
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn recover(hash: H256, signature: &Bytes) -> H160 {
+		let message = libsecp256k1::Message::parse_slice(&hash[..]).unwrap();
+		let rs = libsecp256k1::Signature::parse_standard_slice(&signature.0[..64]).unwrap();
+		let recid = libsecp256k1::RecoveryId::parse_rpc(signature.0[64]).unwrap();
+		public_key_address(&libsecp256k1::recover(&message, &rs, &recid).unwrap())
+	}
+
+	#[test]
+	fn signs_message_with_prefix() {
+		let signer = EthDevSigner::new();
+		let address = signer.accounts()[0];
+		let signature = signer.sign_message(b"hello", &address).unwrap();
+		assert_eq!(signature.0.len(), 65);
+		assert_eq!(
+			recover(personal_message_hash(b"hello"), &signature),
+			address
+		);
+	}
+
+	#[test]
+	fn signs_unsigned_authorizations_with_sender() {
+		let signer = EthDevSigner::new();
+		let address = signer.accounts()[0];
+		let authorization = AuthorizationListItem {
+			chain_id: 42,
+			address: H160::repeat_byte(0x22),
+			nonce: 1.into(),
+			signature: eip2930::MalleableTransactionSignature {
+				odd_y_parity: false,
+				r: H256::zero(),
+				s: H256::zero(),
+			},
+		};
+		let message = TransactionMessage::EIP7702(ethereum::EIP7702TransactionMessage {
+			chain_id: 42,
+			nonce: 0.into(),
+			max_priority_fee_per_gas: 1.into(),
+			max_fee_per_gas: 1.into(),
+			gas_limit: 100_000.into(),
+			destination: ethereum::TransactionAction::Call(address),
+			value: 0.into(),
+			data: vec![],
+			access_list: vec![],
+			authorization_list: vec![authorization],
+		});
+
+		let EthereumTransaction::EIP7702(transaction) = signer.sign(message, &address).unwrap()
+		else {
+			panic!("expected an EIP-7702 transaction");
+		};
+		assert_eq!(
+			transaction.authorization_list[0]
+				.authorizing_address()
+				.unwrap(),
+			address
+		);
+	}
+
+	#[test]
+	fn signs_typed_data_hash() {
+		// The hashing itself is checked against the EIP-712 example in `fc_rpc_core`.
+		let typed_data: TypedData = serde_json::from_value(serde_json::json!({
+			"types": {
+				"EIP712Domain": [{ "name": "name", "type": "string" }],
+				"Greeting": [{ "name": "contents", "type": "string" }]
+			},
+			"primaryType": "Greeting",
+			"domain": { "name": "Frontier" },
+			"message": { "contents": "hello" }
+		}))
+		.unwrap();
+		let signer = EthDevSigner::new();
+		let address = signer.accounts()[0];
+
+		let signature = signer.sign_typed_data(&typed_data, &address).unwrap();
+		assert_eq!(signature.0.len(), 65);
+		assert_eq!(
+			recover(typed_data.signing_hash().unwrap(), &signature),
+			address
+		);
+	}
+}
@@ -0,0 +1,548 @@
+// This file is part of Frontier.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Signer delegating to an external signing service over HTTP JSON-RPC (Clef, Web3Signer).
+
+use std::{
+	sync::Mutex,
+	time::{Duration, Instant},
+};
+
+use ethereum::{TransactionAction, TransactionV3 as EthereumTransaction};
+use ethereum_types::{H160, H256, U256, U64};
+use jsonrpsee::{
+	core::{client::ClientT, params::ArrayParams, ClientError},
+	http_client::{HttpClient, HttpClientBuilder},
+	rpc_params,
+	types::ErrorObjectOwned,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tokio::runtime::RuntimeFlavor;
+// Substrate
+use sp_core::hashing::keccak_256;
+// Frontier
+use fc_rpc_core::types::{Bytes, TransactionMessage, TypedData};
+
+use super::EthSigner;
+use crate::internal_err;
+
+/// JSON-RPC API exposed by the signing service.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RemoteSignerApi {
+	/// Clef external API (`account_*` methods).
+	Clef,
+	/// Web3Signer Ethereum API (`eth_*` methods).
+	Web3Signer,
+}
+
+impl RemoteSignerApi {
+	fn accounts_method(&self) -> &'static str {
+		match self {
+			Self::Clef => "account_list",
+			Self::Web3Signer => "eth_accounts",
+		}
+	}
+
+	fn sign_transaction_method(&self) -> &'static str {
+		match self {
+			Self::Clef => "account_signTransaction",
+			Self::Web3Signer => "eth_signTransaction",
+		}
+	}
+
+	fn sign_typed_data_method(&self) -> &'static str {
+		match self {
+			Self::Clef => "account_signTypedData",
+			Self::Web3Signer => "eth_signTypedData",
+		}
+	}
+}
+
+/// Configuration of [`EthRemoteSigner`].
+#[derive(Clone, Debug)]
+pub struct RemoteSignerConfig {
+	/// HTTP endpoint of the signing service.
+	pub endpoint: String,
+	/// JSON-RPC API exposed by the signing service.
+	pub api: RemoteSignerApi,
+	/// Timeout of a request to the signing service.
+	pub timeout: Duration,
+	/// How long the account list of the signing service is cached.
+	pub accounts_cache_duration: Duration,
+}
+
+/// A signer delegating to an external signing service, so that no key is held by the node.
+///
+/// Requests to the service are made from the calling thread, which must belong to a
+/// multi-threaded tokio runtime, otherwise they fail.
+pub struct EthRemoteSigner {
+	client: HttpClient,
+	api: RemoteSignerApi,
+	accounts_cache_duration: Duration,
+	accounts: Mutex<Option<(Instant, Vec<H160>)>>,
+}
+
+impl EthRemoteSigner {
+	pub fn new(config: RemoteSignerConfig) -> Result<Self, ClientError> {
+		let client = HttpClientBuilder::default()
+			.request_timeout(config.timeout)
+			.build(&config.endpoint)?;
+		Ok(Self {
+			client,
+			api: config.api,
+			accounts_cache_duration: config.accounts_cache_duration,
+			accounts: Mutex::new(None),
+		})
+	}
+
+	fn request<R: DeserializeOwned>(
+		&self,
+		method: &str,
+		params: ArrayParams,
+	) -> Result<R, ErrorObjectOwned> {
+		let handle = tokio::runtime::Handle::try_current()
+			.map_err(|_| internal_err("remote signer used outside of a tokio runtime"))?;
+		// `block_in_place` panics on a current-thread runtime.
+		if handle.runtime_flavor() == RuntimeFlavor::CurrentThread {
+			return Err(internal_err(
+				"remote signer used on a current-thread tokio runtime",
+			));
+		}
+		tokio::task::block_in_place(|| handle.block_on(self.client.request::<R, _>(method, params)))
+			.map_err(|err| match err {
+				ClientError::Call(err) => internal_err(format!("remote signer: {}", err.message())),
+				err => internal_err(format!("remote signer unavailable: {err}")),
+			})
+	}
+
+	fn fetch_accounts(&self) -> Result<Vec<H160>, ErrorObjectOwned> {
+		self.request(self.api.accounts_method(), rpc_params![])
+	}
+}
+
+impl EthSigner for EthRemoteSigner {
+	fn accounts(&self) -> Vec<H160> {
+		let cached = self.accounts.lock().ok().and_then(|cache| cache.clone());
+		if let Some((fetched_at, accounts)) = &cached {
+			if fetched_at.elapsed() < self.accounts_cache_duration {
+				return accounts.clone();
+			}
+		}
+		// The lock is not held while querying the service, not to stall the other callers.
+		match self.fetch_accounts() {
+			Ok(accounts) => {
+				if let Ok(mut cache) = self.accounts.lock() {
+					*cache = Some((Instant::now(), accounts.clone()));
+				}
+				accounts
+			}
+			Err(err) => {
+				log::warn!(target: "eth-remote-signer", "Failed to fetch accounts: {}", err.message());
+				// Keep serving the stale list while the service is unreachable.
+				cached.map(|(_, accounts)| accounts).unwrap_or_default()
+			}
+		}
+	}
+
+	fn sign(
+		&self,
+		message: TransactionMessage,
+		address: &H160,
+	) -> Result<EthereumTransaction, ErrorObjectOwned> {
+		let args = TransactionArgs::new(message.clone(), *address);
+		let response: SignTransactionResponse =
+			self.request(self.api.sign_transaction_method(), rpc_params![args])?;
+		let raw = match response {
+			SignTransactionResponse::Rich { raw } | SignTransactionResponse::Raw(raw) => raw,
+		};
+		let transaction = ethereum::EnvelopedDecodable::decode(&raw.0)
+			.map_err(|_| internal_err("remote signer returned an invalid transaction"))?;
+		verify_transaction(&transaction, message, address)?;
+		Ok(transaction)
+	}
+
+	fn sign_message(&self, message: &[u8], address: &H160) -> Result<Bytes, ErrorObjectOwned> {
+		let data = Bytes::new(message.to_vec());
+		match self.api {
+			RemoteSignerApi::Clef => {
+				self.request("account_signData", rpc_params!["text/plain", address, data])
+			}
+			RemoteSignerApi::Web3Signer => self.request("eth_sign", rpc_params![address, data]),
+		}
+	}
+
+	fn sign_typed_data(
+		&self,
+		typed_data: &TypedData,
+		address: &H160,
+	) -> Result<Bytes, ErrorObjectOwned> {
+		self.request(
+			self.api.sign_typed_data_method(),
+			rpc_params![address, typed_data],
+		)
+	}
+}
+
+/// Checks that the transaction returned by the signing service is the requested one, signed by
+/// `address`.
+fn verify_transaction(
+	transaction: &EthereumTransaction,
+	message: TransactionMessage,
+	address: &H160,
+) -> Result<(), ErrorObjectOwned> {
+	let public = crate::public_key(transaction)
+		.map_err(|_| internal_err("remote signer returned an invalid signature"))?;
+	if H160::from(H256::from(keccak_256(&public))) != *address {
+		return Err(internal_err(
+			"remote signer signed the transaction with another account",
+		));
+	}
+
+	let matches = match (message, transaction) {
+		(TransactionMessage::Legacy(m), EthereumTransaction::Legacy(t)) => m == t.clone().into(),
+		(TransactionMessage::EIP2930(m), EthereumTransaction::EIP2930(t)) => m == t.clone().into(),
+		(TransactionMessage::EIP1559(m), EthereumTransaction::EIP1559(t)) => m == t.clone().into(),
+		(TransactionMessage::EIP7702(mut m), EthereumTransaction::EIP7702(t)) => {
+			let signed = ethereum::EIP7702TransactionMessage::from(t.clone());
+			// Authorizations left unsigned are signed by the service on behalf of the sender.
+			let authorizations_match = m.authorization_list.len()
+				== signed.authorization_list.len()
+				&& m.authorization_list
+					.iter()
+					.zip(&signed.authorization_list)
+					.all(|(requested, signed)| {
+						if requested.signature.r.is_zero() && requested.signature.s.is_zero() {
+							requested.chain_id == signed.chain_id
+								&& requested.address == signed.address
+								&& requested.nonce == signed.nonce
+								&& signed.authorizing_address().ok() == Some(*address)
+						} else {
+							requested == signed
+						}
+					});
+			m.authorization_list = signed.authorization_list.clone();
+			authorizations_match && m == signed
+		}
+		_ => false,
+	};
+	if !matches {
+		return Err(internal_err(
+			"remote signer returned another transaction than the requested one",
+		));
+	}
+	Ok(())
+}
+
+/// Transaction arguments as taken by `eth_signTransaction`.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TransactionArgs {
+	#[serde(rename = "type")]
+	transaction_type: U64,
+	from: H160,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	to: Option<H160>,
+	nonce: U256,
+	gas: U256,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	gas_price: Option<U256>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	max_fee_per_gas: Option<U256>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	max_priority_fee_per_gas: Option<U256>,
+	value: U256,
+	// Set under both names, as signers disagree on which one to read.
+	data: Bytes,
+	input: Bytes,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	chain_id: Option<U64>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	access_list: Option<Vec<AccessListArg>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	authorization_list: Option<Vec<AuthorizationArg>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AccessListArg {
+	address: H160,
+	storage_keys: Vec<H256>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AuthorizationArg {
+	chain_id: U64,
+	address: H160,
+	nonce: U256,
+	y_parity: U64,
+	r: H256,
+	s: H256,
+}
+
+impl TransactionArgs {
+	fn new(message: TransactionMessage, from: H160) -> Self {
+		let to = |action: TransactionAction| match action {
+			TransactionAction::Call(to) => Some(to),
+			TransactionAction::Create => None,
+		};
+		let access_list = |access_list: Vec<ethereum::AccessListItem>| {
+			Some(
+				access_list
+					.into_iter()
+					.map(|item| AccessListArg {
+						address: item.address,
+						storage_keys: item.storage_keys,
+					})
+					.collect(),
+			)
+		};
+
+		match message {
+			TransactionMessage::Legacy(m) => Self {
+				transaction_type: U64::zero(),
+				from,
+				to: to(m.action),
+				nonce: m.nonce,
+				gas: m.gas_limit,
+				gas_price: Some(m.gas_price),
+				max_fee_per_gas: None,
+				max_priority_fee_per_gas: None,
+				value: m.value,
+				data: Bytes::new(m.input.clone()),
+				input: Bytes::new(m.input),
+				chain_id: m.chain_id.map(U64::from),
+				access_list: None,
+				authorization_list: None,
+			},
+			TransactionMessage::EIP2930(m) => Self {
+				transaction_type: U64::one(),
+				from,
+				to: to(m.action),
+				nonce: m.nonce,
+				gas: m.gas_limit,
+				gas_price: Some(m.gas_price),
+				max_fee_per_gas: None,
+				max_priority_fee_per_gas: None,
+				value: m.value,
+				data: Bytes::new(m.input.clone()),
+				input: Bytes::new(m.input),
+				chain_id: Some(m.chain_id.into()),
+				access_list: access_list(m.access_list),
+				authorization_list: None,
+			},
+			TransactionMessage::EIP1559(m) => Self {
+				transaction_type: U64::from(2),
+				from,
+				to: to(m.action),
+				nonce: m.nonce,
+				gas: m.gas_limit,
+				gas_price: None,
+				max_fee_per_gas: Some(m.max_fee_per_gas),
+				max_priority_fee_per_gas: Some(m.max_priority_fee_per_gas),
+				value: m.value,
+				data: Bytes::new(m.input.clone()),
+				input: Bytes::new(m.input),
+				chain_id: Some(m.chain_id.into()),
+				access_list: access_list(m.access_list),
+				authorization_list: None,
+			},
+			TransactionMessage::EIP7702(m) => Self {
+				transaction_type: U64::from(4),
+				from,
+				to: to(m.destination),
+				nonce: m.nonce,
+				gas: m.gas_limit,
+				gas_price: None,
+				max_fee_per_gas: Some(m.max_fee_per_gas),
+				max_priority_fee_per_gas: Some(m.max_priority_fee_per_gas),
+				value: m.value,
+				data: Bytes::new(m.data.clone()),
+				input: Bytes::new(m.data),
+				chain_id: Some(m.chain_id.into()),
+				access_list: access_list(m.access_list),
+				authorization_list: Some(
+					m.authorization_list
+						.into_iter()
+						.map(|item| AuthorizationArg {
+							chain_id: item.chain_id.into(),
+							address: item.address,
+							nonce: item.nonce,
+							y_parity: U64::from(item.signature.odd_y_parity as u64),
+							r: item.signature.r,
+							s: item.signature.s,
+						})
+						.collect(),
+				),
+			},
+		}
+	}
+}
+
+/// Clef answers with the transaction along with its details, Web3Signer with the transaction only.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SignTransactionResponse {
+	Rich { raw: Bytes },
+	Raw(Bytes),
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::{
+		atomic::{AtomicUsize, Ordering},
+		Arc,
+	};
+
+	use jsonrpsee::{
+		server::{Server, ServerHandle},
+		RpcModule,
+	};
+
+	use super::*;
+	use crate::signer::EthDevSigner;
+	use fc_rpc_core::types::TransactionRequest;
+
+	/// Web3Signer stand-in holding the dev account, counting the account list requests.
+	async fn mock_service() -> (String, Arc<AtomicUsize>, ServerHandle) {
+		let accounts_requests = Arc::new(AtomicUsize::new(0));
+		let mut module = RpcModule::new(EthDevSigner::new());
+
+		let requests = accounts_requests.clone();
+		module
+			.register_method("eth_accounts", move |_, signer, _| {
+				requests.fetch_add(1, Ordering::SeqCst);
+				signer.accounts()
+			})
+			.unwrap();
+		module
+			.register_method("eth_signTransaction", |params, signer, _| {
+				let request: TransactionRequest = params.one()?;
+				let from = request.from.unwrap_or_default();
+				let message: Option<TransactionMessage> = request.into();
+				let message = message.ok_or_else(|| internal_err("invalid transaction"))?;
+				let transaction = signer.sign(message, &from)?;
+				Ok::<_, ErrorObjectOwned>(Bytes::new(
+					ethereum::EnvelopedEncodable::encode(&transaction).to_vec(),
+				))
+			})
+			.unwrap();
+		module
+			.register_method("eth_sign", |params, signer, _| {
+				let (address, data): (H160, Bytes) = params.parse()?;
+				signer.sign_message(&data.0, &address)
+			})
+			.unwrap();
+
+		let server = Server::builder().build("127.0.0.1:0").await.unwrap();
+		let endpoint = format!("http://{}", server.local_addr().unwrap());
+		(endpoint, accounts_requests, server.start(module))
+	}
+
+	fn remote_signer(endpoint: String) -> EthRemoteSigner {
+		EthRemoteSigner::new(RemoteSignerConfig {
+			endpoint,
+			api: RemoteSignerApi::Web3Signer,
+			timeout: Duration::from_secs(5),
+			accounts_cache_duration: Duration::from_secs(60),
+		})
+		.unwrap()
+	}
+
+	fn transaction_message() -> TransactionMessage {
+		TransactionMessage::EIP1559(ethereum::EIP1559TransactionMessage {
+			chain_id: 42,
+			nonce: 3.into(),
+			max_priority_fee_per_gas: 1.into(),
+			max_fee_per_gas: 2_000_000_000.into(),
+			gas_limit: 21_000.into(),
+			action: TransactionAction::Call(H160::repeat_byte(0x22)),
+			value: 1_000.into(),
+			input: vec![0x12, 0x34],
+			access_list: vec![],
+		})
+	}
+
+	#[tokio::test(flavor = "multi_thread")]
+	async fn caches_remote_accounts() {
+		let (endpoint, accounts_requests, _server) = mock_service().await;
+		let signer = remote_signer(endpoint);
+
+		let expected = EthDevSigner::new().accounts();
+		assert_eq!(signer.accounts(), expected);
+		assert_eq!(signer.accounts(), expected);
+		assert_eq!(accounts_requests.load(Ordering::SeqCst), 1);
+	}
+
+	#[tokio::test(flavor = "multi_thread")]
+	async fn signs_with_remote_keys() {
+		let (endpoint, _, _server) = mock_service().await;
+		let signer = remote_signer(endpoint);
+		let local = EthDevSigner::new();
+		let address = local.accounts()[0];
+
+		assert_eq!(
+			signer.sign(transaction_message(), &address).unwrap(),
+			local.sign(transaction_message(), &address).unwrap()
+		);
+		assert_eq!(
+			signer.sign_message(b"hello", &address).unwrap(),
+			local.sign_message(b"hello", &address).unwrap()
+		);
+	}
+
+	#[tokio::test(flavor = "multi_thread")]
+	async fn fails_when_service_is_unreachable() {
+		let signer = remote_signer("http://127.0.0.1:1".into());
+		let address = EthDevSigner::new().accounts()[0];
+
+		assert!(signer.accounts().is_empty());
+		assert!(signer.sign_message(b"hello", &address).is_err());
+	}
+
+	#[tokio::test]
+	async fn fails_on_current_thread_runtime() {
+		let (endpoint, _, _server) = mock_service().await;
+		let signer = remote_signer(endpoint);
+		let address = EthDevSigner::new().accounts()[0];
+
+		assert!(signer.sign_message(b"hello", &address).is_err());
+	}
+
+	#[test]
+	fn rejects_transactions_other_than_requested() {
+		let local = EthDevSigner::new();
+		let address = local.accounts()[0];
+		let transaction = local.sign(transaction_message(), &address).unwrap();
+		assert!(verify_transaction(&transaction, transaction_message(), &address).is_ok());
+
+		let TransactionMessage::EIP1559(mut other) = transaction_message() else {
+			unreachable!();
+		};
+		other.nonce = 4.into();
+		assert!(
+			verify_transaction(&transaction, TransactionMessage::EIP1559(other), &address).is_err()
+		);
+		assert!(verify_transaction(
+			&transaction,
+			transaction_message(),
+			&H160::repeat_byte(0x11)
+		)
+		.is_err());
+	}
+}
@@ -0,0 +1,450 @@
+// This file is part of Frontier.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use ethereum_types::{H256, U256};
+use evm::{ExitError, ExitReason};
+use jsonrpsee::core::{async_trait, RpcResult};
+// Substrate
+use sc_client_api::{
+	backend::{Backend, StorageProvider},
+	BlockBackend,
+};
+use sp_api::{CallApiAt, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::{Block as BlockT, UniqueSaturatedInto};
+// Frontier
+use fc_rpc_core::{types::*, TraceApiServer};
+use fp_evm::tracing::{self as runtime, TraceResult};
+
+use crate::{
+	cache::EthBlockDataCacheTask,
+	debug::{format, replay_block},
+	frontier_backend_client, internal_err,
+};
+
+/// Trace API implementation.
+pub struct Trace<B: BlockT, C, BE> {
+	client: Arc<C>,
+	backend: Arc<dyn fc_api::Backend<B>>,
+	block_data_cache: Arc<EthBlockDataCacheTask<B>>,
+	max_block_range: u32,
+	_marker: PhantomData<BE>,
+}
+
+impl<B: BlockT, C, BE> Trace<B, C, BE> {
+	pub fn new(
+		client: Arc<C>,
+		backend: Arc<dyn fc_api::Backend<B>>,
+		block_data_cache: Arc<EthBlockDataCacheTask<B>>,
+		max_block_range: u32,
+	) -> Self {
+		Self {
+			client,
+			backend,
+			block_data_cache,
+			max_block_range,
+			_marker: PhantomData,
+		}
+	}
+}
+
+impl<B, C, BE> Trace<B, C, BE>
+where
+	B: BlockT,
+	C: CallApiAt<B> + ProvideRuntimeApi<B>,
+	C: HeaderBackend<B> + BlockBackend<B> + StorageProvider<B, BE> + 'static,
+	BE: Backend<B> + 'static,
+{
+	async fn substrate_hash_by(&self, number: BlockNumberOrHash) -> RpcResult<Option<B::Hash>> {
+		let id = match frontier_backend_client::native_block_id::<B, C>(
+			self.client.as_ref(),
+			self.backend.as_ref(),
+			Some(number),
+		)
+		.await?
+		{
+			Some(id) => id,
+			None => return Ok(None),
+		};
+
+		let substrate_hash = self
+			.client
+			.expect_block_hash_from_id(&id)
+			.map_err(|_| internal_err(format!("Expect block number from id: {id}")))?;
+		Ok(Some(substrate_hash))
+	}
+
+	/// Replays all the transactions of the block and returns their flat traces.
+	async fn block_traces(&self, substrate_hash: B::Hash) -> RpcResult<Vec<LocalizedTrace>> {
+		let block = match self.block_data_cache.current_block(substrate_hash).await {
+			Some(block) => block,
+			None => return Err(internal_err("block not found")),
+		};
+		if block.transactions.is_empty() {
+			return Ok(vec![]);
+		}
+
+		let results = replay_block::<B, C, Vec<TraceResult>>(
+			self.client.as_ref(),
+			substrate_hash,
+			"DebugRuntimeApi_trace_block",
+			call_tracer_config(),
+		)?
+		.map_err(|err| internal_err(format!("failed to trace block: {err:?}")))?;
+		if results.len() != block.transactions.len() {
+			return Err(internal_err(format!(
+				"expected {} traces, got {}",
+				block.transactions.len(),
+				results.len()
+			)));
+		}
+
+		let block_hash = block.header.hash();
+		let mut traces = Vec::new();
+		for (position, (transaction, result)) in block.transactions.iter().zip(results).enumerate()
+		{
+			let location = TraceLocation {
+				transaction_hash: transaction.hash(),
+				transaction_position: position,
+				block_hash,
+				block_number: block.header.number,
+			};
+			flatten(call_frame(result)?, vec![], &location, &mut traces);
+		}
+		Ok(traces)
+	}
+
+	async fn transaction_traces(&self, hash: H256) -> RpcResult<Option<Vec<LocalizedTrace>>> {
+		let (eth_block_hash, index) = match frontier_backend_client::load_transactions::<B, C>(
+			self.client.as_ref(),
+			self.backend.as_ref(),
+			hash,
+			true,
+		)
+		.await?
+		{
+			Some((hash, index)) => (hash, index as usize),
+			None => return Ok(None),
+		};
+
+		let substrate_hash = match frontier_backend_client::load_hash::<B, C>(
+			self.client.as_ref(),
+			self.backend.as_ref(),
+			eth_block_hash,
+		)
+		.await?
+		{
+			Some(hash) => hash,
+			None => return Ok(None),
+		};
+		let block_number = match self.block_data_cache.current_block(substrate_hash).await {
+			Some(block) => block.header.number,
+			None => return Ok(None),
+		};
+
+		let result = replay_block::<B, C, TraceResult>(
+			self.client.as_ref(),
+			substrate_hash,
+			"DebugRuntimeApi_trace_transaction",
+			(hash, call_tracer_config()),
+		)?
+		.map_err(|err| internal_err(format!("failed to trace transaction: {err:?}")))?;
+
+		let location = TraceLocation {
+			transaction_hash: hash,
+			transaction_position: index,
+			block_hash: eth_block_hash,
+			block_number,
+		};
+		let mut traces = Vec::new();
+		flatten(call_frame(result)?, vec![], &location, &mut traces);
+		Ok(Some(traces))
+	}
+
+	async fn filter_traces(&self, filter: TraceFilter) -> RpcResult<Vec<LocalizedTrace>> {
+		let best_number = self.client.info().best_number;
+		let mut to_number = filter
+			.to_block
+			.and_then(|v| v.to_min_block_num())
+			.map(|s| s.unique_saturated_into())
+			.unwrap_or(best_number);
+		if to_number > best_number {
+			to_number = best_number;
+		}
+		let from_number = filter
+			.from_block
+			.and_then(|v| v.to_min_block_num())
+			.map(|s| s.unique_saturated_into())
+			.unwrap_or(best_number);
+
+		let block_range = to_number.saturating_sub(from_number);
+		if block_range > self.max_block_range.into() {
+			return Err(internal_err(format!(
+				"block range is too wide (maximum {})",
+				self.max_block_range
+			)));
+		}
+
+		let mut skip = filter.after.unwrap_or(0);
+		let count = filter.count.unwrap_or(usize::MAX);
+		let mut traces = Vec::new();
+		let from_number: u64 = from_number.unique_saturated_into();
+		let to_number: u64 = to_number.unique_saturated_into();
+		for number in from_number..=to_number {
+			let substrate_hash = match self
+				.substrate_hash_by(BlockNumberOrHash::Num(number))
+				.await?
+			{
+				Some(hash) => hash,
+				None => continue,
+			};
+			for trace in self.block_traces(substrate_hash).await? {
+				if !filter.matches(&trace) {
+					continue;
+				}
+				if skip > 0 {
+					skip -= 1;
+					continue;
+				}
+				traces.push(trace);
+				if traces.len() >= count {
+					return Ok(traces);
+				}
+			}
+		}
+		Ok(traces)
+	}
+}
+
+#[async_trait]
+impl<B, C, BE> TraceApiServer for Trace<B, C, BE>
+where
+	B: BlockT,
+	C: CallApiAt<B> + ProvideRuntimeApi<B>,
+	C: HeaderBackend<B> + BlockBackend<B> + StorageProvider<B, BE> + 'static,
+	BE: Backend<B> + 'static,
+{
+	async fn trace_block(
+		&self,
+		number: BlockNumberOrHash,
+	) -> RpcResult<Option<Vec<LocalizedTrace>>> {
+		let substrate_hash = match self.substrate_hash_by(number).await? {
+			Some(hash) => hash,
+			None => return Ok(None),
+		};
+		self.block_traces(substrate_hash).await.map(Some)
+	}
+
+	async fn trace_transaction(&self, hash: H256) -> RpcResult<Option<Vec<LocalizedTrace>>> {
+		self.transaction_traces(hash).await
+	}
+
+	async fn trace_filter(&self, filter: TraceFilter) -> RpcResult<Vec<LocalizedTrace>> {
+		self.filter_traces(filter).await
+	}
+}
+
+/// Position of the traced transaction in the chain.
+struct TraceLocation {
+	transaction_hash: H256,
+	transaction_position: usize,
+	block_hash: H256,
+	block_number: U256,
+}
+
+/// The flat traces are built from the full call tree of the call tracer.
+fn call_tracer_config() -> runtime::TracerConfig {
+	runtime::TracerConfig::CallTracer(runtime::CallTracerConfig {
+		only_top_call: false,
+	})
+}
+
+fn call_frame(result: TraceResult) -> RpcResult<runtime::CallFrame> {
+	match result {
+		TraceResult::CallTracer(frame) => Ok(frame),
+		_ => Err(internal_err("unexpected trace result")),
+	}
+}
+
+/// Append the traces of `frame` and its sub-calls to `traces`, in depth-first order.
+fn flatten(
+	frame: runtime::CallFrame,
+	trace_address: Vec<usize>,
+	location: &TraceLocation,
+	traces: &mut Vec<LocalizedTrace>,
+) {
+	let error = frame.exit_reason.as_ref().and_then(trace_error);
+	let gas = U256::from(frame.gas);
+	let gas_used = U256::from(frame.gas_used);
+	let value = frame.value.unwrap_or_default();
+	let (action, result) = match frame.call_type {
+		runtime::CallType::Create | runtime::CallType::Create2 => {
+			let action = TraceAction::Create(CreateAction {
+				from: frame.from,
+				gas,
+				init: frame.input.into(),
+				value,
+			});
+			let result = CreateOutput {
+				address: frame.to.unwrap_or_default(),
+				code: frame.output.into(),
+				gas_used,
+			};
+			(action, TraceOutput::Create(result))
+		}
+		runtime::CallType::SelfDestruct => {
+			let action = TraceAction::Suicide(SuicideAction {
+				address: frame.from,
+				refund_address: frame.to.unwrap_or_default(),
+				balance: value,
+			});
+			let trace = LocalizedTrace {
+				action,
+				result: None,
+				error: None,
+				subtraces: 0,
+				trace_address,
+				transaction_hash: Some(location.transaction_hash),
+				transaction_position: Some(location.transaction_position),
+				block_hash: location.block_hash,
+				block_number: location.block_number,
+			};
+			traces.push(trace);
+			return;
+		}
+		call_type => {
+			let action = TraceAction::Call(CallAction {
+				call_type: call_type_name(call_type).into(),
+				from: frame.from,
+				to: frame.to.unwrap_or_default(),
+				gas,
+				input: frame.input.into(),
+				value,
+			});
+			let result = CallOutput {
+				gas_used,
+				output: frame.output.into(),
+			};
+			(action, TraceOutput::Call(result))
+		}
+	};
+
+	traces.push(LocalizedTrace {
+		action,
+		result: error.is_none().then_some(result),
+		error,
+		subtraces: frame.calls.len(),
+		trace_address: trace_address.clone(),
+		transaction_hash: Some(location.transaction_hash),
+		transaction_position: Some(location.transaction_position),
+		block_hash: location.block_hash,
+		block_number: location.block_number,
+	});
+	for (index, call) in frame.calls.into_iter().enumerate() {
+		let mut address = trace_address.clone();
+		address.push(index);
+		flatten(call, address, location, traces);
+	}
+}
+
+fn call_type_name(call_type: runtime::CallType) -> &'static str {
+	match call_type {
+		runtime::CallType::CallCode => "callcode",
+		runtime::CallType::DelegateCall => "delegatecall",
+		runtime::CallType::StaticCall => "staticcall",
+		_ => "call",
+	}
+}
+
+/// Error message of a failed frame, using Parity's wording where there is an equivalent.
+fn trace_error(reason: &ExitReason) -> Option<String> {
+	let error = match reason {
+		ExitReason::Succeed(_) => return None,
+		ExitReason::Revert(_) => "Reverted",
+		ExitReason::Error(ExitError::OutOfGas) => "Out of gas",
+		ExitReason::Error(ExitError::InvalidJump) => "Bad jump destination",
+		ExitReason::Error(ExitError::StackUnderflow) => "Stack underflow",
+		ExitReason::Error(ExitError::StackOverflow) => "Out of stack",
+		ExitReason::Error(ExitError::DesignatedInvalid | ExitError::InvalidCode(_)) => {
+			"Bad instruction"
+		}
+		reason => return format::exit_error(reason),
+	};
+	Some(error.into())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ethereum_types::H160;
+	use evm::{ExitRevert, ExitSucceed};
+
+	fn frame(call_type: runtime::CallType, calls: Vec<runtime::CallFrame>) -> runtime::CallFrame {
+		runtime::CallFrame {
+			call_type,
+			from: H160::from_low_u64_be(1),
+			to: Some(H160::from_low_u64_be(2)),
+			value: Some(U256::zero()),
+			gas: 100,
+			gas_used: 10,
+			input: vec![],
+			output: vec![],
+			exit_reason: Some(ExitReason::Succeed(ExitSucceed::Returned)),
+			calls,
+		}
+	}
+
+	#[test]
+	fn flattens_call_tree() {
+		let mut reverted = frame(runtime::CallType::DelegateCall, vec![]);
+		reverted.exit_reason = Some(ExitReason::Revert(ExitRevert::Reverted));
+		let root = frame(
+			runtime::CallType::Call,
+			vec![
+				frame(
+					runtime::CallType::Create,
+					vec![frame(runtime::CallType::SelfDestruct, vec![])],
+				),
+				reverted,
+			],
+		);
+		let location = TraceLocation {
+			transaction_hash: H256::repeat_byte(1),
+			transaction_position: 3,
+			block_hash: H256::repeat_byte(2),
+			block_number: U256::from(7),
+		};
+
+		let mut traces = Vec::new();
+		flatten(root, vec![], &location, &mut traces);
+
+		let addresses: Vec<_> = traces.iter().map(|t| t.trace_address.clone()).collect();
+		assert_eq!(addresses, vec![vec![], vec![0], vec![0, 0], vec![1]]);
+		let subtraces: Vec<_> = traces.iter().map(|t| t.subtraces).collect();
+		assert_eq!(subtraces, vec![2, 1, 0, 0]);
+		assert!(matches!(traces[1].action, TraceAction::Create(_)));
+		assert!(matches!(traces[2].action, TraceAction::Suicide(_)));
+		assert_eq!(traces[3].error.as_deref(), Some("Reverted"));
+		assert_eq!(traces[3].result, None);
+		assert!(traces
+			.iter()
+			.all(|t| t.transaction_position == Some(3) && t.block_number == U256::from(7)));
+	}
+}
@@ -31,27 +31,29 @@
 
 extern crate alloc;
 
+pub mod migrations;
 #[cfg(all(feature = "std", test))]
 mod mock;
 #[cfg(all(feature = "std", test))]
 mod tests;
 
-use alloc::{vec, vec::Vec};
+use alloc::{collections::btree_map::BTreeMap, vec, vec::Vec};
 use core::marker::PhantomData;
 pub use ethereum::{
 	AccessListItem, BlockV3 as Block, LegacyTransactionMessage, Log, ReceiptV4 as Receipt,
 	TransactionAction, TransactionV3 as Transaction,
 };
 use ethereum_types::{Bloom, BloomInput, H160, H256, H64, U256};
-use evm::ExitReason;
+use evm::{ExitError, ExitReason};
 use scale_codec::{Decode, DecodeWithMemTracking, Encode, MaxEncodedLen};
 use scale_info::TypeInfo;
 // Substrate
 use frame_support::{
 	dispatch::{
-		DispatchErrorWithPostInfo, DispatchInfo, DispatchResultWithPostInfo, Pays, PostDispatchInfo,
+		DispatchClass, DispatchErrorWithPostInfo, DispatchInfo, DispatchResultWithPostInfo, Pays,
+		PostDispatchInfo,
 	},
-	traits::{EnsureOrigin, Get, Time},
+	traits::{EnsureOrigin, Get, PostInherents, Time},
 	weights::Weight,
 };
 use frame_system::{pallet_prelude::OriginFor, CheckWeight, WeightInfo};
@@ -59,7 +61,8 @@ use sp_runtime::{
 	generic::DigestItem,
 	traits::{DispatchInfoOf, Dispatchable, One, Saturating, UniqueSaturatedInto, Zero},
 	transaction_validity::{
-		InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransactionBuilder,
+		InvalidTransaction, TransactionPriority, TransactionValidity, TransactionValidityError,
+		ValidTransaction, ValidTransactionBuilder,
 	},
 	RuntimeDebug, SaturatedConversion,
 };
@@ -116,22 +119,31 @@ where
 	T::RuntimeCall: Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>,
 {
 	pub fn is_self_contained(&self) -> bool {
-		matches!(self, Call::transact { .. })
+		matches!(self, Call::transact { .. } | Call::transact_batch { .. })
 	}
 
 	pub fn check_self_contained(&self) -> Option<Result<H160, TransactionValidityError>> {
-		if let Call::transact { transaction } = self {
-			let check = || {
-				let origin = Pallet::<T>::recover_signer(transaction).ok_or(
-					InvalidTransaction::Custom(TransactionValidationError::InvalidSignature as u8),
-				)?;
+		match self {
+			Call::transact { transaction } => {
+				let check = || {
+					let origin = Pallet::<T>::recover_signer(transaction).ok_or(
+						InvalidTransaction::Custom(
+							TransactionValidationError::InvalidSignature as u8,
+						),
+					)?;
 
-				Ok(origin)
-			};
+					Ok(origin)
+				};
 
-			Some(check())
-		} else {
-			None
+				Some(check())
+			}
+			// The batch is submitted by the signer of its first transaction.
+			Call::transact_batch { transactions } => Some(
+				Pallet::<T>::recover_signers(transactions)
+					.map(|sources| sources[0])
+					.map_err(Into::into),
+			),
+			_ => None,
 		}
 	}
 
@@ -141,20 +153,24 @@ where
 		dispatch_info: &DispatchInfoOf<T::RuntimeCall>,
 		len: usize,
 	) -> Option<Result<(), TransactionValidityError>> {
-		if let Call::transact { transaction } = self {
-			if let Err(e) =
-				CheckWeight::<T>::do_validate(dispatch_info, len).and_then(|(_, next_len)| {
-					CheckWeight::<T>::do_prepare(dispatch_info, len, next_len)
-				}) {
-				return Some(Err(e));
-			}
+		if !self.is_self_contained() {
+			return None;
+		}
+		if let Err(e) = CheckWeight::<T>::do_validate(dispatch_info, len)
+			.and_then(|(_, next_len)| CheckWeight::<T>::do_prepare(dispatch_info, len, next_len))
+		{
+			return Some(Err(e));
+		}
 
-			Some(Pallet::<T>::validate_transaction_in_block(
+		match self {
+			Call::transact { transaction } => Some(Pallet::<T>::validate_transaction_in_block(
 				*origin,
 				transaction,
-			))
-		} else {
-			None
+			)),
+			Call::transact_batch { transactions } => {
+				Some(Pallet::<T>::validate_batch_in_block(transactions))
+			}
+			_ => None,
 		}
 	}
 
@@ -164,17 +180,22 @@ where
 		dispatch_info: &DispatchInfoOf<T::RuntimeCall>,
 		len: usize,
 	) -> Option<TransactionValidity> {
-		if let Call::transact { transaction } = self {
-			if let Err(e) = CheckWeight::<T>::do_validate(dispatch_info, len) {
-				return Some(Err(e));
-			}
+		if !self.is_self_contained() {
+			return None;
+		}
+		if let Err(e) = CheckWeight::<T>::do_validate(dispatch_info, len) {
+			return Some(Err(e));
+		}
 
-			Some(Pallet::<T>::validate_transaction_in_pool(
+		match self {
+			Call::transact { transaction } => Some(Pallet::<T>::validate_transaction_in_pool(
 				*origin,
 				transaction,
-			))
-		} else {
-			None
+			)),
+			Call::transact_batch { transactions } => {
+				Some(Pallet::<T>::validate_batch_in_pool(transactions))
+			}
+			_ => None,
 		}
 	}
 }
@@ -209,6 +230,18 @@ pub mod pallet {
 		type PostLogContent: Get<PostLogContent>;
 		/// The maximum length of the extra data in the Executed event.
 		type ExtraDataLength: Get<u32>;
+		/// Whether to maintain the EIP-2935 history storage contract, and serve `BLOCKHASH` from
+		/// it with the Ethereum semantics.
+		type BlockHashHistory: Get<bool>;
+		/// Number of Ethereum block hashes kept in [`BlockHash`] besides the genesis one, older
+		/// ones being pruned.
+		type BlockHashRetention: Get<u32>;
+		/// Root recorded in the EIP-4788 beacon roots contract after the inherents of each block,
+		/// for contracts to verify proofs against. `None` disables the contract.
+		///
+		/// Unlike Ethereum, where it is the root of the parent beacon block, any commitment can
+		/// be recorded, such as [`ParentBlockHash`].
+		type BeaconRoot: Get<Option<H256>>;
 	}
 
 	pub mod config_preludes {
@@ -232,6 +265,9 @@ pub mod pallet {
 			type StateRoot = IntermediateStateRoot<Self::Version>;
 			type PostLogContent = PostBlockAndTxnHashes;
 			type ExtraDataLength = ConstU32<30>;
+			type BlockHashHistory = ConstBool<false>;
+			type BlockHashRetention = ConstU32<256>;
+			type BeaconRoot = frame_support::traits::GetDefault;
 		}
 	}
 
@@ -248,9 +284,8 @@ pub mod pallet {
 				)),
 			);
 			// move block hash pruning window by one block
-			let block_hash_count = T::BlockHashCount::get();
 			let to_remove = n
-				.saturating_sub(block_hash_count)
+				.saturating_sub(T::BlockHashRetention::get().into())
 				.saturating_sub(One::one());
 			// keep genesis hash
 			if !to_remove.is_zero() {
@@ -287,7 +322,17 @@ pub mod pallet {
 			//	- read: frame_system::Pallet::<T>::block_number()
 			//	- write: <Pallet<T>>::store_block()
 			//	- write: <BlockHash<T>>::remove()
-			weight.saturating_add(T::DbWeight::get().reads_writes(2, 2))
+			weight = weight.saturating_add(T::DbWeight::get().reads_writes(2, 2));
+			if T::BlockHashHistory::get() {
+				//	- read: <pallet_evm::AccountCodes<T>>::contains_key()
+				//	- write: <pallet_evm::AccountStorages<T>>::insert()
+				weight = weight
+					.saturating_add(T::DbWeight::get().reads_writes(1, 1))
+					.saturating_add(Self::system_contract_deployment_weight(
+						HISTORY_STORAGE_ADDRESS,
+					));
+			}
+			weight
 		}
 
 		fn on_runtime_upgrade() -> Weight {
@@ -328,6 +373,69 @@ pub mod pallet {
 			Self::apply_validated_transaction(source, transaction, None)
 				.map(|(post_info, _)| post_info)
 		}
+
+		/// Transact a batch of independently signed Ethereum transactions, in order.
+		///
+		/// The batch stops at the first transaction that cannot be applied: the transactions
+		/// before it are kept and charged, the ones from it on are discarded.
+		#[pallet::call_index(1)]
+		#[pallet::weight({
+			let without_base_extrinsic_weight = true;
+			<T as pallet_evm::Config>::GasWeightMapping::gas_to_weight(
+				transactions.iter().fold(0u64, |gas_limit, transaction| {
+					let transaction_data: TransactionData = transaction.into();
+					gas_limit.saturating_add(transaction_data.gas_limit.unique_saturated_into())
+				}),
+				without_base_extrinsic_weight,
+			)
+		})]
+		pub fn transact_batch(
+			origin: OriginFor<T>,
+			transactions: Vec<Transaction>,
+		) -> DispatchResultWithPostInfo {
+			let source = ensure_ethereum_transaction(origin)?;
+			// Disable transact functionality if PreLog exist.
+			assert!(
+				fp_consensus::find_pre_log(&frame_system::Pallet::<T>::digest()).is_err(),
+				"pre log already exists; block is invalid",
+			);
+			let sources =
+				Self::recover_signers(&transactions).map_err(|_| Error::<T>::InvalidSignature)?;
+			ensure!(
+				sources.first() == Some(&source),
+				Error::<T>::InvalidSignature
+			);
+
+			// Each transaction is applied in its own storage layer, so a failing one only undoes
+			// itself: the transactions applied before it keep their state and fees.
+			let mut actual_weight = Weight::zero();
+			for (source, transaction) in sources.into_iter().zip(transactions) {
+				#[cfg(feature = "tracing")]
+				let transaction_hash = transaction.hash();
+				match frame_support::storage::with_storage_layer(|| {
+					Self::apply_validated_transaction(source, transaction, None)
+				}) {
+					Ok((post_info, _)) => {
+						actual_weight = actual_weight
+							.saturating_add(post_info.actual_weight.unwrap_or_default());
+						#[cfg(feature = "tracing")]
+						pallet_evm::runner::tracing::on_ethereum_transaction_applied(
+							transaction_hash,
+						);
+					}
+					Err(e) => {
+						actual_weight = actual_weight
+							.saturating_add(e.post_info.actual_weight.unwrap_or_default());
+						break;
+					}
+				}
+			}
+
+			Ok(PostDispatchInfo {
+				actual_weight: Some(actual_weight),
+				pays_fee: Pays::No,
+			})
+		}
 	}
 
 	#[pallet::event]
@@ -405,6 +513,21 @@ impl<T: Config> Pallet<T> {
 		}
 	}
 
+	/// Recovers the signers of the transactions of a non-empty batch.
+	pub fn recover_signers(transactions: &[Transaction]) -> Result<Vec<H160>, InvalidTransaction> {
+		if transactions.is_empty() {
+			return Err(InvalidTransaction::Call);
+		}
+		transactions
+			.iter()
+			.map(|transaction| {
+				Self::recover_signer(transaction).ok_or(InvalidTransaction::Custom(
+					TransactionValidationError::InvalidSignature as u8,
+				))
+			})
+			.collect()
+	}
+
 	fn recover_signer(transaction: &Transaction) -> Option<H160> {
 		let mut sig = [0u8; 65];
 		let mut msg = [0u8; 32];
@@ -498,6 +621,9 @@ impl<T: Config> Pallet<T> {
 		CurrentReceipts::<T>::put(receipts.clone());
 		CurrentTransactionStatuses::<T>::put(statuses.clone());
 		BlockHash::<T>::insert(block_number, block.header.hash());
+		if T::BlockHashHistory::get() {
+			Self::store_block_hash_history(block_number.low_u32(), block.header.hash());
+		}
 
 		match post_log {
 			Some(PostLogContent::BlockAndTxnHashes) => {
@@ -518,6 +644,92 @@ impl<T: Config> Pallet<T> {
 		}
 	}
 
+	/// Records the hash of the Ethereum block `number` in the EIP-2935 history storage contract,
+	/// deploying the contract first if needed.
+	fn store_block_hash_history(number: u32, hash: H256) {
+		if let Err(e) = Self::deploy_system_contract(HISTORY_STORAGE_ADDRESS, HISTORY_STORAGE_CODE)
+		{
+			log::error!(
+				target: "ethereum",
+				"Failed to deploy the history storage contract: {e:?}"
+			);
+			return;
+		}
+		pallet_evm::AccountStorages::<T>::insert(
+			HISTORY_STORAGE_ADDRESS,
+			history_storage_slot(number),
+			hash,
+		);
+	}
+
+	/// Records `root` in the EIP-4788 beacon roots contract under the current timestamp, deploying
+	/// the contract first if needed.
+	fn store_beacon_root(root: H256) {
+		if let Err(e) = Self::deploy_system_contract(BEACON_ROOTS_ADDRESS, BEACON_ROOTS_CODE) {
+			log::error!(
+				target: "ethereum",
+				"Failed to deploy the beacon roots contract: {e:?}"
+			);
+			return;
+		}
+		let now: u64 = T::Timestamp::now().unique_saturated_into();
+		let timestamp = now / 1000;
+		let index = timestamp % u64::from(BEACON_ROOTS_HISTORY_BUFFER_LENGTH);
+		pallet_evm::AccountStorages::<T>::insert(
+			BEACON_ROOTS_ADDRESS,
+			H256::from_low_u64_be(index),
+			H256::from_low_u64_be(timestamp),
+		);
+		pallet_evm::AccountStorages::<T>::insert(
+			BEACON_ROOTS_ADDRESS,
+			H256::from_low_u64_be(index + u64::from(BEACON_ROOTS_HISTORY_BUFFER_LENGTH)),
+			root,
+		);
+	}
+
+	/// Weight of deploying the system contract at `address` with [`Self::deploy_system_contract`],
+	/// besides checking whether it is deployed, or zero if it already is:
+	///	- read: <pallet_evm::AccountCodes<T>>::contains_key()
+	///	- read/write: the account created for the contract
+	///	- write: <pallet_evm::AccountCodesMetadata<T>>::insert()
+	///	- write: <pallet_evm::AccountCodes<T>>::insert()
+	fn system_contract_deployment_weight(address: H160) -> Weight {
+		if pallet_evm::AccountCodes::<T>::contains_key(address) {
+			Weight::zero()
+		} else {
+			T::DbWeight::get().reads_writes(2, 3)
+		}
+	}
+
+	/// Deploys the code of a system contract, unless already done.
+	fn deploy_system_contract(address: H160, code: &[u8]) -> Result<(), ExitError> {
+		if !pallet_evm::AccountCodes::<T>::contains_key(address) {
+			pallet_evm::Pallet::<T>::create_account(address, code.to_vec(), None)?;
+		}
+		Ok(())
+	}
+
+	/// Hash of the Ethereum block `number` kept by the EIP-2935 history storage contract, if it is
+	/// one of the `window` blocks before the current one.
+	pub fn historical_block_hash(number: u32, window: u32) -> Option<H256> {
+		let current = UniqueSaturatedInto::<u32>::unique_saturated_into(
+			frame_system::Pallet::<T>::block_number(),
+		);
+		if number >= current || current - number > window.min(HISTORY_SERVE_WINDOW) {
+			return None;
+		}
+		let hash = pallet_evm::AccountStorages::<T>::get(
+			HISTORY_STORAGE_ADDRESS,
+			history_storage_slot(number),
+		);
+		// Blocks before the history was enabled are only in `BlockHash`.
+		if hash.is_zero() {
+			Some(BlockHash::<T>::get(U256::from(number)))
+		} else {
+			Some(hash)
+		}
+	}
+
 	fn logs_bloom(logs: Vec<Log>, bloom: &mut Bloom) {
 		for log in logs {
 			bloom.accrue(BloomInput::Raw(&log.address[..]));
@@ -533,19 +745,27 @@ impl<T: Config> Pallet<T> {
 	fn validate_transaction_in_pool(
 		origin: H160,
 		transaction: &Transaction,
+	) -> TransactionValidity {
+		let (who, _) = pallet_evm::Pallet::<T>::account_basic(&origin);
+		Self::validate_transaction_in_pool_for(origin, &who, transaction)
+	}
+
+	fn validate_transaction_in_pool_for(
+		origin: H160,
+		who: &pallet_evm::Account,
+		transaction: &Transaction,
 	) -> TransactionValidity {
 		let transaction_data: TransactionData = transaction.into();
 		let transaction_nonce = transaction_data.nonce;
 		let (weight_limit, proof_size_base_cost) = Self::transaction_weight(&transaction_data);
 		let (base_fee, _) = T::FeeCalculator::min_gas_price();
-		let (who, _) = pallet_evm::Pallet::<T>::account_basic(&origin);
 
 		// Check if this is an EIP-7702 transaction
 		let is_eip7702 = matches!(transaction, Transaction::EIP7702(_));
 
 		let _ = CheckEvmTransaction::<InvalidTransactionWrapper>::new(
 			CheckEvmTransactionConfig {
-				evm_config: T::config(),
+				evm_config: pallet_evm::Pallet::<T>::evm_config(),
 				block_gas_limit: T::BlockGasLimit::get(),
 				base_fee,
 				chain_id: T::ChainId::get(),
@@ -555,10 +775,10 @@ impl<T: Config> Pallet<T> {
 			weight_limit,
 			proof_size_base_cost,
 		)
-		.validate_in_pool_for(&who)
+		.validate_in_pool_for(who)
 		.and_then(|v| v.with_chain_id())
 		.and_then(|v| v.with_base_fee())
-		.and_then(|v| v.with_balance_for(&who))
+		.and_then(|v| v.with_balance_for(who))
 		.and_then(|v| v.with_eip7702_authorization_list(is_eip7702))
 		.map_err(|e| e.0)?;
 
@@ -895,7 +1115,9 @@ impl<T: Config> Pallet<T> {
 					validate,
 					weight_limit,
 					proof_size_base_cost,
-					config.as_ref().unwrap_or_else(|| T::config()),
+					config
+						.as_ref()
+						.unwrap_or_else(pallet_evm::Pallet::<T>::evm_config),
 				) {
 					Ok(res) => res,
 					Err(e) => {
@@ -927,7 +1149,9 @@ impl<T: Config> Pallet<T> {
 						validate,
 						weight_limit,
 						proof_size_base_cost,
-						config.as_ref().unwrap_or_else(|| T::config()),
+						config
+							.as_ref()
+							.unwrap_or_else(pallet_evm::Pallet::<T>::evm_config),
 						force_address,
 					) {
 						Ok(res) => res,
@@ -956,7 +1180,9 @@ impl<T: Config> Pallet<T> {
 						validate,
 						weight_limit,
 						proof_size_base_cost,
-						config.as_ref().unwrap_or_else(|| T::config()),
+						config
+							.as_ref()
+							.unwrap_or_else(pallet_evm::Pallet::<T>::evm_config),
 					) {
 						Ok(res) => res,
 						Err(e) => {
@@ -983,18 +1209,25 @@ impl<T: Config> Pallet<T> {
 	pub fn validate_transaction_in_block(
 		origin: H160,
 		transaction: &Transaction,
+	) -> Result<(), TransactionValidityError> {
+		let (who, _) = pallet_evm::Pallet::<T>::account_basic(&origin);
+		Self::validate_transaction_in_block_for(&who, transaction)
+	}
+
+	fn validate_transaction_in_block_for(
+		who: &pallet_evm::Account,
+		transaction: &Transaction,
 	) -> Result<(), TransactionValidityError> {
 		let transaction_data: TransactionData = transaction.into();
 		let (weight_limit, proof_size_base_cost) = Self::transaction_weight(&transaction_data);
 		let (base_fee, _) = T::FeeCalculator::min_gas_price();
-		let (who, _) = pallet_evm::Pallet::<T>::account_basic(&origin);
 
 		// Check if this is an EIP-7702 transaction
 		let is_eip7702 = matches!(transaction, Transaction::EIP7702(_));
 
 		let _ = CheckEvmTransaction::<InvalidTransactionWrapper>::new(
 			CheckEvmTransactionConfig {
-				evm_config: T::config(),
+				evm_config: pallet_evm::Pallet::<T>::evm_config(),
 				block_gas_limit: T::BlockGasLimit::get(),
 				base_fee,
 				chain_id: T::ChainId::get(),
@@ -1004,16 +1237,81 @@ impl<T: Config> Pallet<T> {
 			weight_limit,
 			proof_size_base_cost,
 		)
-		.validate_in_block_for(&who)
+		.validate_in_block_for(who)
 		.and_then(|v| v.with_chain_id())
 		.and_then(|v| v.with_base_fee())
-		.and_then(|v| v.with_balance_for(&who))
+		.and_then(|v| v.with_balance_for(who))
 		.and_then(|v| v.with_eip7702_authorization_list(is_eip7702))
 		.map_err(|e| TransactionValidityError::Invalid(e.0))?;
 
 		Ok(())
 	}
 
+	/// Validate a batch of Ethereum transactions already in block, each one as if the previous
+	/// ones were applied with their maximum cost.
+	///
+	/// This function must be called during the pre-dispatch phase
+	/// (just before applying the extrinsic).
+	pub fn validate_batch_in_block(
+		transactions: &[Transaction],
+	) -> Result<(), TransactionValidityError> {
+		let sources = Self::recover_signers(transactions)?;
+		Self::for_each_in_batch(&sources, transactions, |_, who, transaction| {
+			Self::validate_transaction_in_block_for(who, transaction)
+		})
+	}
+
+	// Controls that must be performed by the pool for a batch, each transaction being validated
+	// as if the previous ones were applied with their maximum cost.
+	//
+	// The batch gets the lowest priority of its transactions, so that bundling a transaction with
+	// others never raises its priority.
+	fn validate_batch_in_pool(transactions: &[Transaction]) -> TransactionValidity {
+		let sources = Self::recover_signers(transactions)?;
+		let mut valid = ValidTransaction::default();
+		let mut priority = TransactionPriority::MAX;
+		Self::for_each_in_batch(&sources, transactions, |source, who, transaction| {
+			let inner = Self::validate_transaction_in_pool_for(source, who, transaction)?;
+			priority = priority.min(inner.priority);
+			valid = core::mem::take(&mut valid).combine_with(inner);
+			Ok(())
+		})?;
+		Ok(ValidTransaction { priority, ..valid })
+	}
+
+	/// Calls `f` with each transaction of a batch, its signer, and the account of the signer once
+	/// the previous transactions of the batch are applied with their maximum cost.
+	fn for_each_in_batch(
+		sources: &[H160],
+		transactions: &[Transaction],
+		mut f: impl FnMut(
+			H160,
+			&pallet_evm::Account,
+			&Transaction,
+		) -> Result<(), TransactionValidityError>,
+	) -> Result<(), TransactionValidityError> {
+		let mut accounts = BTreeMap::<H160, pallet_evm::Account>::new();
+		for (source, transaction) in sources.iter().zip(transactions) {
+			let who = accounts
+				.entry(*source)
+				.or_insert_with(|| pallet_evm::Pallet::<T>::account_basic(source).0);
+			f(*source, who, transaction)?;
+
+			let transaction_data: TransactionData = transaction.into();
+			let gas_price = transaction_data
+				.max_fee_per_gas
+				.or(transaction_data.gas_price)
+				.unwrap_or_default();
+			let max_cost = transaction_data
+				.gas_limit
+				.saturating_mul(gas_price)
+				.saturating_add(transaction_data.value);
+			who.nonce = who.nonce.saturating_add(U256::one());
+			who.balance = who.balance.saturating_sub(max_cost);
+		}
+		Ok(())
+	}
+
 	pub fn migrate_block_v0_to_v2() -> Weight {
 		let db_weights = T::DbWeight::get();
 		let mut weight: Weight = db_weights.reads(1);
@@ -1096,6 +1394,37 @@ pub enum ReturnValue {
 	Hash(H160),
 }
 
+/// Records the [`Config::BeaconRoot`] of the block once its timestamp is set, so that contracts
+/// can read it with `block.timestamp` as with EIP-4788.
+impl<T: Config> PostInherents for Pallet<T> {
+	fn post_inherents() {
+		if let Some(root) = T::BeaconRoot::get() {
+			let weight = T::DbWeight::get().reads_writes(3, 2).saturating_add(
+				Self::system_contract_deployment_weight(BEACON_ROOTS_ADDRESS),
+			);
+			Self::store_beacon_root(root);
+			frame_system::Pallet::<T>::register_extra_weight_unchecked(
+				weight,
+				DispatchClass::Mandatory,
+			);
+		}
+	}
+}
+
+/// The hash of the parent Substrate block, as a [`Config::BeaconRoot`].
+///
+/// The state root of the parent block is not known to the runtime, only the hash of its header
+/// is. A contract verifying a storage proof of the parent block is given the header, checks it
+/// against this hash and takes the state root from it.
+pub struct ParentBlockHash<T>(PhantomData<T>);
+impl<T: frame_system::Config> Get<Option<H256>> for ParentBlockHash<T> {
+	fn get() -> Option<H256> {
+		Some(H256::from_slice(
+			frame_system::Pallet::<T>::parent_hash().as_ref(),
+		))
+	}
+}
+
 pub struct IntermediateStateRoot<T>(PhantomData<T>);
 impl<T: Get<RuntimeVersion>> Get<H256> for IntermediateStateRoot<T> {
 	fn get() -> H256 {
@@ -1105,11 +1434,52 @@ impl<T: Get<RuntimeVersion>> Get<H256> for IntermediateStateRoot<T> {
 	}
 }
 
+/// Address of the EIP-2935 history storage contract.
+pub const HISTORY_STORAGE_ADDRESS: H160 = H160(hex_literal::hex!(
+	"0000f90827f1c53a10cb7a02335b175320002935"
+));
+
+/// Number of block hashes served by the EIP-2935 history storage contract.
+pub const HISTORY_SERVE_WINDOW: u32 = 8191;
+
+/// Number of block hashes served by `BLOCKHASH`.
+pub const BLOCKHASH_SERVE_WINDOW: u32 = 256;
+
+/// Runtime code of the EIP-2935 history storage contract.
+pub const HISTORY_STORAGE_CODE: &[u8] = &hex_literal::hex!(
+	"3373fffffffffffffffffffffffffffffffffffffffe14604657602036036042575f35600143038111604257611fff81430311604257611fff9006545f5260205ff35b5f5ffd5b5f35611fff60014303065500"
+);
+
+/// Address of the EIP-4788 beacon roots contract.
+pub const BEACON_ROOTS_ADDRESS: H160 = H160(hex_literal::hex!(
+	"000f3df6d732807ef1319fb7b8bb8522d0beac02"
+));
+
+/// Number of roots kept by the EIP-4788 beacon roots contract.
+pub const BEACON_ROOTS_HISTORY_BUFFER_LENGTH: u32 = 8191;
+
+/// Runtime code of the EIP-4788 beacon roots contract.
+pub const BEACON_ROOTS_CODE: &[u8] = &hex_literal::hex!(
+	"3373fffffffffffffffffffffffffffffffffffffffe14604d57602036146024575f5ffd5b5f35801560495762001fff810690815414603c575f5ffd5b62001fff01545f5260205ff35b5f5ffd5b62001fff42064281555f359062001fff015500"
+);
+
+/// Storage slot of the history storage contract holding the hash of block `number`.
+fn history_storage_slot(number: u32) -> H256 {
+	H256::from_low_u64_be((number % HISTORY_SERVE_WINDOW).into())
+}
+
 /// Returns the Ethereum block hash by number.
+///
+/// With [`Config::BlockHashHistory`], only the hashes of the [`BLOCKHASH_SERVE_WINDOW`] blocks
+/// before the current one are returned, as Ethereum clients do.
 pub struct EthereumBlockHashMapping<T>(PhantomData<T>);
 impl<T: Config> BlockHashMapping for EthereumBlockHashMapping<T> {
 	fn block_hash(number: u32) -> H256 {
-		BlockHash::<T>::get(U256::from(number))
+		if T::BlockHashHistory::get() {
+			Pallet::<T>::historical_block_hash(number, BLOCKHASH_SERVE_WINDOW).unwrap_or_default()
+		} else {
+			BlockHash::<T>::get(U256::from(number))
+		}
 	}
 }
 
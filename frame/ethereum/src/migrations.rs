@@ -0,0 +1,194 @@
+// This file is part of Frontier.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Migrations of the Ethereum pallet.
+
+#[cfg(feature = "try-runtime")]
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use ethereum_types::U256;
+#[cfg(feature = "try-runtime")]
+use scale_codec::{Decode, Encode};
+// Substrate
+use frame_support::{
+	migrations::{MigrationId, SteppedMigration, SteppedMigrationError},
+	traits::Get,
+	weights::WeightMeter,
+};
+use sp_runtime::traits::UniqueSaturatedInto;
+
+use crate::{BlockHash, Config};
+
+/// Identifier of the multi-block migrations of this pallet.
+const PALLET_MIGRATIONS_ID: &[u8; 15] = b"pallet-ethereum";
+
+/// Multi-block migration removing the [`BlockHash`] entries older than
+/// [`Config::BlockHashRetention`], left behind by runtimes which did not prune them or which
+/// kept more of them.
+///
+/// Runtimes lowering [`Config::BlockHashRetention`] must register it in the `Migrations` of
+/// `pallet_migrations`, otherwise the older entries are never removed.
+pub struct PruneBlockHashes<T>(PhantomData<T>);
+
+impl<T: Config> PruneBlockHashes<T> {
+	/// Number of the oldest block hash to keep, besides the genesis one.
+	fn oldest_kept() -> U256 {
+		let number: u32 = frame_system::Pallet::<T>::block_number().unique_saturated_into();
+		// The hash of the current block is only stored when finalizing it.
+		U256::from(
+			number
+				.saturating_sub(1)
+				.saturating_sub(T::BlockHashRetention::get()),
+		)
+	}
+
+	fn is_pruned(number: U256, oldest_kept: U256) -> bool {
+		!number.is_zero() && number < oldest_kept
+	}
+}
+
+impl<T: Config> SteppedMigration for PruneBlockHashes<T> {
+	/// Number of the last visited entry.
+	type Cursor = U256;
+	type Identifier = MigrationId<15>;
+
+	fn id() -> Self::Identifier {
+		MigrationId {
+			pallet_id: *PALLET_MIGRATIONS_ID,
+			version_from: 0,
+			version_to: 1,
+		}
+	}
+
+	fn step(
+		mut cursor: Option<Self::Cursor>,
+		meter: &mut WeightMeter,
+	) -> Result<Option<Self::Cursor>, SteppedMigrationError> {
+		let required = T::DbWeight::get().reads_writes(1, 1);
+		if meter.remaining().any_lt(required) {
+			return Err(SteppedMigrationError::InsufficientWeight { required });
+		}
+
+		let oldest_kept = Self::oldest_kept();
+		while meter.try_consume(required).is_ok() {
+			let mut keys = match cursor {
+				Some(last) => BlockHash::<T>::iter_keys_from(BlockHash::<T>::hashed_key_for(last)),
+				None => BlockHash::<T>::iter_keys(),
+			};
+			let Some(number) = keys.next() else {
+				return Ok(None);
+			};
+			if Self::is_pruned(number, oldest_kept) {
+				BlockHash::<T>::remove(number);
+			}
+			cursor = Some(number);
+		}
+		Ok(cursor)
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<Vec<u8>, sp_runtime::TryRuntimeError> {
+		let oldest_kept = Self::oldest_kept();
+		let kept = BlockHash::<T>::iter()
+			.filter(|(number, _)| !Self::is_pruned(*number, oldest_kept))
+			.collect::<Vec<_>>();
+		Ok(kept.encode())
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade(state: Vec<u8>) -> Result<(), sp_runtime::TryRuntimeError> {
+		let mut kept =
+			Vec::<(U256, ethereum_types::H256)>::decode(&mut state.as_slice()).map_err(|_| {
+				"the state parameter should be something that was generated by pre_upgrade"
+			})?;
+		let mut remaining = BlockHash::<T>::iter().collect::<Vec<_>>();
+		kept.sort();
+		remaining.sort();
+		frame_support::ensure!(
+			kept == remaining,
+			"only the block hashes older than the retention bound should be removed"
+		);
+		Ok(())
+	}
+}
+
+#[cfg(all(feature = "std", test))]
+mod tests {
+	use super::*;
+	use crate::mock::*;
+	use ethereum_types::H256;
+	use frame_support::{
+		traits::Hooks,
+		weights::{RuntimeDbWeight, Weight},
+	};
+
+	#[test]
+	fn prune_block_hashes_works() {
+		let (_, mut ext) = new_test_ext(0);
+		ext.execute_with(|| {
+			for number in 0..300u64 {
+				BlockHash::<Test>::insert(U256::from(number), H256::from_low_u64_be(number + 1));
+			}
+			System::set_block_number(300);
+
+			MockDbWeight::set(RuntimeDbWeight {
+				read: 1_000,
+				write: 1_000,
+			});
+			let required = <Test as frame_system::Config>::DbWeight::get().reads_writes(1, 1);
+
+			// A step visits one entry at least.
+			assert_eq!(
+				PruneBlockHashes::<Test>::step(
+					None,
+					&mut WeightMeter::with_limit(required.saturating_sub(Weight::from_parts(1, 0)))
+				),
+				Err(SteppedMigrationError::InsufficientWeight { required })
+			);
+
+			// Each step visits 100 entries and the next one resumes from its cursor.
+			let mut cursor = None;
+			let mut steps = 0;
+			loop {
+				let mut meter = WeightMeter::with_limit(required.saturating_mul(100));
+				let previous = cursor;
+				cursor = PruneBlockHashes::<Test>::step(cursor, &mut meter).unwrap();
+				steps += 1;
+				if cursor.is_none() {
+					break;
+				}
+				assert_ne!(cursor, previous);
+				assert!(meter.remaining().any_lt(required));
+			}
+			// 300 entries visited by 3 steps, and a last one finding no more entries.
+			assert_eq!(steps, 4);
+			// The genesis hash and the last 256 ones before the current block are kept.
+			for number in 0..300u64 {
+				assert_eq!(
+					BlockHash::<Test>::contains_key(U256::from(number)),
+					number == 0 || number >= 43,
+				);
+			}
+
+			// Finalizing the block moves the retention window.
+			Ethereum::on_finalize(300);
+			assert!(!BlockHash::<Test>::contains_key(U256::from(43)));
+			assert!(BlockHash::<Test>::contains_key(U256::from(44)));
+			assert!(BlockHash::<Test>::contains_key(U256::from(300)));
+		});
+	}
+}
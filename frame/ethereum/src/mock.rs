@@ -24,7 +24,12 @@ use ethereum::{
 };
 use rlp::RlpStream;
 // Substrate
-use frame_support::{derive_impl, parameter_types, traits::FindAuthor, ConsensusEngineId};
+use frame_support::{
+	derive_impl, parameter_types,
+	traits::{ConstBool, FindAuthor},
+	weights::RuntimeDbWeight,
+	ConsensusEngineId,
+};
 use sp_core::{hashing::keccak_256, H160, H256, U256};
 use sp_runtime::{
 	traits::{Dispatchable, IdentityLookup},
@@ -49,6 +54,8 @@ frame_support::construct_runtime! {
 
 parameter_types! {
 	pub const BlockHashCount: u64 = 250;
+	/// Free by default, the tests of weight-bound code set it.
+	pub static MockDbWeight: RuntimeDbWeight = RuntimeDbWeight { read: 0, write: 0 };
 }
 
 #[derive_impl(frame_system::config_preludes::TestDefaultConfig as frame_system::DefaultConfig)]
@@ -58,6 +65,7 @@ impl frame_system::Config for Test {
 	type Block = frame_system::mocking::MockBlock<Self>;
 	type BlockHashCount = BlockHashCount;
 	type AccountData = pallet_balances::AccountData<u64>;
+	type DbWeight = MockDbWeight;
 }
 
 parameter_types! {
@@ -111,7 +119,10 @@ impl pallet_evm::Config for Test {
 }
 
 #[derive_impl(crate::config_preludes::TestDefaultConfig)]
-impl Config for Test {}
+impl Config for Test {
+	type BlockHashHistory = ConstBool<true>;
+	type BeaconRoot = crate::ParentBlockHash<Self>;
+}
 
 impl fp_self_contained::SelfContainedCall for RuntimeCall {
 	type SignedInfo = H160;
@@ -161,9 +172,9 @@ impl fp_self_contained::SelfContainedCall for RuntimeCall {
 		info: Self::SignedInfo,
 	) -> Option<sp_runtime::DispatchResultWithInfo<sp_runtime::traits::PostDispatchInfoOf<Self>>> {
 		match self {
-			call @ RuntimeCall::Ethereum(crate::Call::transact { .. }) => {
-				Some(call.dispatch(RuntimeOrigin::from(RawOrigin::EthereumTransaction(info))))
-			}
+			call @ RuntimeCall::Ethereum(
+				crate::Call::transact { .. } | crate::Call::transact_batch { .. },
+			) => Some(call.dispatch(RuntimeOrigin::from(RawOrigin::EthereumTransaction(info)))),
 			_ => None,
 		}
 	}
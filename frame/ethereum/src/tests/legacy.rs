@@ -22,9 +22,10 @@ use evm::{ExitReason, ExitRevert, ExitSucceed};
 use fp_ethereum::{TransactionData, ValidatedTransaction};
 use frame_support::{
 	dispatch::{DispatchClass, GetDispatchInfo, Pays, PostDispatchInfo},
-	weights::Weight,
+	traits::{Hooks, PostInherents},
+	weights::{RuntimeDbWeight, Weight},
 };
-use pallet_evm::AddressMapping;
+use pallet_evm::{AddressMapping, BlockHashMapping};
 use sp_runtime::{DispatchError, DispatchErrorWithPostInfo, ModuleError};
 
 fn legacy_erc20_creation_unsigned_transaction() -> LegacyUnsignedTransaction {
@@ -706,3 +707,320 @@ fn proof_size_base_cost_should_keep_the_same_in_execution_and_estimate() {
 		);
 	});
 }
+
+#[test]
+fn block_hash_history_works() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		for number in 1..=3 {
+			System::set_block_number(number);
+			Ethereum::on_finalize(number);
+		}
+		let hash = |number: u32| crate::BlockHash::<Test>::get(U256::from(number));
+		let get_history = |nonce: u32, number: u32| {
+			let transaction = LegacyUnsignedTransaction {
+				nonce: U256::from(nonce),
+				gas_price: U256::from(1),
+				gas_limit: U256::from(0x100000),
+				action: TransactionAction::Call(crate::HISTORY_STORAGE_ADDRESS),
+				value: U256::zero(),
+				input: H256::from_low_u64_be(number.into()).as_bytes().to_vec(),
+			}
+			.sign(&alice.private_key);
+			match Ethereum::execute(alice.address, &transaction, None, None).unwrap() {
+				(_, _, CallOrCreateInfo::Call(info)) => (info.exit_reason, info.value),
+				_ => panic!("expected call info"),
+			}
+		};
+
+		System::set_block_number(4);
+		for number in 1..=3 {
+			assert_ne!(hash(number), H256::zero());
+			assert_eq!(
+				crate::EthereumBlockHashMapping::<Test>::block_hash(number),
+				hash(number)
+			);
+		}
+		assert_eq!(
+			crate::EthereumBlockHashMapping::<Test>::block_hash(4),
+			H256::zero()
+		);
+		assert_eq!(
+			get_history(0, 2),
+			(
+				ExitReason::Succeed(ExitSucceed::Returned),
+				hash(2).as_bytes().to_vec()
+			)
+		);
+		assert_eq!(
+			get_history(1, 4).0,
+			ExitReason::Revert(ExitRevert::Reverted)
+		);
+
+		// `BLOCKHASH` only serves the last 256 blocks, unlike the history storage contract.
+		System::set_block_number(258);
+		assert_eq!(
+			crate::EthereumBlockHashMapping::<Test>::block_hash(1),
+			H256::zero()
+		);
+		assert_eq!(
+			crate::EthereumBlockHashMapping::<Test>::block_hash(2),
+			hash(2)
+		);
+		assert_eq!(
+			get_history(2, 1),
+			(
+				ExitReason::Succeed(ExitSucceed::Returned),
+				hash(1).as_bytes().to_vec()
+			)
+		);
+	});
+}
+
+#[test]
+fn beacon_roots_works() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		let get_root = |nonce: u32, timestamp: u64| {
+			let transaction = LegacyUnsignedTransaction {
+				nonce: U256::from(nonce),
+				gas_price: U256::from(1),
+				gas_limit: U256::from(0x100000),
+				action: TransactionAction::Call(crate::BEACON_ROOTS_ADDRESS),
+				value: U256::zero(),
+				input: H256::from_low_u64_be(timestamp).as_bytes().to_vec(),
+			}
+			.sign(&alice.private_key);
+			match Ethereum::execute(alice.address, &transaction, None, None).unwrap() {
+				(_, _, CallOrCreateInfo::Call(info)) => (info.exit_reason, info.value),
+				_ => panic!("expected call info"),
+			}
+		};
+
+		System::set_block_number(2);
+		System::set_parent_hash(H256::repeat_byte(1));
+		Timestamp::set_timestamp(12_000);
+		Ethereum::post_inherents();
+
+		assert_eq!(
+			get_root(0, 12),
+			(
+				ExitReason::Succeed(ExitSucceed::Returned),
+				H256::repeat_byte(1).as_bytes().to_vec()
+			)
+		);
+		assert_eq!(get_root(1, 13).0, ExitReason::Revert(ExitRevert::Reverted));
+		// The timestamp of a root overwritten in the ring buffer is rejected.
+		assert_eq!(
+			get_root(2, 12 + u64::from(crate::BEACON_ROOTS_HISTORY_BUFFER_LENGTH)).0,
+			ExitReason::Revert(ExitRevert::Reverted)
+		);
+	});
+}
+
+#[test]
+fn beacon_roots_charges_the_contract_deployment() {
+	let (_, mut ext) = new_test_ext(1);
+
+	ext.execute_with(|| {
+		MockDbWeight::set(RuntimeDbWeight { read: 1, write: 10 });
+		let post_inherents_weight = || {
+			let before = System::block_weight()
+				.get(DispatchClass::Mandatory)
+				.ref_time();
+			Ethereum::post_inherents();
+			System::block_weight()
+				.get(DispatchClass::Mandatory)
+				.ref_time() - before
+		};
+
+		// The first block also deploys the contract: 2 reads and 3 writes more.
+		System::set_block_number(2);
+		Timestamp::set_timestamp(12_000);
+		assert_eq!(post_inherents_weight(), 3 + 2 * 10 + 2 + 3 * 10);
+
+		System::set_block_number(3);
+		Timestamp::set_timestamp(24_000);
+		assert_eq!(post_inherents_weight(), 3 + 2 * 10);
+	});
+}
+
+#[test]
+fn transact_batch_works() {
+	let (pairs, mut ext) = new_test_ext(2);
+	let alice = &pairs[0];
+	let bob = &pairs[1];
+
+	ext.execute_with(|| {
+		let transfer = |from: &AccountInfo, to: &AccountInfo, nonce: u64| {
+			LegacyUnsignedTransaction {
+				nonce: U256::from(nonce),
+				gas_price: U256::from(1),
+				gas_limit: U256::from(0x100000),
+				action: TransactionAction::Call(to.address),
+				value: U256::from(1),
+				input: Vec::new(),
+			}
+			.sign(&from.private_key)
+		};
+		let extrinsic = |transactions: Vec<Transaction>| {
+			let call = crate::Call::<Test>::transact_batch { transactions };
+			let source = call.check_self_contained().unwrap().unwrap();
+			CheckedExtrinsic::<u64, _, SignedExtra, H160> {
+				signed: fp_self_contained::CheckedSignature::SelfContained(source),
+				function: RuntimeCall::Ethereum(call),
+			}
+		};
+
+		assert_eq!(
+			crate::Call::<Test>::transact_batch {
+				transactions: Vec::new()
+			}
+			.check_self_contained(),
+			Some(Err(InvalidTransaction::Call.into()))
+		);
+
+		// The nonces of the batch must follow each other.
+		let batch = extrinsic(vec![
+			transfer(alice, bob, 0),
+			transfer(bob, alice, 0),
+			transfer(alice, bob, 2),
+		]);
+		let dispatch_info = batch.get_dispatch_info();
+		assert_err!(
+			batch.apply::<Test>(&dispatch_info, 0),
+			TransactionValidityError::Invalid(InvalidTransaction::Future)
+		);
+
+		let transactions = vec![
+			transfer(alice, bob, 0),
+			transfer(bob, alice, 0),
+			transfer(alice, bob, 1),
+		];
+		let batch = extrinsic(transactions.clone());
+		let dispatch_info = batch.get_dispatch_info();
+		let RuntimeCall::Ethereum(call) = &batch.function else {
+			unreachable!()
+		};
+		assert_eq!(
+			call.validate_self_contained(&alice.address, &dispatch_info, 0)
+				.unwrap(),
+			ValidTransactionBuilder::default()
+				.and_provides((alice.address, U256::from(0)))
+				.and_provides((bob.address, U256::from(0)))
+				.and_provides((alice.address, U256::from(1)))
+				.priority(0u64)
+				.build()
+		);
+		assert_ok!(batch.apply::<Test>(&dispatch_info, 0).unwrap());
+
+		// The transactions are applied individually.
+		assert_eq!(crate::Pending::<Test>::count(), 3);
+		for (index, transaction) in transactions.iter().enumerate() {
+			let (pending, status, _) = crate::Pending::<Test>::get(index as u32).unwrap();
+			assert_eq!(&pending, transaction);
+			assert_eq!(status.transaction_hash, transaction.hash());
+		}
+		assert_eq!(
+			pallet_evm::Pallet::<Test>::account_basic(&alice.address)
+				.0
+				.nonce,
+			U256::from(2)
+		);
+		assert_eq!(
+			pallet_evm::Pallet::<Test>::account_basic(&bob.address)
+				.0
+				.nonce,
+			U256::from(1)
+		);
+	});
+}
+
+#[test]
+fn transact_batch_priority_is_the_lowest_of_its_transactions() {
+	let (pairs, mut ext) = new_test_ext(2);
+	let alice = &pairs[0];
+	let bob = &pairs[1];
+
+	ext.execute_with(|| {
+		let transfer = |from: &AccountInfo, to: &AccountInfo, gas_price: u64| {
+			LegacyUnsignedTransaction {
+				nonce: U256::zero(),
+				gas_price: U256::from(gas_price),
+				gas_limit: U256::from(0x100000),
+				action: TransactionAction::Call(to.address),
+				value: U256::from(1),
+				input: Vec::new(),
+			}
+			.sign(&from.private_key)
+		};
+
+		// With a base fee of 1, the tips of the transactions are 4 and 2.
+		let call = crate::Call::<Test>::transact_batch {
+			transactions: vec![transfer(alice, bob, 5), transfer(bob, alice, 3)],
+		};
+		let source = call.check_self_contained().unwrap().unwrap();
+		let extrinsic = CheckedExtrinsic::<u64, _, SignedExtra, H160> {
+			signed: fp_self_contained::CheckedSignature::SelfContained(source),
+			function: RuntimeCall::Ethereum(call.clone()),
+		};
+		let dispatch_info = extrinsic.get_dispatch_info();
+
+		assert_eq!(
+			call.validate_self_contained(&source, &dispatch_info, 0)
+				.unwrap()
+				.unwrap()
+				.priority,
+			2
+		);
+	});
+}
+
+#[test]
+fn transact_batch_keeps_the_transactions_applied_before_a_failure() {
+	let (pairs, mut ext) = new_test_ext(2);
+	let alice = &pairs[0];
+	let bob = &pairs[1];
+
+	ext.execute_with(|| {
+		let transfer = |from: &AccountInfo, to: &AccountInfo, nonce: u64, gas_price: u64| {
+			LegacyUnsignedTransaction {
+				nonce: U256::from(nonce),
+				gas_price: U256::from(gas_price),
+				gas_limit: U256::from(0x100000),
+				action: TransactionAction::Call(to.address),
+				value: U256::from(1),
+				input: Vec::new(),
+			}
+			.sign(&from.private_key)
+		};
+		let account =
+			|account: &AccountInfo| pallet_evm::Pallet::<Test>::account_basic(&account.address).0;
+
+		// Bob cannot pay for his transaction, which only fails once dispatched as the batch is
+		// not validated beforehand.
+		let applied = transfer(alice, bob, 0, 1);
+		let post_info = Ethereum::transact_batch(
+			RawOrigin::EthereumTransaction(alice.address).into(),
+			vec![
+				applied.clone(),
+				transfer(bob, alice, 0, 100),
+				transfer(alice, bob, 1, 1),
+			],
+		)
+		.unwrap();
+		assert_eq!(post_info.pays_fee, Pays::No);
+
+		// Only the transaction applied before the failure is kept, and it is paid for.
+		assert_eq!(crate::Pending::<Test>::count(), 1);
+		assert_eq!(crate::Pending::<Test>::get(0).unwrap().0, applied);
+		assert_eq!(account(alice).nonce, U256::from(1));
+		assert!(account(alice).balance < U256::from(10_000_000 - 1));
+		assert_eq!(account(bob).nonce, U256::zero());
+		assert_eq!(account(bob).balance, U256::from(10_000_000 + 1));
+	});
+}
@@ -16,6 +16,7 @@
 // limitations under the License.
 use super::*;
 use frame_benchmarking::benchmarks;
+use frame_support::traits::Hooks;
 
 type CurrencyOf<T> = <T as Config>::Currency;
 
@@ -31,6 +32,38 @@ benchmarks! {
 		assert!(result.is_err());
 		assert_eq!(result.unwrap_err(), sp_runtime::DispatchError::BadOrigin);
 	}
+
+	schedule_fork {
+		// A full schedule but for the scheduled fork, which is inserted first.
+		frame_system::Pallet::<T>::set_block_number(0u32.into());
+		let schedule = (2..=MAX_SCHEDULED_FORKS)
+			.map(|activation| (activation.into(), EvmConfigPreset::Pectra))
+			.collect::<Vec<_>>();
+		<ForkSchedule<T>>::put(BoundedVec::truncate_from(schedule));
+	}: _(RawOrigin::Root, 1u32.into(), EvmConfigPreset::Cancun)
+	verify {
+		assert_eq!(<ForkSchedule<T>>::get().len() as u32, MAX_SCHEDULED_FORKS);
+	}
+
+	cancel_fork {
+		frame_system::Pallet::<T>::set_block_number(0u32.into());
+		let schedule = (1..=MAX_SCHEDULED_FORKS)
+			.map(|activation| (activation.into(), EvmConfigPreset::Pectra))
+			.collect::<Vec<_>>();
+		<ForkSchedule<T>>::put(BoundedVec::truncate_from(schedule));
+	}: _(RawOrigin::Root, 1u32.into())
+	verify {
+		assert_eq!(<ForkSchedule<T>>::get().len() as u32, MAX_SCHEDULED_FORKS - 1);
+	}
+
+	on_initialize {
+		let schedule = (1..=MAX_SCHEDULED_FORKS)
+			.map(|activation| (activation.into(), EvmConfigPreset::Pectra))
+			.collect::<Vec<_>>();
+		<ForkSchedule<T>>::put(BoundedVec::truncate_from(schedule));
+	}: {
+		Pallet::<T>::on_initialize(1u32.into());
+	}
 }
 
 // impl_benchmark_test_suite!(Pallet, crate::tests::new_test_ext(), crate::mock::Test);
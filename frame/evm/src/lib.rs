@@ -97,7 +97,7 @@ use frame_support::{
 use frame_system::RawOrigin;
 use sp_core::{H160, H256, U256};
 use sp_runtime::{
-	traits::{BadOrigin, NumberFor, Saturating, UniqueSaturatedInto, Zero},
+	traits::{BadOrigin, NumberFor, One, Saturating, UniqueSaturatedInto, Zero},
 	AccountId32, DispatchErrorWithPostInfo,
 };
 // Frontier
@@ -287,6 +287,16 @@ pub mod pallet {
 		}
 	}
 
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(_: BlockNumberFor<T>) -> Weight {
+			// Every EVM execution of the block reads the fork schedule, it is charged once
+			// here as the later reads hit the storage cache.
+			let _ = <ForkSchedule<T>>::get();
+			<T as pallet::Config>::WeightInfo::on_initialize()
+		}
+	}
+
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		/// Withdraw balance from EVM into currency/balances pallet.
@@ -348,7 +358,7 @@ pub mod pallet {
 				validate,
 				None,
 				None,
-				T::config(),
+				Self::evm_config(),
 			) {
 				Ok(info) => info,
 				Err(e) => {
@@ -425,7 +435,7 @@ pub mod pallet {
 				validate,
 				None,
 				None,
-				T::config(),
+				Self::evm_config(),
 			) {
 				Ok(info) => info,
 				Err(e) => {
@@ -515,7 +525,7 @@ pub mod pallet {
 				validate,
 				None,
 				None,
-				T::config(),
+				Self::evm_config(),
 			) {
 				Ok(info) => info,
 				Err(e) => {
@@ -566,6 +576,57 @@ pub mod pallet {
 				pays_fee: Pays::No,
 			})
 		}
+
+		/// Schedule the EVM rules of `preset` to apply from block `activation` onwards, replacing
+		/// any fork already scheduled at that block.
+		#[pallet::call_index(4)]
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::schedule_fork())]
+		pub fn schedule_fork(
+			origin: OriginFor<T>,
+			activation: BlockNumberFor<T>,
+			preset: EvmConfigPreset,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			let now = frame_system::Pallet::<T>::block_number();
+			ensure!(activation > now, Error::<T>::ForkActivationInPast);
+
+			<ForkSchedule<T>>::try_mutate(|schedule| -> DispatchResult {
+				// Only the latest activated fork is still needed to derive the active rules.
+				while schedule.len() > 1 && schedule[1].0 <= now {
+					schedule.remove(0);
+				}
+				match schedule.binary_search_by_key(&activation, |(activation, _)| *activation) {
+					Ok(index) => schedule[index].1 = preset,
+					Err(index) => schedule
+						.try_insert(index, (activation, preset))
+						.map_err(|_| Error::<T>::TooManyForks)?,
+				}
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::ForkScheduled { activation, preset });
+			Ok(())
+		}
+
+		/// Cancel the fork scheduled at block `activation`, if it is not active yet.
+		#[pallet::call_index(5)]
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::cancel_fork())]
+		pub fn cancel_fork(origin: OriginFor<T>, activation: BlockNumberFor<T>) -> DispatchResult {
+			ensure_root(origin)?;
+			let now = frame_system::Pallet::<T>::block_number();
+			ensure!(activation > now, Error::<T>::ForkActivationInPast);
+
+			<ForkSchedule<T>>::try_mutate(|schedule| -> DispatchResult {
+				let index = schedule
+					.binary_search_by_key(&activation, |(activation, _)| *activation)
+					.map_err(|_| Error::<T>::ForkNotScheduled)?;
+				schedule.remove(index);
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::ForkCancelled { activation });
+			Ok(())
+		}
 	}
 
 	#[pallet::event]
@@ -581,6 +642,13 @@ pub mod pallet {
 		Executed { address: H160 },
 		/// A contract has been executed with errors. States are reverted with only gas fees applied.
 		ExecutedFailed { address: H160 },
+		/// The EVM rules of a preset were scheduled to apply from a block onwards.
+		ForkScheduled {
+			activation: BlockNumberFor<T>,
+			preset: EvmConfigPreset,
+		},
+		/// A scheduled fork was cancelled.
+		ForkCancelled { activation: BlockNumberFor<T> },
 	}
 
 	#[pallet::error]
@@ -613,6 +681,12 @@ pub mod pallet {
 		Undefined,
 		/// Address not allowed to deploy contracts either via CREATE or CALL(CREATE).
 		CreateOriginNotAllowed,
+		/// Forks can only be scheduled or cancelled for future blocks.
+		ForkActivationInPast,
+		/// No fork is scheduled at the given block.
+		ForkNotScheduled,
+		/// The fork schedule is full.
+		TooManyForks,
 	}
 
 	impl<T> From<TransactionValidationError> for Error<T> {
@@ -687,8 +761,20 @@ pub mod pallet {
 	#[pallet::storage]
 	pub type AccountStorages<T: Config> =
 		StorageDoubleMap<_, Blake2_128Concat, H160, Blake2_128Concat, H256, H256, ValueQuery>;
+
+	/// The EVM hard-fork schedule, as `(activation, preset)` pairs sorted by activation block.
+	/// Blocks before the first activation follow [`Config::config`].
+	#[pallet::storage]
+	pub type ForkSchedule<T: Config> = StorageValue<
+		_,
+		BoundedVec<(BlockNumberFor<T>, EvmConfigPreset), ConstU32<MAX_SCHEDULED_FORKS>>,
+		ValueQuery,
+	>;
 }
 
+/// Maximum number of entries in the fork schedule.
+pub const MAX_SCHEDULED_FORKS: u32 = 16;
+
 /// Utility alias for easy access to the [`AccountProvider::AccountId`] type from a given config.
 pub type AccountIdOf<T> = <<T as Config>::AccountProvider as AccountProvider>::AccountId;
 
@@ -724,6 +810,42 @@ impl CodeMetadata {
 	}
 }
 
+/// The EVM rules of an Ethereum hard fork, which can be scheduled on chain.
+#[derive(
+	Debug,
+	Clone,
+	Copy,
+	Eq,
+	PartialEq,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo,
+	MaxEncodedLen
+)]
+pub enum EvmConfigPreset {
+	Istanbul,
+	Berlin,
+	London,
+	Shanghai,
+	Cancun,
+	Pectra,
+}
+
+impl EvmConfigPreset {
+	/// EVM config of the hard fork.
+	pub fn config(&self) -> &'static EvmConfig {
+		match self {
+			Self::Istanbul => &ISTANBUL_CONFIG,
+			Self::Berlin => &BERLIN_CONFIG,
+			Self::London => &LONDON_CONFIG,
+			Self::Shanghai => &SHANGHAI_CONFIG,
+			Self::Cancun => &CANCUN_CONFIG,
+			Self::Pectra => &PECTRA_CONFIG,
+		}
+	}
+}
+
 pub trait EnsureAddressOrigin<OuterOrigin> {
 	/// Success return type.
 	type Success;
@@ -939,9 +1061,35 @@ where
 	}
 }
 
+static ISTANBUL_CONFIG: EvmConfig = EvmConfig::istanbul();
+static BERLIN_CONFIG: EvmConfig = EvmConfig::berlin();
+static LONDON_CONFIG: EvmConfig = EvmConfig::london();
+static SHANGHAI_CONFIG: EvmConfig = EvmConfig::shanghai();
+static CANCUN_CONFIG: EvmConfig = EvmConfig::cancun();
 static PECTRA_CONFIG: EvmConfig = EvmConfig::pectra();
 
 impl<T: Config> Pallet<T> {
+	/// EVM config of the rules active at the current block, according to the fork schedule.
+	pub fn evm_config() -> &'static EvmConfig {
+		Self::evm_config_at(frame_system::Pallet::<T>::block_number())
+	}
+
+	/// EVM config of the rules of the block following the current one, which the runtime API
+	/// calls executed on the state of the current block simulate.
+	pub fn next_block_evm_config() -> &'static EvmConfig {
+		Self::evm_config_at(frame_system::Pallet::<T>::block_number().saturating_add(One::one()))
+	}
+
+	/// EVM config of the rules active at block `number`, according to the fork schedule.
+	pub fn evm_config_at(number: BlockNumberFor<T>) -> &'static EvmConfig {
+		<ForkSchedule<T>>::get()
+			.iter()
+			.rev()
+			.find(|(activation, _)| *activation <= number)
+			.map(|(_, preset)| preset.config())
+			.unwrap_or_else(T::config)
+	}
+
 	/// Check whether an account is empty.
 	pub fn is_account_empty(address: &H160) -> bool {
 		let (account, _) = Self::account_basic(address);
@@ -279,6 +279,9 @@ where
 					weight,
 				})?;
 
+		#[cfg(feature = "tracing")]
+		super::tracing::on_transaction_start::<T>(source);
+
 		// Deduct fee from the `source` account. Returns `None` if `total_fee` is Zero.
 		let fee = T::OnChargeTransaction::withdraw_fee(&source, total_fee)
 			.map_err(|e| RunnerError { error: e, weight })?;
@@ -307,6 +310,11 @@ where
 			|| {
 				let (reason, retv) = f(&mut executor);
 
+				// Report the accessed addresses and storage keys, e.g. to build access lists.
+				if let Some(accessed) = executor.state().metadata().accessed() {
+					fp_evm::record_accessed(accessed);
+				}
+
 				// Compute the storage gas cost based on the storage growth.
 				let storage_gas = match &executor.state().storage_meter {
 					Some(storage_meter) => storage_meter.storage_to_gas(storage_growth_ratio),
@@ -367,6 +375,9 @@ where
 			},
 		);
 
+		#[cfg(feature = "tracing")]
+		super::tracing::on_transaction_end(&reason, effective_gas.unique_saturated_into());
+
 		let actual_fee = effective_gas.saturating_mul(total_fee_per_gas);
 		let actual_base_fee = effective_gas.saturating_mul(base_fee);
 
@@ -1057,7 +1068,16 @@ where
 	}
 
 	fn block_hash(&self, number: U256) -> H256 {
-		if number > U256::from(u32::MAX) {
+		let overridden = fp_evm::block_override(|overrides| {
+			overrides
+				.block_hashes
+				.iter()
+				.find(|(overridden, _)| *overridden == number)
+				.map(|(_, hash)| *hash)
+		});
+		if let Some(hash) = overridden {
+			hash
+		} else if number > U256::from(u32::MAX) {
 			H256::default()
 		} else {
 			T::BlockHashMapping::block_hash(number.as_u32())
@@ -1065,17 +1085,22 @@ where
 	}
 
 	fn block_number(&self) -> U256 {
-		let number: u128 = frame_system::Pallet::<T>::block_number().unique_saturated_into();
-		U256::from(number)
+		fp_evm::block_override(|overrides| overrides.number).unwrap_or_else(|| {
+			let number: u128 = frame_system::Pallet::<T>::block_number().unique_saturated_into();
+			U256::from(number)
+		})
 	}
 
 	fn block_coinbase(&self) -> H160 {
-		Pallet::<T>::find_author()
+		fp_evm::block_override(|overrides| overrides.coinbase)
+			.unwrap_or_else(Pallet::<T>::find_author)
 	}
 
 	fn block_timestamp(&self) -> U256 {
-		let now: u128 = T::Timestamp::now().unique_saturated_into();
-		U256::from(now / 1000)
+		fp_evm::block_override(|overrides| overrides.timestamp).unwrap_or_else(|| {
+			let now: u128 = T::Timestamp::now().unique_saturated_into();
+			U256::from(now / 1000)
+		})
 	}
 
 	fn block_difficulty(&self) -> U256 {
@@ -1083,16 +1108,19 @@ where
 	}
 
 	fn block_randomness(&self) -> Option<H256> {
-		None
+		fp_evm::block_override(|overrides| overrides.random)
 	}
 
 	fn block_gas_limit(&self) -> U256 {
-		T::BlockGasLimit::get()
+		fp_evm::block_override(|overrides| overrides.gas_limit)
+			.unwrap_or_else(T::BlockGasLimit::get)
 	}
 
 	fn block_base_fee_per_gas(&self) -> U256 {
-		let (base_fee, _) = T::FeeCalculator::min_gas_price();
-		base_fee
+		fp_evm::block_override(|overrides| overrides.base_fee).unwrap_or_else(|| {
+			let (base_fee, _) = T::FeeCalculator::min_gas_price();
+			base_fee
+		})
 	}
 
 	fn chain_id(&self) -> U256 {
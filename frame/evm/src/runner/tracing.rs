@@ -0,0 +1,817 @@
+// This file is part of Frontier.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Opt-in tracing of EVM executions.
+//!
+//! A [`Tracer`] is installed with [`using`] around any code that ends up in the stack runner,
+//! typically the application of an Ethereum extrinsic. Events emitted by the executor, the
+//! interpreter and the gasometer are forwarded to the installed tracer, which builds the
+//! requested [`TraceResult`].
+
+use alloc::{collections::btree_map::BTreeMap, vec::Vec};
+use evm::{
+	tracing::{Event as EvmEvent, EventListener as EvmListener},
+	CreateScheme, ExitReason, ExitSucceed, Opcode,
+};
+use evm_gasometer::tracing::{Event as GasometerEvent, EventListener as GasometerListener};
+use evm_runtime::tracing::{Event as RuntimeEvent, EventListener as RuntimeListener};
+use sp_core::{H160, H256, U256};
+// Frontier
+use fp_evm::tracing::{
+	AccountState, CallFrame, CallTracerConfig, CallType, PrestateResult, PrestateTracerConfig,
+	StructLog, StructLoggerConfig, StructLoggerResult, TraceResult, TracerConfig,
+};
+
+use crate::{AccountCodes, AccountStorages, Config, Pallet};
+
+environmental::environmental!(TRACER: Tracer);
+
+/// Run `f` with `tracer` listening to every EVM execution happening inside of it.
+pub fn using<R, F: FnOnce() -> R>(tracer: &mut Tracer, f: F) -> R {
+	TRACER::using(tracer, || {
+		evm::tracing::using(&mut ListenerProxy, || {
+			evm_gasometer::tracing::using(&mut ListenerProxy, || {
+				evm_runtime::tracing::using(&mut ListenerProxy, f)
+			})
+		})
+	})
+}
+
+/// Called by the runner before the fees of an EVM operation are withdrawn.
+pub(crate) fn on_transaction_start<T: Config>(source: H160) {
+	TRACER::with(|tracer| {
+		if let TracerKind::PrestateTracer(prestate) = &mut tracer.kind {
+			prestate.reader.get_or_insert_with(StateReader::new::<T>);
+			prestate.touch_account(source);
+		}
+	});
+}
+
+/// Called by the runner once an EVM operation is finished.
+pub(crate) fn on_transaction_end(reason: &ExitReason, used_gas: u64) {
+	TRACER::with(|tracer| {
+		tracer.exit_reason = Some(reason.clone());
+		tracer.used_gas = used_gas;
+	});
+}
+
+/// Called once an Ethereum transaction is applied by a call applying several of them, so each
+/// one gets its own trace. Transactions left unapplied are not reported.
+pub fn on_ethereum_transaction_applied(transaction_hash: H256) {
+	TRACER::with(|tracer| tracer.finish_transaction(transaction_hash));
+}
+
+/// Forwards the events of the `evm` crates to the installed [`Tracer`].
+struct ListenerProxy;
+
+impl EvmListener for ListenerProxy {
+	fn event(&mut self, event: EvmEvent) {
+		TRACER::with(|tracer| tracer.on_evm_event(event));
+	}
+}
+
+impl RuntimeListener for ListenerProxy {
+	fn event(&mut self, event: RuntimeEvent) {
+		TRACER::with(|tracer| tracer.on_runtime_event(event));
+	}
+}
+
+impl GasometerListener for ListenerProxy {
+	fn event(&mut self, event: GasometerEvent) {
+		TRACER::with(|tracer| tracer.on_gasometer_event(event));
+	}
+}
+
+/// Collects the trace of an EVM execution.
+pub struct Tracer {
+	config: TracerConfig,
+	kind: TracerKind,
+	/// Exit reason of the outermost call frame.
+	exit_reason: Option<ExitReason>,
+	/// Data returned by the outermost call frame.
+	return_value: Vec<u8>,
+	/// Gas used by the transaction, as reported by the runner.
+	used_gas: u64,
+	/// Traces of the Ethereum transactions already applied, by hash.
+	transactions: Vec<(H256, TraceResult)>,
+}
+
+enum TracerKind {
+	StructLogger(StructLogger),
+	CallTracer(CallTracer),
+	PrestateTracer(PrestateTracer),
+}
+
+impl Tracer {
+	pub fn new(config: TracerConfig) -> Self {
+		let kind = match config.clone() {
+			TracerConfig::StructLogger(config) => TracerKind::StructLogger(StructLogger {
+				config,
+				..Default::default()
+			}),
+			TracerConfig::CallTracer(config) => TracerKind::CallTracer(CallTracer {
+				config,
+				..Default::default()
+			}),
+			TracerConfig::PrestateTracer(config) => TracerKind::PrestateTracer(PrestateTracer {
+				config,
+				..Default::default()
+			}),
+		};
+		Self {
+			config,
+			kind,
+			exit_reason: None,
+			return_value: Vec::new(),
+			used_gas: 0,
+			transactions: Vec::new(),
+		}
+	}
+
+	/// Consume the tracer and return the trace of each Ethereum transaction reported with
+	/// [`on_ethereum_transaction_applied`], in order.
+	pub fn into_transaction_results(self) -> Vec<(H256, TraceResult)> {
+		self.transactions
+	}
+
+	fn finish_transaction(&mut self, transaction_hash: H256) {
+		let mut finished = core::mem::replace(self, Self::new(self.config.clone()));
+		self.transactions = core::mem::take(&mut finished.transactions);
+		self.transactions
+			.push((transaction_hash, finished.into_result()));
+	}
+
+	/// Consume the tracer and build the trace of the execution.
+	///
+	/// The prestate tracer reads the post-execution state, so this must be called right after
+	/// the traced execution.
+	pub fn into_result(self) -> TraceResult {
+		let failed = !matches!(self.exit_reason, Some(ExitReason::Succeed(_)));
+		match self.kind {
+			TracerKind::StructLogger(logger) => TraceResult::StructLogger(StructLoggerResult {
+				gas: self.used_gas,
+				failed,
+				return_value: self.return_value,
+				struct_logs: logger.logs,
+			}),
+			TracerKind::CallTracer(mut tracer) => {
+				// Frames still open at this point were never exited by the executor, which
+				// happens when the outermost frame is exited at the transaction level only.
+				while !tracer.stack.is_empty() {
+					let exit_reason = self
+						.exit_reason
+						.clone()
+						.unwrap_or(ExitReason::Succeed(ExitSucceed::Stopped));
+					tracer.exit_frame(&exit_reason, &self.return_value);
+				}
+				let mut root = tracer.root.unwrap_or_else(|| CallFrame {
+					call_type: CallType::Call,
+					from: H160::default(),
+					to: None,
+					value: None,
+					gas: 0,
+					gas_used: 0,
+					input: Vec::new(),
+					output: self.return_value.clone(),
+					exit_reason: self.exit_reason.clone(),
+					calls: Vec::new(),
+				});
+				// The outermost frame reports the gas of the whole transaction.
+				if let Some(gas_limit) = tracer.transaction_gas_limit {
+					root.gas = gas_limit;
+				}
+				root.gas_used = self.used_gas;
+				TraceResult::CallTracer(root)
+			}
+			TracerKind::PrestateTracer(tracer) => TraceResult::PrestateTracer(tracer.into_result()),
+		}
+	}
+
+	fn on_evm_event(&mut self, event: EvmEvent) {
+		let outermost_exit = match event {
+			EvmEvent::Exit {
+				reason,
+				return_value,
+			} => Some((reason.clone(), return_value.to_vec())),
+			_ => None,
+		};
+
+		let depth = match &mut self.kind {
+			TracerKind::StructLogger(logger) => logger.on_evm_event(event),
+			TracerKind::CallTracer(tracer) => tracer.on_evm_event(event),
+			TracerKind::PrestateTracer(tracer) => tracer.on_evm_event(event),
+		};
+
+		if let (Some((reason, return_value)), 0) = (outermost_exit, depth) {
+			self.exit_reason = Some(reason);
+			self.return_value = return_value;
+		}
+	}
+
+	fn on_runtime_event(&mut self, event: RuntimeEvent) {
+		match &mut self.kind {
+			TracerKind::StructLogger(logger) => logger.on_runtime_event(event),
+			TracerKind::CallTracer(tracer) => {
+				if let RuntimeEvent::Step { opcode, .. } = event {
+					tracer.last_opcode = Some(opcode);
+				}
+			}
+			TracerKind::PrestateTracer(tracer) => tracer.on_runtime_event(event),
+		}
+	}
+
+	fn on_gasometer_event(&mut self, event: GasometerEvent) {
+		match &mut self.kind {
+			TracerKind::StructLogger(logger) => logger.on_gasometer_event(event),
+			TracerKind::CallTracer(tracer) => tracer.on_gasometer_event(event),
+			TracerKind::PrestateTracer(_) => {}
+		}
+	}
+}
+
+/// Gas left and gas charged by a gasometer event, plus the gas used by the gasometer after it.
+struct GasRecord {
+	gas_limit: u64,
+	remaining: u64,
+	cost: u64,
+	used_after: u64,
+}
+
+fn gas_record(event: GasometerEvent) -> Option<GasRecord> {
+	let (snapshot, cost, used_after) = match event {
+		GasometerEvent::RecordCost { cost, snapshot } => {
+			let snapshot = snapshot?;
+			let used_after = snapshot
+				.used_gas
+				.saturating_add(snapshot.memory_gas)
+				.saturating_add(cost);
+			(snapshot, cost, used_after)
+		}
+		GasometerEvent::RecordDynamicCost {
+			gas_cost,
+			memory_gas,
+			snapshot,
+			..
+		} => {
+			let snapshot = snapshot?;
+			let cost = gas_cost.saturating_add(memory_gas.saturating_sub(snapshot.memory_gas));
+			let used_after = snapshot
+				.used_gas
+				.saturating_add(gas_cost)
+				.saturating_add(memory_gas.max(snapshot.memory_gas));
+			(snapshot, cost, used_after)
+		}
+		GasometerEvent::RecordStipend { stipend, snapshot } => {
+			let snapshot = snapshot?;
+			let used_after = snapshot
+				.used_gas
+				.saturating_sub(stipend)
+				.saturating_add(snapshot.memory_gas);
+			(snapshot, 0, used_after)
+		}
+		GasometerEvent::RecordRefund { snapshot, .. } => {
+			let snapshot = snapshot?;
+			let used_after = snapshot.used_gas.saturating_add(snapshot.memory_gas);
+			(snapshot, 0, used_after)
+		}
+		GasometerEvent::RecordTransaction { cost, snapshot } => {
+			let snapshot = snapshot?;
+			let used_after = snapshot
+				.used_gas
+				.saturating_add(snapshot.memory_gas)
+				.saturating_add(cost);
+			(snapshot, 0, used_after)
+		}
+	};
+
+	Some(GasRecord {
+		gas_limit: snapshot.gas_limit,
+		remaining: snapshot
+			.gas_limit
+			.saturating_sub(snapshot.used_gas)
+			.saturating_sub(snapshot.memory_gas),
+		cost,
+		used_after,
+	})
+}
+
+/// Opcode level tracer, equivalent to geth's default struct logger.
+#[derive(Default)]
+struct StructLogger {
+	config: StructLoggerConfig,
+	logs: Vec<StructLog>,
+	depth: u32,
+	/// Whether the last captured step is still being charged by the gasometer.
+	step_pending: bool,
+	/// Storage accessed so far, by contract.
+	storage: BTreeMap<H160, BTreeMap<H256, H256>>,
+	/// Return data of the last call frame that exited.
+	return_data: Vec<u8>,
+}
+
+impl StructLogger {
+	fn limit_reached(&self) -> bool {
+		self.config.limit > 0 && self.logs.len() as u64 >= self.config.limit
+	}
+
+	/// Returns the depth after the event.
+	fn on_evm_event(&mut self, event: EvmEvent) -> u32 {
+		match event {
+			EvmEvent::Call { .. } | EvmEvent::Create { .. } => {
+				self.depth = self.depth.saturating_add(1);
+				self.step_pending = false;
+			}
+			EvmEvent::Exit { return_value, .. } => {
+				self.depth = self.depth.saturating_sub(1);
+				self.step_pending = false;
+				self.return_data = return_value.to_vec();
+			}
+			_ => {}
+		}
+		self.depth
+	}
+
+	fn on_runtime_event(&mut self, event: RuntimeEvent) {
+		match event {
+			RuntimeEvent::Step {
+				opcode,
+				position,
+				stack,
+				memory,
+				..
+			} => {
+				if self.limit_reached() {
+					self.step_pending = false;
+					return;
+				}
+				self.logs.push(StructLog {
+					pc: position.as_ref().map(|pc| *pc as u64).unwrap_or_default(),
+					op: opcode.0,
+					gas: 0,
+					gas_cost: 0,
+					depth: self.depth,
+					stack: (!self.config.disable_stack).then(|| stack.data().clone()),
+					memory: (!self.config.disable_memory).then(|| memory.data().clone()),
+					storage: None,
+					return_data: self
+						.config
+						.enable_return_data
+						.then(|| self.return_data.clone()),
+				});
+				self.step_pending = true;
+			}
+			RuntimeEvent::SLoad {
+				address,
+				index,
+				value,
+			}
+			| RuntimeEvent::SStore {
+				address,
+				index,
+				value,
+			} => {
+				if self.config.disable_storage || !self.step_pending {
+					return;
+				}
+				let storage = self.storage.entry(address).or_default();
+				storage.insert(index, value);
+				let storage = storage.clone();
+				if let Some(log) = self.logs.last_mut() {
+					log.storage = Some(storage);
+				}
+			}
+			_ => {}
+		}
+	}
+
+	fn on_gasometer_event(&mut self, event: GasometerEvent) {
+		if !self.step_pending {
+			return;
+		}
+		let (Some(record), Some(log)) = (gas_record(event), self.logs.last_mut()) else {
+			return;
+		};
+		if log.gas == 0 && log.gas_cost == 0 {
+			log.gas = record.remaining;
+		}
+		log.gas_cost = log.gas_cost.saturating_add(record.cost);
+	}
+}
+
+/// A call frame that has not exited yet.
+struct PendingFrame {
+	frame: CallFrame,
+	/// Gas limit and gas used of the last gasometer event attributed to this frame.
+	last_gas_record: Option<(u64, u64)>,
+}
+
+/// Call tree tracer, equivalent to geth's `callTracer`.
+#[derive(Default)]
+struct CallTracer {
+	config: CallTracerConfig,
+	/// Frames that have not exited yet, innermost last.
+	stack: Vec<PendingFrame>,
+	/// The outermost frame, once exited.
+	root: Option<CallFrame>,
+	/// Number of nested frames not captured because of `only_top_call`.
+	skipped_depth: u32,
+	/// Opcode of the last interpreted step, used to tell apart the kind of the next call.
+	last_opcode: Option<Opcode>,
+	/// Gas limit of the traced transaction.
+	transaction_gas_limit: Option<u64>,
+}
+
+impl CallTracer {
+	fn enter_frame(&mut self, frame: CallFrame) {
+		if self.config.only_top_call && !self.stack.is_empty() {
+			self.skipped_depth = self.skipped_depth.saturating_add(1);
+			return;
+		}
+		self.stack.push(PendingFrame {
+			frame,
+			last_gas_record: None,
+		});
+	}
+
+	fn exit_frame(&mut self, reason: &ExitReason, return_value: &[u8]) {
+		if self.skipped_depth > 0 {
+			self.skipped_depth -= 1;
+			return;
+		}
+		let Some(PendingFrame {
+			mut frame,
+			last_gas_record,
+		}) = self.stack.pop()
+		else {
+			return;
+		};
+
+		if let Some((gas_limit, used_gas)) = last_gas_record {
+			frame.gas = gas_limit;
+			frame.gas_used = used_gas.min(gas_limit);
+		}
+		// Errors other than reverts consume all the gas of the frame.
+		if matches!(reason, ExitReason::Error(_) | ExitReason::Fatal(_)) {
+			frame.gas_used = frame.gas;
+		}
+		frame.exit_reason = Some(reason.clone());
+		frame.output = return_value.to_vec();
+
+		match self.stack.last_mut() {
+			Some(parent) => parent.frame.calls.push(frame),
+			None => self.root = Some(frame),
+		}
+	}
+
+	/// Returns the depth after the event.
+	fn on_evm_event(&mut self, event: EvmEvent) -> u32 {
+		match event {
+			EvmEvent::TransactCall { gas_limit, .. }
+			| EvmEvent::TransactCreate { gas_limit, .. }
+			| EvmEvent::TransactCreate2 { gas_limit, .. } => {
+				if self.stack.is_empty() {
+					self.transaction_gas_limit = Some(gas_limit);
+				}
+			}
+			EvmEvent::Call {
+				code_address,
+				transfer,
+				input,
+				target_gas,
+				is_static,
+				context,
+			} => {
+				let call_type = if self.stack.is_empty() {
+					CallType::Call
+				} else {
+					match self.last_opcode {
+						Some(Opcode::CALLCODE) => CallType::CallCode,
+						Some(Opcode::DELEGATECALL) => CallType::DelegateCall,
+						Some(Opcode::STATICCALL) => CallType::StaticCall,
+						_ if is_static => CallType::StaticCall,
+						_ => CallType::Call,
+					}
+				};
+				let (from, value) = match call_type {
+					CallType::DelegateCall => (context.address, None),
+					CallType::StaticCall => (context.caller, None),
+					_ => (
+						context.caller,
+						Some(transfer.as_ref().map(|t| t.value).unwrap_or_default()),
+					),
+				};
+				self.enter_frame(CallFrame {
+					call_type,
+					from,
+					to: Some(code_address),
+					value,
+					gas: target_gas.unwrap_or_default(),
+					gas_used: 0,
+					input: input.to_vec(),
+					output: Vec::new(),
+					exit_reason: None,
+					calls: Vec::new(),
+				});
+			}
+			EvmEvent::Create {
+				caller,
+				address,
+				scheme,
+				value,
+				init_code,
+				target_gas,
+			} => {
+				let call_type = match scheme {
+					CreateScheme::Create2 { .. } => CallType::Create2,
+					_ => CallType::Create,
+				};
+				self.enter_frame(CallFrame {
+					call_type,
+					from: caller,
+					to: Some(address),
+					value: Some(value),
+					gas: target_gas.unwrap_or_default(),
+					gas_used: 0,
+					input: init_code.to_vec(),
+					output: Vec::new(),
+					exit_reason: None,
+					calls: Vec::new(),
+				});
+			}
+			EvmEvent::Suicide {
+				address,
+				target,
+				balance,
+			} => {
+				if self.config.only_top_call {
+					return self.stack.len() as u32;
+				}
+				if let Some(parent) = self.stack.last_mut() {
+					parent.frame.calls.push(CallFrame {
+						call_type: CallType::SelfDestruct,
+						from: address,
+						to: Some(target),
+						value: Some(balance),
+						gas: 0,
+						gas_used: 0,
+						input: Vec::new(),
+						output: Vec::new(),
+						exit_reason: Some(ExitReason::Succeed(ExitSucceed::Suicided)),
+						calls: Vec::new(),
+					});
+				}
+			}
+			EvmEvent::Exit {
+				reason,
+				return_value,
+			} => self.exit_frame(reason, return_value),
+			_ => {}
+		}
+		self.stack.len() as u32 + self.skipped_depth
+	}
+
+	fn on_gasometer_event(&mut self, event: GasometerEvent) {
+		if self.skipped_depth > 0 {
+			return;
+		}
+		let Some(record) = gas_record(event) else {
+			return;
+		};
+		let len = self.stack.len();
+		// Right after entering a frame, the caller is still charged for the gas it hands over.
+		// Such events are recognized by the gas limit of the caller's gasometer.
+		if len >= 2 {
+			if let Some((parent_gas_limit, _)) = self.stack[len - 2].last_gas_record {
+				if parent_gas_limit == record.gas_limit {
+					self.stack[len - 2].last_gas_record =
+						Some((record.gas_limit, record.used_after));
+					return;
+				}
+			}
+		}
+		if let Some(frame) = self.stack.last_mut() {
+			frame.last_gas_record = Some((record.gas_limit, record.used_after));
+		}
+	}
+}
+
+/// Reads the state of accounts from the pallet storage.
+#[derive(Clone, Copy)]
+struct StateReader {
+	basic: fn(H160) -> (U256, U256),
+	code: fn(H160) -> Vec<u8>,
+	storage: fn(H160, H256) -> H256,
+}
+
+impl StateReader {
+	fn new<T: Config>() -> Self {
+		Self {
+			basic: |address| {
+				let (account, _) = Pallet::<T>::account_basic(&address);
+				(account.balance, account.nonce)
+			},
+			code: |address| AccountCodes::<T>::get(address),
+			storage: |address, index| AccountStorages::<T>::get(address, index),
+		}
+	}
+
+	/// Current balance, nonce and code of an account, without its storage.
+	fn account(&self, address: H160) -> AccountState {
+		let (balance, nonce) = (self.basic)(address);
+		let code = (self.code)(address);
+		AccountState {
+			balance: Some(balance),
+			nonce: Some(nonce),
+			code: (!code.is_empty()).then_some(code),
+			storage: BTreeMap::new(),
+		}
+	}
+}
+
+/// Touched accounts tracer, equivalent to geth's `prestateTracer`.
+///
+/// The state of an account or a storage slot is read when it is first touched, which is before
+/// the execution modifies it.
+#[derive(Default)]
+struct PrestateTracer {
+	config: PrestateTracerConfig,
+	/// Set by the runner, nothing is captured before that.
+	reader: Option<StateReader>,
+	depth: u32,
+	/// State of the touched accounts before the execution.
+	pre: BTreeMap<H160, AccountState>,
+}
+
+impl PrestateTracer {
+	fn touch_account(&mut self, address: H160) {
+		let Some(reader) = self.reader else {
+			return;
+		};
+		self.pre
+			.entry(address)
+			.or_insert_with(|| reader.account(address));
+	}
+
+	fn touch_storage(&mut self, address: H160, index: H256, value: Option<H256>) {
+		let Some(reader) = self.reader else {
+			return;
+		};
+		self.touch_account(address);
+		if let Some(account) = self.pre.get_mut(&address) {
+			account
+				.storage
+				.entry(index)
+				.or_insert_with(|| value.unwrap_or_else(|| (reader.storage)(address, index)));
+		}
+	}
+
+	/// Returns the depth after the event.
+	fn on_evm_event(&mut self, event: EvmEvent) -> u32 {
+		match event {
+			EvmEvent::TransactCall {
+				caller, address, ..
+			}
+			| EvmEvent::TransactCreate {
+				caller, address, ..
+			}
+			| EvmEvent::TransactCreate2 {
+				caller, address, ..
+			} => {
+				self.touch_account(caller);
+				self.touch_account(address);
+			}
+			EvmEvent::Call {
+				code_address,
+				transfer,
+				context,
+				..
+			} => {
+				self.depth = self.depth.saturating_add(1);
+				self.touch_account(context.caller);
+				self.touch_account(context.address);
+				self.touch_account(code_address);
+				if let Some(transfer) = transfer {
+					self.touch_account(transfer.source);
+					self.touch_account(transfer.target);
+				}
+			}
+			EvmEvent::Create {
+				caller, address, ..
+			} => {
+				self.depth = self.depth.saturating_add(1);
+				self.touch_account(caller);
+				self.touch_account(address);
+			}
+			EvmEvent::Suicide {
+				address, target, ..
+			} => {
+				self.touch_account(address);
+				self.touch_account(target);
+			}
+			EvmEvent::Exit { .. } => {
+				self.depth = self.depth.saturating_sub(1);
+			}
+			_ => {}
+		}
+		self.depth
+	}
+
+	fn on_runtime_event(&mut self, event: RuntimeEvent) {
+		match event {
+			RuntimeEvent::Step { opcode, stack, .. } => {
+				// Opcodes reading another account than the executing one.
+				if matches!(
+					opcode,
+					Opcode::BALANCE
+						| Opcode::EXTCODESIZE
+						| Opcode::EXTCODECOPY
+						| Opcode::EXTCODEHASH
+				) {
+					if let Some(top) = stack.data().last() {
+						self.touch_account(H160::from_slice(&top.as_bytes()[12..]));
+					}
+				}
+			}
+			RuntimeEvent::SLoad {
+				address,
+				index,
+				value,
+			} => self.touch_storage(address, index, Some(value)),
+			RuntimeEvent::SStore { address, index, .. } => self.touch_storage(address, index, None),
+			_ => {}
+		}
+	}
+
+	fn into_result(self) -> PrestateResult {
+		let reader = match self.reader {
+			Some(reader) if self.config.diff_mode => reader,
+			_ => return PrestateResult::Prestate(self.pre),
+		};
+
+		let mut pre_diff = BTreeMap::new();
+		let mut post_diff = BTreeMap::new();
+		for (address, pre) in self.pre {
+			let post = reader.account(address);
+			let mut pre_state = AccountState {
+				storage: BTreeMap::new(),
+				..pre.clone()
+			};
+			let mut post_state = AccountState::default();
+			let mut modified = false;
+
+			if post.balance != pre.balance {
+				post_state.balance = post.balance;
+				modified = true;
+			}
+			if post.nonce != pre.nonce {
+				post_state.nonce = post.nonce;
+				modified = true;
+			}
+			if post.code != pre.code {
+				post_state.code = post.code.clone();
+				modified = true;
+			}
+			for (index, value) in pre.storage {
+				let new_value = (reader.storage)(address, index);
+				if new_value != value {
+					modified = true;
+					pre_state.storage.insert(index, value);
+					if !new_value.is_zero() {
+						post_state.storage.insert(index, new_value);
+					}
+				}
+			}
+
+			if !modified {
+				continue;
+			}
+			pre_diff.insert(address, pre_state);
+			// Deleted accounts are left out of the post state.
+			let deleted = post.balance.unwrap_or_default().is_zero()
+				&& post.nonce.unwrap_or_default().is_zero()
+				&& post.code.is_none();
+			if !deleted {
+				post_diff.insert(address, post_state);
+			}
+		}
+
+		PrestateResult::Diff {
+			pre: pre_diff,
+			post: post_diff,
+		}
+	}
+}
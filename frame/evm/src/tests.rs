@@ -20,7 +20,7 @@ use crate::mock::*;
 
 use evm::ExitReason;
 use frame_support::{
-	assert_ok,
+	assert_noop, assert_ok,
 	traits::{LockIdentifier, LockableCurrency, WithdrawReasons},
 };
 use sp_runtime::BuildStorage;
@@ -1701,3 +1701,266 @@ fn metadata_empty_dont_code_gets_cached() {
 		assert!(<AccountCodesMetadata<Test>>::get(address).is_none());
 	});
 }
+
+#[test]
+fn fork_schedule_works() {
+	new_test_ext().execute_with(|| {
+		let is_active = |config: &'static EvmConfig| core::ptr::eq(EVM::evm_config(), config);
+		System::set_block_number(10);
+
+		assert_noop!(
+			EVM::schedule_fork(
+				RuntimeOrigin::signed(Default::default()),
+				20,
+				EvmConfigPreset::London
+			),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_noop!(
+			EVM::schedule_fork(RuntimeOrigin::root(), 10, EvmConfigPreset::London),
+			Error::<Test>::ForkActivationInPast
+		);
+		assert_ok!(EVM::schedule_fork(
+			RuntimeOrigin::root(),
+			30,
+			EvmConfigPreset::Cancun
+		));
+		assert_ok!(EVM::schedule_fork(
+			RuntimeOrigin::root(),
+			20,
+			EvmConfigPreset::London
+		));
+		assert_ok!(EVM::schedule_fork(
+			RuntimeOrigin::root(),
+			40,
+			EvmConfigPreset::Pectra
+		));
+		assert_ok!(EVM::cancel_fork(RuntimeOrigin::root(), 40));
+		assert_noop!(
+			EVM::cancel_fork(RuntimeOrigin::root(), 40),
+			Error::<Test>::ForkNotScheduled
+		);
+
+		// Blocks before the first activation follow `Config::config`.
+		assert!(is_active(<Test as Config>::config()));
+		System::set_block_number(20);
+		assert!(is_active(EvmConfigPreset::London.config()));
+		System::set_block_number(29);
+		assert!(is_active(EvmConfigPreset::London.config()));
+		// Runtime API calls on the state of block 29 simulate block 30.
+		assert!(core::ptr::eq(
+			EVM::next_block_evm_config(),
+			EvmConfigPreset::Cancun.config()
+		));
+		assert!(core::ptr::eq(
+			EVM::evm_config_at(19),
+			<Test as Config>::config()
+		));
+		System::set_block_number(30);
+		assert!(is_active(EvmConfigPreset::Cancun.config()));
+
+		// Superseded forks are dropped when scheduling a new one.
+		assert_ok!(EVM::schedule_fork(
+			RuntimeOrigin::root(),
+			50,
+			EvmConfigPreset::Pectra
+		));
+		assert_eq!(
+			<ForkSchedule<Test>>::get().into_inner(),
+			vec![(30, EvmConfigPreset::Cancun), (50, EvmConfigPreset::Pectra)]
+		);
+		assert!(is_active(EvmConfigPreset::Cancun.config()));
+	});
+}
+
+#[cfg(feature = "tracing")]
+mod tracing_test {
+	use super::*;
+	use crate::runner::tracing::{using, Tracer};
+	use evm::{ExitRevert, ExitSucceed};
+	use fp_evm::tracing::{
+		CallTracerConfig, CallType, StructLoggerConfig, TraceResult, TracerConfig,
+	};
+
+	const GAS_LIMIT: u64 = 1_000_000;
+
+	fn deploy(address: H160, code: Vec<u8>) {
+		EVM::create_account(address, code, None).expect("account is created");
+	}
+
+	fn trace_call(target: H160, config: TracerConfig) -> (CallInfo, TraceResult) {
+		let mut tracer = Tracer::new(config);
+		let info = using(&mut tracer, || {
+			<Test as Config>::Runner::call(
+				H160::default(),
+				target,
+				Vec::new(),
+				U256::zero(),
+				GAS_LIMIT,
+				Some(FixedGasPrice::min_gas_price().0),
+				None,
+				None,
+				Vec::new(),
+				Vec::new(),
+				true, // transactional
+				true, // must be validated
+				None,
+				None,
+				&<Test as Config>::config().clone(),
+			)
+			.expect("call succeeds")
+		});
+		(info, tracer.into_result())
+	}
+
+	/// Contract `caller` calling `callee`, which reverts.
+	fn deploy_nested_call(caller: H160, callee: H160) {
+		// PUSH1 0 (retSize) PUSH1 0 (retOffset) PUSH1 0 (argsSize) PUSH1 0 (argsOffset)
+		// PUSH1 0 (value) PUSH20 callee GAS CALL POP STOP
+		let mut code = vec![
+			0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x73,
+		];
+		code.extend_from_slice(callee.as_bytes());
+		code.extend_from_slice(&[0x5a, 0xf1, 0x50, 0x00]);
+		deploy(caller, code);
+		// PUSH1 0 PUSH1 0 REVERT
+		deploy(callee, vec![0x60, 0x00, 0x60, 0x00, 0xfd]);
+	}
+
+	#[test]
+	fn struct_logger_captures_steps() {
+		new_test_ext().execute_with(|| {
+			// PUSH1 0x2a PUSH1 0x00 SSTORE STOP
+			let contract = H160::from_low_u64_be(0x100);
+			deploy(contract, vec![0x60, 0x2a, 0x60, 0x00, 0x55, 0x00]);
+
+			let (info, result) =
+				trace_call(contract, TracerConfig::StructLogger(Default::default()));
+			let TraceResult::StructLogger(result) = result else {
+				panic!("unexpected trace result");
+			};
+			assert!(!result.failed);
+			assert_eq!(result.gas, info.used_gas.effective.as_u64());
+
+			let logs = result.struct_logs;
+			assert_eq!(
+				logs.iter().map(|log| (log.pc, log.op)).collect::<Vec<_>>(),
+				vec![(0, 0x60), (2, 0x60), (4, 0x55), (5, 0x00)]
+			);
+			assert!(logs.iter().all(|log| log.depth == 1));
+
+			// Each step reports the gas left before it and its own cost.
+			assert_eq!(logs[0].gas_cost, 3);
+			assert_eq!(logs[1].gas, logs[0].gas - 3);
+			assert_eq!(logs[2].gas, logs[1].gas - 3);
+
+			// The stack and the storage are captured as seen by the step.
+			assert_eq!(logs[0].stack, Some(vec![]));
+			assert_eq!(
+				logs[2].stack,
+				Some(vec![H256::from_low_u64_be(0x2a), H256::zero()])
+			);
+			assert_eq!(logs[1].storage, None);
+			assert_eq!(
+				logs[2].storage,
+				Some(BTreeMap::from([(
+					H256::zero(),
+					H256::from_low_u64_be(0x2a)
+				)]))
+			);
+		});
+	}
+
+	#[test]
+	fn struct_logger_respects_config() {
+		new_test_ext().execute_with(|| {
+			let contract = H160::from_low_u64_be(0x100);
+			deploy(contract, vec![0x60, 0x2a, 0x60, 0x00, 0x55, 0x00]);
+
+			let (_, result) = trace_call(
+				contract,
+				TracerConfig::StructLogger(StructLoggerConfig {
+					disable_stack: true,
+					disable_storage: true,
+					limit: 3,
+					..Default::default()
+				}),
+			);
+			let TraceResult::StructLogger(result) = result else {
+				panic!("unexpected trace result");
+			};
+			assert_eq!(result.struct_logs.len(), 3);
+			assert!(result
+				.struct_logs
+				.iter()
+				.all(|log| log.stack.is_none() && log.storage.is_none()));
+		});
+	}
+
+	#[test]
+	fn call_tracer_builds_call_tree() {
+		new_test_ext().execute_with(|| {
+			let caller = H160::from_low_u64_be(0x100);
+			let callee = H160::from_low_u64_be(0x200);
+			deploy_nested_call(caller, callee);
+
+			let (info, result) = trace_call(
+				caller,
+				TracerConfig::CallTracer(CallTracerConfig {
+					only_top_call: false,
+				}),
+			);
+			let TraceResult::CallTracer(root) = result else {
+				panic!("unexpected trace result");
+			};
+			assert_eq!(root.call_type, CallType::Call);
+			assert_eq!(root.from, H160::default());
+			assert_eq!(root.to, Some(caller));
+			assert_eq!(root.value, Some(U256::zero()));
+			assert_eq!(root.gas, GAS_LIMIT);
+			assert_eq!(root.gas_used, info.used_gas.effective.as_u64());
+			assert_eq!(
+				root.exit_reason,
+				Some(ExitReason::Succeed(ExitSucceed::Stopped))
+			);
+
+			assert_eq!(root.calls.len(), 1);
+			let call = &root.calls[0];
+			assert_eq!(call.call_type, CallType::Call);
+			assert_eq!(call.from, caller);
+			assert_eq!(call.to, Some(callee));
+			assert_eq!(call.value, Some(U256::zero()));
+			assert_eq!(
+				call.exit_reason,
+				Some(ExitReason::Revert(ExitRevert::Reverted))
+			);
+			assert!(call.gas > 0 && call.gas_used <= call.gas);
+			assert!(call.calls.is_empty());
+		});
+	}
+
+	#[test]
+	fn call_tracer_only_top_call() {
+		new_test_ext().execute_with(|| {
+			let caller = H160::from_low_u64_be(0x100);
+			let callee = H160::from_low_u64_be(0x200);
+			deploy_nested_call(caller, callee);
+
+			let (_, result) = trace_call(
+				caller,
+				TracerConfig::CallTracer(CallTracerConfig {
+					only_top_call: true,
+				}),
+			);
+			let TraceResult::CallTracer(root) = result else {
+				panic!("unexpected trace result");
+			};
+			assert_eq!(root.to, Some(caller));
+			assert_eq!(
+				root.exit_reason,
+				Some(ExitReason::Succeed(ExitSucceed::Stopped))
+			);
+			assert!(root.calls.is_empty());
+		});
+	}
+}
@@ -22,6 +22,9 @@
 //! WORST CASE MAP SIZE: `1000000`
 //! HOSTNAME: `ubuntu`, CPU: `12th Gen Intel(R) Core(TM) i7-1260P`
 //! EXECUTION: , WASM-EXECUTION: Compiled, CHAIN: Some("dev"), DB CACHE: 1024
+//!
+//! The weights of `schedule_fork`, `cancel_fork` and `on_initialize` are placeholders until the
+//! benchmarks are run again.
 
 // Executed Command:
 // ./target/release/frontier-template-node
@@ -49,6 +52,9 @@ use core::marker::PhantomData;
 /// Weight functions needed for pallet_evm.
 pub trait WeightInfo {
 	fn withdraw() -> Weight;
+	fn schedule_fork() -> Weight;
+	fn cancel_fork() -> Weight;
+	fn on_initialize() -> Weight;
 }
 
 /// Weights for pallet_evm using the Substrate node and recommended hardware.
@@ -61,6 +67,26 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 		// Minimum execution time: 1_564_000 picoseconds.
 		Weight::from_parts(1_696_000, 0)
 	}
+	// Placeholder weight, not produced by a benchmark run: it over-estimates the cost of
+	// accessing `EVM::ForkSchedule` until the `schedule_fork` benchmark is run.
+	fn schedule_fork() -> Weight {
+		Weight::from_parts(50_000_000, 4_000)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	// Placeholder weight, not produced by a benchmark run: it over-estimates the cost of
+	// accessing `EVM::ForkSchedule` until the `cancel_fork` benchmark is run.
+	fn cancel_fork() -> Weight {
+		Weight::from_parts(50_000_000, 4_000)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	// Placeholder weight, not produced by a benchmark run: it over-estimates the cost of
+	// accessing `EVM::ForkSchedule` until the `on_initialize` benchmark is run.
+	fn on_initialize() -> Weight {
+		Weight::from_parts(20_000_000, 4_000)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+	}
 }
 
 // For backwards compatibility and tests
@@ -72,4 +98,24 @@ impl WeightInfo for () {
 		// Minimum execution time: 1_564_000 picoseconds.
 		Weight::from_parts(1_696_000, 0)
 	}
+	// Placeholder weight, not produced by a benchmark run: it over-estimates the cost of
+	// accessing `EVM::ForkSchedule` until the `schedule_fork` benchmark is run.
+	fn schedule_fork() -> Weight {
+		Weight::from_parts(50_000_000, 4_000)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	// Placeholder weight, not produced by a benchmark run: it over-estimates the cost of
+	// accessing `EVM::ForkSchedule` until the `cancel_fork` benchmark is run.
+	fn cancel_fork() -> Weight {
+		Weight::from_parts(50_000_000, 4_000)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	// Placeholder weight, not produced by a benchmark run: it over-estimates the cost of
+	// accessing `EVM::ForkSchedule` until the `on_initialize` benchmark is run.
+	fn on_initialize() -> Weight {
+		Weight::from_parts(20_000_000, 4_000)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+	}
 }
@@ -0,0 +1,45 @@
+// This file is part of Frontier.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use alloc::collections::{BTreeMap, BTreeSet};
+
+use evm::executor::stack::Accessed;
+use sp_core::{H160, H256};
+
+/// Storage keys accessed by the EVM executions, per address.
+pub type AccessedState = BTreeMap<H160, BTreeSet<H256>>;
+
+environmental::environmental!(ACCESSED: AccessedState);
+
+/// Run `f` and return the addresses and storage keys accessed by the EVM executions it ran.
+pub fn using_accessed_recorder<R, F: FnOnce() -> R>(f: F) -> (R, AccessedState) {
+	let mut accessed = AccessedState::new();
+	let result = ACCESSED::using(&mut accessed, f);
+	(result, accessed)
+}
+
+/// Report the addresses and storage keys accessed by an EVM execution to the recorder, if any.
+pub fn record_accessed(accessed: &Accessed) {
+	ACCESSED::with(|recorded| {
+		for address in &accessed.accessed_addresses {
+			recorded.entry(*address).or_default();
+		}
+		for (address, key) in &accessed.accessed_storage {
+			recorded.entry(*address).or_default().insert(*key);
+		}
+	});
+}
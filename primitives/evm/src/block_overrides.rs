@@ -0,0 +1,67 @@
+// This file is part of Frontier.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use alloc::vec::Vec;
+use scale_codec::{Decode, DecodeWithMemTracking, Encode};
+use scale_info::TypeInfo;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use sp_core::{H160, H256, U256};
+
+environmental::environmental!(BLOCK_OVERRIDES: BlockOverrides);
+
+/// Block environment seen by the EVM in place of the current block, used by simulated calls.
+#[derive(
+	Clone,
+	Eq,
+	PartialEq,
+	Default,
+	Debug,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo
+)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BlockOverrides {
+	/// Block number.
+	pub number: Option<U256>,
+	/// Block timestamp, in seconds.
+	pub timestamp: Option<U256>,
+	/// Block author.
+	pub coinbase: Option<H160>,
+	/// Block gas limit.
+	pub gas_limit: Option<U256>,
+	/// Block base fee per gas.
+	pub base_fee: Option<U256>,
+	/// Value returned by `PREVRANDAO`.
+	pub random: Option<H256>,
+	/// Hashes returned by `BLOCKHASH` for blocks unknown to the chain, such as the previous
+	/// blocks of a simulation.
+	#[cfg_attr(feature = "serde", serde(default))]
+	pub block_hashes: Vec<(U256, H256)>,
+}
+
+/// Run `f` with the block environment overridden by `overrides`.
+pub fn using_block_overrides<R, F: FnOnce() -> R>(overrides: &mut BlockOverrides, f: F) -> R {
+	BLOCK_OVERRIDES::using(overrides, f)
+}
+
+/// Read a field of the block overrides in effect, if any.
+pub fn block_override<T, F: FnOnce(&BlockOverrides) -> Option<T>>(f: F) -> Option<T> {
+	BLOCK_OVERRIDES::with(|overrides| f(overrides)).flatten()
+}
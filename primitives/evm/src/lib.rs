@@ -20,9 +20,12 @@
 
 extern crate alloc;
 
+mod accessed;
 mod account_provider;
+mod block_overrides;
 mod precompile;
 mod storage_oog;
+pub mod tracing;
 mod validation;
 
 use alloc::{collections::BTreeMap, vec::Vec};
@@ -40,7 +43,9 @@ pub use evm::{
 };
 
 pub use self::{
+	accessed::{record_accessed, using_accessed_recorder, AccessedState},
 	account_provider::AccountProvider,
+	block_overrides::{block_override, using_block_overrides, BlockOverrides},
 	precompile::{
 		Context, ExitError, ExitRevert, ExitSucceed, IsPrecompileResult, LinearCostPrecompile,
 		Precompile, PrecompileFailure, PrecompileHandle, PrecompileOutput, PrecompileResult,
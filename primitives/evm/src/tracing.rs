@@ -0,0 +1,198 @@
+// This file is part of Frontier.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Types shared between the runtime tracer and the client-side debug RPC.
+
+use alloc::{collections::BTreeMap, vec::Vec};
+use scale_codec::{Decode, DecodeWithMemTracking, Encode};
+use scale_info::TypeInfo;
+use sp_core::{H160, H256, U256};
+
+use crate::ExitReason;
+
+/// The tracer to run while replaying an EVM execution.
+#[derive(
+	Clone,
+	Eq,
+	PartialEq,
+	Debug,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo
+)]
+pub enum TracerConfig {
+	/// Opcode level tracer, equivalent to geth's default struct logger.
+	StructLogger(StructLoggerConfig),
+	/// Call tree tracer, equivalent to geth's `callTracer`.
+	CallTracer(CallTracerConfig),
+	/// Touched accounts tracer, equivalent to geth's `prestateTracer`.
+	PrestateTracer(PrestateTracerConfig),
+}
+
+/// Options of the struct logger.
+#[derive(
+	Clone,
+	Eq,
+	PartialEq,
+	Default,
+	Debug,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo
+)]
+pub struct StructLoggerConfig {
+	/// Do not capture the stack.
+	pub disable_stack: bool,
+	/// Do not capture the memory.
+	pub disable_memory: bool,
+	/// Do not capture the storage.
+	pub disable_storage: bool,
+	/// Capture the return data.
+	pub enable_return_data: bool,
+	/// Maximum number of steps to capture, `0` means no limit.
+	pub limit: u64,
+}
+
+/// Options of the call tracer.
+#[derive(
+	Clone,
+	Eq,
+	PartialEq,
+	Default,
+	Debug,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo
+)]
+pub struct CallTracerConfig {
+	/// Only trace the top-level call frame.
+	pub only_top_call: bool,
+}
+
+/// Options of the prestate tracer.
+#[derive(
+	Clone,
+	Eq,
+	PartialEq,
+	Default,
+	Debug,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo
+)]
+pub struct PrestateTracerConfig {
+	/// Return the accounts modified by the execution, before and after it, instead of the
+	/// state of all the touched accounts.
+	pub diff_mode: bool,
+}
+
+/// Result of a traced EVM execution.
+#[derive(Clone, Eq, PartialEq, Debug, Encode, Decode, TypeInfo)]
+pub enum TraceResult {
+	StructLogger(StructLoggerResult),
+	CallTracer(CallFrame),
+	PrestateTracer(PrestateResult),
+}
+
+/// Output of the struct logger.
+#[derive(Clone, Eq, PartialEq, Default, Debug, Encode, Decode, TypeInfo)]
+pub struct StructLoggerResult {
+	/// Gas used by the whole transaction.
+	pub gas: u64,
+	/// Whether the transaction failed.
+	pub failed: bool,
+	/// Data returned by the top-level call frame.
+	pub return_value: Vec<u8>,
+	/// Captured steps.
+	pub struct_logs: Vec<StructLog>,
+}
+
+/// A single opcode step captured by the struct logger.
+#[derive(Clone, Eq, PartialEq, Default, Debug, Encode, Decode, TypeInfo)]
+pub struct StructLog {
+	/// Program counter.
+	pub pc: u64,
+	/// Raw opcode.
+	pub op: u8,
+	/// Remaining gas before executing the opcode.
+	pub gas: u64,
+	/// Gas charged for the opcode.
+	pub gas_cost: u64,
+	/// Call depth, starting at 1.
+	pub depth: u32,
+	/// Stack before executing the opcode.
+	pub stack: Option<Vec<H256>>,
+	/// Memory before executing the opcode.
+	pub memory: Option<Vec<u8>>,
+	/// Storage of the executing contract accessed so far, captured on `SLOAD` and `SSTORE`.
+	pub storage: Option<BTreeMap<H256, H256>>,
+	/// Return data of the last call frame that exited.
+	pub return_data: Option<Vec<u8>>,
+}
+
+/// Kind of a call frame.
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Encode, Decode, TypeInfo)]
+pub enum CallType {
+	Call,
+	CallCode,
+	DelegateCall,
+	StaticCall,
+	Create,
+	Create2,
+	SelfDestruct,
+}
+
+/// A call frame captured by the call tracer.
+#[derive(Clone, Eq, PartialEq, Debug, Encode, Decode, TypeInfo)]
+pub struct CallFrame {
+	pub call_type: CallType,
+	pub from: H160,
+	pub to: Option<H160>,
+	pub value: Option<U256>,
+	pub gas: u64,
+	pub gas_used: u64,
+	pub input: Vec<u8>,
+	pub output: Vec<u8>,
+	/// `None` while the frame has not exited.
+	pub exit_reason: Option<ExitReason>,
+	pub calls: Vec<CallFrame>,
+}
+
+/// Output of the prestate tracer.
+#[derive(Clone, Eq, PartialEq, Debug, Encode, Decode, TypeInfo)]
+pub enum PrestateResult {
+	/// State of all the touched accounts before the execution.
+	Prestate(BTreeMap<H160, AccountState>),
+	/// Modified accounts, before and after the execution.
+	Diff {
+		pre: BTreeMap<H160, AccountState>,
+		post: BTreeMap<H160, AccountState>,
+	},
+}
+
+/// State of an account, fields left out are either empty or unchanged.
+#[derive(Clone, Eq, PartialEq, Default, Debug, Encode, Decode, TypeInfo)]
+pub struct AccountState {
+	pub balance: Option<U256>,
+	pub nonce: Option<U256>,
+	pub code: Option<Vec<u8>>,
+	pub storage: BTreeMap<H256, H256>,
+}
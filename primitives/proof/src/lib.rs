@@ -0,0 +1,245 @@
+// This file is part of Frontier.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Verification of the EVM state proofs returned by `eth_getProof`.
+//!
+//! Frontier keeps the EVM state in the Substrate state trie, so the proofs are Substrate read
+//! proofs of the storage entries backing an account, checked against the block's state root
+//! rather than Ethereum Merkle-Patricia proofs.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![warn(unused_crate_dependencies)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+use hash_db::{HashDB, Hasher, EMPTY_PREFIX};
+use scale_codec::Decode;
+use sp_core::{H160, H256};
+use sp_trie::{read_trie_value, LayoutV1, MemoryDB, StorageProof};
+
+/// Storage keys of the entries backing an EVM account.
+pub mod keys {
+	use alloc::vec::Vec;
+
+	use sp_core::{
+		hashing::{blake2_128, twox_128},
+		H160, H256,
+	};
+	// Frontier
+	use fp_storage::constants::*;
+
+	fn storage_prefix(module: &[u8], storage: &[u8]) -> Vec<u8> {
+		[twox_128(module), twox_128(storage)].concat().to_vec()
+	}
+
+	fn blake2_128_concat(bytes: &[u8]) -> Vec<u8> {
+		let mut key = blake2_128(bytes).to_vec();
+		key.extend_from_slice(bytes);
+		key
+	}
+
+	/// Key of `System::Account`, holding the nonce and the balance of the account.
+	///
+	/// `account_id` is the encoded account id the address maps to in the runtime.
+	pub fn system_account(account_id: &[u8]) -> Vec<u8> {
+		let mut key = storage_prefix(PALLET_SYSTEM, SYSTEM_ACCOUNT);
+		key.extend(blake2_128_concat(account_id));
+		key
+	}
+
+	/// Key of `EVM::AccountCodes`, holding the code of the account.
+	pub fn account_code(address: H160) -> Vec<u8> {
+		let mut key = storage_prefix(PALLET_EVM, EVM_ACCOUNT_CODES);
+		key.extend(blake2_128_concat(address.as_bytes()));
+		key
+	}
+
+	/// Key of `EVM::AccountStorages`, holding the storage slot `index` of the account.
+	pub fn account_storage(address: H160, index: H256) -> Vec<u8> {
+		let mut key = storage_prefix(PALLET_EVM, EVM_ACCOUNT_STORAGES);
+		key.extend(blake2_128_concat(address.as_bytes()));
+		key.extend(blake2_128_concat(index.as_bytes()));
+		key
+	}
+}
+
+/// Errors of the proof verification.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Error {
+	/// The proof does not contain the state root.
+	StorageRootMismatch,
+	/// The proof does not contain the trie nodes leading to the requested key.
+	StorageValueUnavailable,
+	/// The proven value could not be decoded.
+	StorageValueDecodeFailed,
+}
+
+/// Reads the storage values proven by a Substrate read proof.
+pub struct StorageProofChecker<H: Hasher> {
+	root: H::Out,
+	db: MemoryDB<H>,
+}
+
+impl<H: Hasher> StorageProofChecker<H> {
+	/// Builds a checker from the trie nodes of a proof, checked against `root`.
+	pub fn new(root: H::Out, proof: Vec<Vec<u8>>) -> Result<Self, Error> {
+		let db = StorageProof::new(proof).into_memory_db::<H>();
+		if !db.contains(&root, EMPTY_PREFIX) {
+			return Err(Error::StorageRootMismatch);
+		}
+		Ok(Self { root, db })
+	}
+
+	/// Raw value stored under `key`, `None` when the proof shows that there is none.
+	pub fn read_value(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+		read_trie_value::<LayoutV1<H>, _>(&self.db, &self.root, key, None, None)
+			.map_err(|_| Error::StorageValueUnavailable)
+	}
+
+	/// Decoded value stored under `key`, `None` when the proof shows that there is none.
+	pub fn read_and_decode_value<T: Decode>(&self, key: &[u8]) -> Result<Option<T>, Error> {
+		self.read_value(key)?
+			.map(|value| T::decode(&mut &value[..]).map_err(|_| Error::StorageValueDecodeFailed))
+			.transpose()
+	}
+
+	/// Nonce and balance of the account stored under `account_id` in `System::Account`.
+	///
+	/// `Nonce` and `Balance` are the runtime types of `frame_system::AccountInfo` and of the free
+	/// balance of its account data.
+	pub fn account_basic<Nonce, Balance>(
+		&self,
+		account_id: &[u8],
+	) -> Result<(Nonce, Balance), Error>
+	where
+		Nonce: Decode + Default,
+		Balance: Decode + Default,
+	{
+		// `AccountInfo { nonce, consumers, providers, sufficients, data: AccountData { free, .. } }`
+		let info = self.read_and_decode_value::<(Nonce, u32, u32, u32, Balance)>(
+			&keys::system_account(account_id),
+		)?;
+		Ok(info
+			.map(|(nonce, _, _, _, balance)| (nonce, balance))
+			.unwrap_or_default())
+	}
+
+	/// Code of the account, empty when there is none.
+	pub fn account_code(&self, address: H160) -> Result<Vec<u8>, Error> {
+		Ok(self
+			.read_and_decode_value::<Vec<u8>>(&keys::account_code(address))?
+			.unwrap_or_default())
+	}
+
+	/// Storage slot `index` of the account, zero when it is unset.
+	pub fn account_storage(&self, address: H160, index: H256) -> Result<H256, Error> {
+		Ok(self
+			.read_and_decode_value::<H256>(&keys::account_storage(address, index))?
+			.unwrap_or_default())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use scale_codec::Encode;
+	use sp_runtime::traits::BlakeTwo256;
+	use sp_trie::{TrieDBMutBuilder, TrieMut};
+
+	/// Builds a state trie with the given entries and returns its root along with a proof
+	/// containing all its nodes.
+	fn state(entries: Vec<(Vec<u8>, Vec<u8>)>) -> (H256, Vec<Vec<u8>>) {
+		let mut db = MemoryDB::<BlakeTwo256>::default();
+		let mut root = H256::zero();
+		{
+			let mut trie =
+				TrieDBMutBuilder::<LayoutV1<BlakeTwo256>>::new(&mut db, &mut root).build();
+			for (key, value) in entries {
+				trie.insert(&key, &value).unwrap();
+			}
+		}
+		let proof = db
+			.drain()
+			.into_values()
+			.filter(|(_, rc)| *rc > 0)
+			.map(|(node, _)| node)
+			.collect();
+		(root, proof)
+	}
+
+	#[test]
+	fn reads_proven_account() {
+		let address = H160::repeat_byte(0xaa);
+		let account_id = address.as_bytes().to_vec();
+		let slot = H256::from_low_u64_be(1);
+		let (root, proof) = state(vec![
+			(
+				keys::system_account(&account_id),
+				(3u32, 0u32, 1u32, 0u32, 1_000u128, 0u128, 0u128, 0u128).encode(),
+			),
+			(keys::account_code(address), vec![0x60, 0x00].encode()),
+			(
+				keys::account_storage(address, slot),
+				H256::repeat_byte(7).encode(),
+			),
+		]);
+
+		let checker = StorageProofChecker::<BlakeTwo256>::new(root, proof).unwrap();
+		assert_eq!(
+			checker.account_basic::<u32, u128>(&account_id),
+			Ok((3, 1_000))
+		);
+		assert_eq!(checker.account_code(address), Ok(vec![0x60, 0x00]));
+		assert_eq!(
+			checker.account_storage(address, slot),
+			Ok(H256::repeat_byte(7))
+		);
+		assert_eq!(
+			checker.account_storage(address, H256::zero()),
+			Ok(H256::zero())
+		);
+	}
+
+	#[test]
+	fn rejects_proof_of_another_root() {
+		let (_, proof) = state(vec![(b"key".to_vec(), b"value".to_vec())]);
+		assert_eq!(
+			StorageProofChecker::<BlakeTwo256>::new(H256::repeat_byte(1), proof).err(),
+			Some(Error::StorageRootMismatch)
+		);
+	}
+
+	#[test]
+	fn missing_nodes_are_unavailable() {
+		let (root, proof) = state(vec![
+			(keys::account_code(H160::repeat_byte(1)), vec![1u8].encode()),
+			(keys::account_code(H160::repeat_byte(2)), vec![2u8].encode()),
+		]);
+		// Only keep the root node.
+		let root_node = proof
+			.into_iter()
+			.find(|node| BlakeTwo256::hash(node) == root)
+			.unwrap();
+		let checker = StorageProofChecker::<BlakeTwo256>::new(root, vec![root_node]).unwrap();
+		assert_eq!(
+			checker.account_code(H160::repeat_byte(1)),
+			Err(Error::StorageValueUnavailable)
+		);
+	}
+}
@@ -30,7 +30,7 @@ use scale_info::TypeInfo;
 use sp_core::{H256, U256};
 use sp_runtime::{
 	traits::{Block as BlockT, HashingFor},
-	Permill, RuntimeDebug,
+	DispatchError, Permill, RuntimeDebug,
 };
 use sp_state_machine::OverlayedChanges;
 
@@ -94,7 +94,7 @@ impl<B: BlockT, C> RuntimeStorageOverride<B, C> for () {
 
 sp_api::decl_runtime_apis! {
 	/// API necessary for Ethereum-compatibility layer.
-	#[api_version(6)]
+	#[api_version(9)]
 	pub trait EthereumRuntimeRPCApi {
 		/// Returns runtime defined pallet_evm::ChainId.
 		fn chain_id() -> u64;
@@ -164,6 +164,20 @@ sp_api::decl_runtime_apis! {
 			estimate: bool,
 			access_list: Option<Vec<(Address, Vec<H256>)>>,
 		) -> Result<fp_evm::ExecutionInfoV2::<Vec<u8>>, sp_runtime::DispatchError>;
+		#[changed_in(9)]
+		fn call(
+			from: Address,
+			to: Address,
+			data: Vec<u8>,
+			value: U256,
+			gas_limit: U256,
+			max_fee_per_gas: Option<U256>,
+			max_priority_fee_per_gas: Option<U256>,
+			nonce: Option<U256>,
+			estimate: bool,
+			access_list: Option<Vec<(Address, Vec<H256>)>>,
+			authorization_list: Option<AuthorizationList>,
+		) -> Result<fp_evm::ExecutionInfoV2::<Vec<u8>>, sp_runtime::DispatchError>;
 		#[allow(clippy::type_complexity)]
 		fn call(
 			from: Address,
@@ -177,6 +191,7 @@ sp_api::decl_runtime_apis! {
 			estimate: bool,
 			access_list: Option<Vec<(Address, Vec<H256>)>>,
 			authorization_list: Option<AuthorizationList>,
+			block_overrides: Option<fp_evm::BlockOverrides>,
 		) -> Result<fp_evm::ExecutionInfoV2::<Vec<u8>>, sp_runtime::DispatchError>;
 
 		/// Returns a frame_ethereum::create response.
@@ -225,6 +240,19 @@ sp_api::decl_runtime_apis! {
 			estimate: bool,
 			access_list: Option<Vec<(Address, Vec<H256>)>>,
 		) -> Result<fp_evm::ExecutionInfoV2::<Address>, sp_runtime::DispatchError>;
+		#[changed_in(9)]
+		fn create(
+			from: Address,
+			data: Vec<u8>,
+			value: U256,
+			gas_limit: U256,
+			max_fee_per_gas: Option<U256>,
+			max_priority_fee_per_gas: Option<U256>,
+			nonce: Option<U256>,
+			estimate: bool,
+			access_list: Option<Vec<(Address, Vec<H256>)>>,
+			authorization_list: Option<AuthorizationList>,
+		) -> Result<fp_evm::ExecutionInfoV2::<Address>, sp_runtime::DispatchError>;
 		#[allow(clippy::type_complexity)]
 		fn create(
 			from: Address,
@@ -237,6 +265,7 @@ sp_api::decl_runtime_apis! {
 			estimate: bool,
 			access_list: Option<Vec<(Address, Vec<H256>)>>,
 			authorization_list: Option<AuthorizationList>,
+			block_overrides: Option<fp_evm::BlockOverrides>,
 		) -> Result<fp_evm::ExecutionInfoV2::<Address>, sp_runtime::DispatchError>;
 
 		/// Return the current block. Legacy.
@@ -301,6 +330,85 @@ sp_api::decl_runtime_apis! {
 		/// If your project don't need to have a different behavior to initialize "pending" blocks,
 		/// you can copy your Core_initialize_block implementation.
 		fn initialize_pending_block(header: &<Block as BlockT>::Header);
+
+		/// Executes a call, or a create if `to` is `None`, and returns its execution info along
+		/// with the addresses and storage keys it accessed. As with geth, the sender, the receiver
+		/// and the precompiles are left out as they are always warm.
+		#[api_version(7)]
+		#[allow(clippy::type_complexity)]
+		fn create_access_list(
+			from: Address,
+			to: Option<Address>,
+			data: Vec<u8>,
+			value: U256,
+			gas_limit: U256,
+			max_fee_per_gas: Option<U256>,
+			max_priority_fee_per_gas: Option<U256>,
+			nonce: Option<U256>,
+			access_list: Option<Vec<(Address, Vec<H256>)>>,
+			authorization_list: Option<AuthorizationList>,
+		) -> Result<
+			(fp_evm::ExecutionInfoV2::<Vec<u8>>, Vec<(Address, Vec<H256>)>),
+			sp_runtime::DispatchError,
+		>;
+
+		/// Executes a call, or a create if `to` is `None`, in the block environment overridden by
+		/// `block_overrides`, and returns its execution info. For creates the returned value is
+		/// the deployed code. When `validate` is set, the nonce and the fees are checked as for a
+		/// transaction.
+		#[api_version(8)]
+		fn simulate_call(
+			from: Address,
+			to: Option<Address>,
+			data: Vec<u8>,
+			value: U256,
+			gas_limit: U256,
+			max_fee_per_gas: Option<U256>,
+			max_priority_fee_per_gas: Option<U256>,
+			nonce: Option<U256>,
+			access_list: Option<Vec<(Address, Vec<H256>)>>,
+			authorization_list: Option<AuthorizationList>,
+			block_overrides: Option<fp_evm::BlockOverrides>,
+			validate: bool,
+		) -> Result<fp_evm::ExecutionInfoV2::<Vec<u8>>, sp_runtime::DispatchError>;
+	}
+
+	/// API replaying Ethereum transactions with a tracer installed.
+	pub trait DebugRuntimeApi {
+		/// Replay the extrinsics of a block on top of its parent state, up to and including the
+		/// Ethereum transaction `transaction_hash`, and return the trace of that transaction.
+		///
+		/// The block must have been initialized beforehand.
+		fn trace_transaction(
+			xts: Vec<<Block as BlockT>::Extrinsic>,
+			transaction_hash: H256,
+			config: fp_evm::tracing::TracerConfig,
+		) -> Result<fp_evm::tracing::TraceResult, DispatchError>;
+
+		/// Replay all the extrinsics of a block on top of its parent state and return the trace
+		/// of each Ethereum transaction, in the order they appear in the block.
+		///
+		/// The block must have been initialized beforehand.
+		fn trace_block(
+			xts: Vec<<Block as BlockT>::Extrinsic>,
+			config: fp_evm::tracing::TracerConfig,
+		) -> Result<Vec<fp_evm::tracing::TraceResult>, DispatchError>;
+
+		/// Execute a call or a contract creation, when `to` is `None`, and return its trace.
+		fn trace_call(
+			from: Address,
+			to: Option<Address>,
+			data: Vec<u8>,
+			value: U256,
+			gas_limit: U256,
+			max_fee_per_gas: Option<U256>,
+			max_priority_fee_per_gas: Option<U256>,
+			nonce: Option<U256>,
+			access_list: Option<Vec<(Address, Vec<H256>)>>,
+			authorization_list: Option<AuthorizationList>,
+			block_overrides: Option<fp_evm::BlockOverrides>,
+			config: fp_evm::tracing::TracerConfig,
+		) -> Result<fp_evm::tracing::TraceResult, DispatchError>;
 	}
 
 	#[api_version(2)]
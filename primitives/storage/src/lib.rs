@@ -22,6 +22,10 @@ use scale_codec::{Decode, Encode};
 
 /// Some storage constants
 pub mod constants {
+	/// Pallet System storage items
+	pub const PALLET_SYSTEM: &[u8] = b"System";
+	pub const SYSTEM_ACCOUNT: &[u8] = b"Account";
+
 	/// Pallet Evm storage items
 	pub const PALLET_EVM: &[u8] = b"EVM";
 	pub const EVM_ACCOUNT_CODES: &[u8] = b"AccountCodes";
@@ -136,6 +136,12 @@ pub fn run() -> sc_cli::Result<()> {
 							}
 						};
 					}
+					#[cfg(feature = "postgres")]
+					crate::eth::BackendType::Postgres => {
+						eprintln!(
+							"The Postgres frontier database is not purged, drop it manually."
+						);
+					}
 				};
 				cmd.run(config.database)
 			})
@@ -5,7 +5,7 @@ use jsonrpsee::RpcModule;
 use sc_client_api::{
 	backend::{Backend, StorageProvider},
 	client::BlockchainEvents,
-	AuxStore, UsageProvider,
+	AuxStore, BlockBackend, ProofProvider, UsageProvider,
 };
 use sc_network::service::traits::NetworkService;
 use sc_network_sync::SyncingService;
@@ -19,7 +19,7 @@ use sp_core::H256;
 use sp_inherents::CreateInherentDataProviders;
 use sp_runtime::traits::Block as BlockT;
 // Frontier
-pub use fc_rpc::{EthBlockDataCacheTask, EthConfig};
+pub use fc_rpc::{EthBlockDataCacheTask, EthConfig, EthKeystoreSigner, RemoteSignerConfig};
 pub use fc_rpc_core::types::{FeeHistoryCache, FeeHistoryCacheLimit, FilterPool};
 use fc_storage::StorageOverride;
 use fp_rpc::{ConvertTransaction, ConvertTransactionRuntimeApi, EthereumRuntimeRPCApi};
@@ -36,6 +36,12 @@ pub struct EthDeps<B: BlockT, C, P, CT, CIDP> {
 	pub is_authority: bool,
 	/// Whether to enable dev signer
 	pub enable_dev_signer: bool,
+	/// Whether to serve the v2 Ethereum RPC API instead of the v1 API.
+	pub eth_rpc_v2: bool,
+	/// Encrypted JSON keystore signer, also served by the `personal` RPC.
+	pub keystore: Option<EthKeystoreSigner>,
+	/// External signing service to delegate signing to.
+	pub remote_signer: Option<RemoteSignerConfig>,
 	/// Network service
 	pub network: Arc<dyn NetworkService>,
 	/// Chain syncing service
@@ -84,7 +90,13 @@ where
 		+ ConvertTransactionRuntimeApi<B>
 		+ EthereumRuntimeRPCApi<B>,
 	C: HeaderBackend<B> + HeaderMetadata<B, Error = BlockChainError>,
-	C: BlockchainEvents<B> + AuxStore + UsageProvider<B> + StorageProvider<B, BE> + 'static,
+	C: BlockchainEvents<B>
+		+ BlockBackend<B>
+		+ AuxStore
+		+ ProofProvider<B>
+		+ UsageProvider<B>
+		+ StorageProvider<B, BE>
+		+ 'static,
 	BE: Backend<B> + 'static,
 	P: TransactionPool<Block = B, Hash = B::Hash> + 'static,
 	CT: ConvertTransaction<<B as BlockT>::Extrinsic> + Send + Sync + 'static,
@@ -93,8 +105,8 @@ where
 {
 	use fc_rpc::{
 		pending::AuraConsensusDataProvider, Debug, DebugApiServer, Eth, EthApiServer, EthDevSigner,
-		EthFilter, EthFilterApiServer, EthPubSub, EthPubSubApiServer, EthSigner, Net, NetApiServer,
-		Web3, Web3ApiServer,
+		EthFilter, EthFilterApiServer, EthPubSub, EthPubSubApiServer, EthRemoteSigner, EthSigner,
+		Net, NetApiServer, Personal, PersonalApiServer, Trace, TraceApiServer, Web3, Web3ApiServer,
 	};
 	#[cfg(feature = "txpool")]
 	use fc_rpc::{TxPool, TxPoolApiServer};
@@ -105,6 +117,9 @@ where
 		converter,
 		is_authority,
 		enable_dev_signer,
+		eth_rpc_v2,
+		keystore,
+		remote_signer,
 		network,
 		sync,
 		frontier_backend,
@@ -124,81 +139,130 @@ where
 	if enable_dev_signer {
 		signers.push(Box::new(EthDevSigner::new()) as Box<dyn EthSigner>);
 	}
+	if let Some(keystore) = keystore {
+		signers.push(Box::new(keystore.clone()) as Box<dyn EthSigner>);
+		io.merge(Personal::new(keystore).into_rpc())?;
+	}
+	if let Some(config) = remote_signer {
+		signers.push(Box::new(EthRemoteSigner::new(config)?) as Box<dyn EthSigner>);
+	}
 
-	io.merge(
-		Eth::<B, C, P, CT, BE, CIDP, EC>::new(
+	let eth = Eth::<B, C, P, CT, BE, CIDP, EC>::new(
+		client.clone(),
+		pool.clone(),
+		converter,
+		sync.clone(),
+		signers,
+		storage_override.clone(),
+		frontier_backend.clone(),
+		is_authority,
+		block_data_cache.clone(),
+		fee_history_cache,
+		fee_history_cache_limit,
+		execute_gas_limit_multiplier,
+		forced_parent_hashes,
+		pending_create_inherent_data_providers,
+		Some(Box::new(AuraConsensusDataProvider::new(client.clone()))),
+	)
+	.replace_config::<EC>();
+	if eth_rpc_v2 {
+		io.merge(fc_rpc_v2::Eth::new(eth).into_rpc_module()?)?;
+	} else {
+		io.merge(eth.into_rpc())?;
+	}
+
+	if let Some(filter_pool) = filter_pool {
+		let filter = EthFilter::new(
 			client.clone(),
-			pool.clone(),
-			converter,
-			sync.clone(),
-			signers,
-			storage_override.clone(),
 			frontier_backend.clone(),
-			is_authority,
+			pool.clone(),
+			filter_pool,
+			500_usize, // max stored filters
+			max_past_logs,
+			max_block_range,
 			block_data_cache.clone(),
-			fee_history_cache,
-			fee_history_cache_limit,
-			execute_gas_limit_multiplier,
-			forced_parent_hashes,
-			pending_create_inherent_data_providers,
-			Some(Box::new(AuraConsensusDataProvider::new(client.clone()))),
-		)
-		.replace_config::<EC>()
-		.into_rpc(),
-	)?;
+		);
+		if eth_rpc_v2 {
+			io.merge(fc_rpc_v2::EthFilterApiServer::into_rpc(
+				fc_rpc_v2::EthFilter::new(filter),
+			))?;
+		} else {
+			io.merge(filter.into_rpc())?;
+		}
+	}
 
-	if let Some(filter_pool) = filter_pool {
-		io.merge(
-			EthFilter::new(
-				client.clone(),
-				frontier_backend.clone(),
-				pool.clone(),
-				filter_pool,
-				500_usize, // max stored filters
-				max_past_logs,
-				max_block_range,
-				block_data_cache.clone(),
-			)
-			.into_rpc(),
-		)?;
+	let pubsub = EthPubSub::new(
+		pool.clone(),
+		client.clone(),
+		sync,
+		subscription_task_executor,
+		storage_override.clone(),
+		pubsub_notification_sinks,
+	);
+	if eth_rpc_v2 {
+		io.merge(fc_rpc_v2::EthPubSubApiServer::into_rpc(
+			fc_rpc_v2::EthPubSub::new(pubsub),
+		))?;
+	} else {
+		io.merge(pubsub.into_rpc())?;
 	}
 
-	io.merge(
-		EthPubSub::new(
-			pool.clone(),
-			client.clone(),
-			sync,
-			subscription_task_executor,
-			storage_override.clone(),
-			pubsub_notification_sinks,
-		)
-		.into_rpc(),
-	)?;
+	let net = Net::new(
+		client.clone(),
+		network,
+		// Whether to format the `peer_count` response as Hex (default) or not.
+		true,
+	);
+	if eth_rpc_v2 {
+		io.merge(fc_rpc_v2::NetApiServer::into_rpc(fc_rpc_v2::Net::new(net)))?;
+	} else {
+		io.merge(net.into_rpc())?;
+	}
 
-	io.merge(
-		Net::new(
-			client.clone(),
-			network,
-			// Whether to format the `peer_count` response as Hex (default) or not.
-			true,
-		)
-		.into_rpc(),
-	)?;
+	let web3 = Web3::new(client.clone());
+	if eth_rpc_v2 {
+		io.merge(fc_rpc_v2::Web3ApiServer::into_rpc(fc_rpc_v2::Web3::new(
+			web3,
+		)))?;
+	} else {
+		io.merge(web3.into_rpc())?;
+	}
 
-	io.merge(Web3::new(client.clone()).into_rpc())?;
+	let debug = Debug::<B, C, BE, EC>::new(
+		client.clone(),
+		frontier_backend.clone(),
+		storage_override,
+		block_data_cache.clone(),
+	);
+	if eth_rpc_v2 {
+		io.merge(fc_rpc_v2::DebugApiServer::into_rpc(fc_rpc_v2::Debug::new(
+			debug,
+		)))?;
+	} else {
+		io.merge(debug.into_rpc())?;
+	}
 
 	io.merge(
-		Debug::new(
+		Trace::new(
 			client.clone(),
 			frontier_backend,
-			storage_override,
 			block_data_cache,
+			max_block_range,
 		)
 		.into_rpc(),
 	)?;
 
 	#[cfg(feature = "txpool")]
-	io.merge(TxPool::new(client, pool).into_rpc())?;
+	{
+		let txpool = TxPool::new(client, pool);
+		if eth_rpc_v2 {
+			io.merge(fc_rpc_v2::TxPoolApiServer::into_rpc(
+				fc_rpc_v2::TxPool::new(txpool),
+			))?;
+		} else {
+			io.merge(txpool.into_rpc())?;
+		}
+	}
 
 	Ok(io)
 }
@@ -29,8 +29,8 @@ use crate::{
 	client::{BaseRuntimeApiCollection, FullBackend, FullClient, RuntimeApiCollection},
 	eth::{
 		new_frontier_partial, spawn_frontier_tasks, BackendType, EthCompatRuntimeApiCollection,
-		FrontierBackend, FrontierBlockImport, FrontierPartialComponents, StorageOverride,
-		StorageOverrideHandler,
+		FrontierBackend, FrontierBlockImport, FrontierPartialComponents, RemoteSignerType,
+		StorageOverride, StorageOverrideHandler,
 	},
 };
 
@@ -140,6 +140,7 @@ where
 			Arc::clone(&client),
 			&config.database,
 			&db_config_dir(config),
+			eth_config.frontier_kv_log_index,
 		)?)),
 		BackendType::Sql => {
 			let db_path = db_config_dir(config).join("sql");
@@ -162,6 +163,31 @@ where
 			.unwrap_or_else(|err| panic!("failed creating sql backend: {err:?}"));
 			FrontierBackend::Sql(Arc::new(backend))
 		}
+		#[cfg(feature = "postgres")]
+		BackendType::Postgres => {
+			let url = eth_config
+				.frontier_sql_backend_url
+				.as_deref()
+				.ok_or_else(|| {
+					ServiceError::Other("missing `--frontier-sql-backend-url`".to_string())
+				})?;
+			let backend = futures::executor::block_on(fc_db::sql::Backend::new(
+				fc_db::sql::BackendConfig::Postgres(fc_db::sql::PostgresBackendConfig {
+					url,
+					statement_timeout: (eth_config.frontier_sql_backend_statement_timeout != 0)
+						.then(|| {
+							std::time::Duration::from_millis(
+								eth_config.frontier_sql_backend_statement_timeout,
+							)
+						}),
+				}),
+				eth_config.frontier_sql_backend_pool_size,
+				None,
+				storage_override.clone(),
+			))
+			.unwrap_or_else(|err| panic!("failed creating sql backend: {err:?}"));
+			FrontierBackend::Sql(Arc::new(backend))
+		}
 	};
 
 	let (import_queue, block_import) = build_import_queue(
@@ -423,6 +449,28 @@ where
 
 		let is_authority = role.is_authority();
 		let enable_dev_signer = eth_config.enable_dev_signer;
+		let eth_rpc_v2 = eth_config.eth_rpc_v2;
+		let keystore = eth_config
+			.eth_keystore_path
+			.as_ref()
+			.map(|path| fc_rpc::EthKeystoreSigner::open(path, false))
+			.transpose()
+			.map_err(|err| {
+				ServiceError::Other(format!("Failed to open Ethereum keystore: {err}"))
+			})?;
+		let remote_signer =
+			eth_config
+				.eth_remote_signer_url
+				.clone()
+				.map(|endpoint| fc_rpc::RemoteSignerConfig {
+					endpoint,
+					api: match eth_config.eth_remote_signer_type {
+						RemoteSignerType::Web3Signer => fc_rpc::RemoteSignerApi::Web3Signer,
+						RemoteSignerType::Clef => fc_rpc::RemoteSignerApi::Clef,
+					},
+					timeout: Duration::from_secs(eth_config.eth_remote_signer_timeout),
+					accounts_cache_duration: Duration::from_secs(60),
+				});
 		let max_past_logs = eth_config.max_past_logs;
 		let max_block_range = eth_config.max_block_range;
 		let execute_gas_limit_multiplier = eth_config.execute_gas_limit_multiplier;
@@ -460,6 +508,9 @@ where
 				converter: Some(TransactionConverter::<B>::default()),
 				is_authority,
 				enable_dev_signer,
+				eth_rpc_v2,
+				keystore: keystore.clone(),
+				remote_signer: remote_signer.clone(),
 				network: network.clone(),
 				sync: sync_service.clone(),
 				frontier_backend: match &*frontier_backend {
@@ -522,6 +573,7 @@ where
 		fee_history_cache_limit,
 		sync_service.clone(),
 		pubsub_notification_sinks,
+		eth_config.frontier_pruning,
 	)
 	.await;
 
@@ -46,7 +46,9 @@ use frame_support::{
 	derive_impl,
 	genesis_builder_helper::build_state,
 	parameter_types,
-	traits::{ConstBool, ConstU32, ConstU64, ConstU8, FindAuthor, OnFinalize, OnTimestampSet},
+	traits::{
+		ConstBool, ConstU32, ConstU64, ConstU8, FindAuthor, GetDefault, OnFinalize, OnTimestampSet,
+	},
 	weights::{constants::WEIGHT_REF_TIME_PER_MILLIS, IdentityFee, Weight},
 };
 use pallet_transaction_payment::FungibleAdapter;
@@ -56,7 +58,10 @@ use sp_genesis_builder::PresetId;
 use fp_account::EthereumSignature;
 use fp_evm::weight_per_gas;
 use fp_rpc::TransactionStatus;
-use pallet_ethereum::{Call::transact, PostLogContent, Transaction as EthereumTransaction};
+use pallet_ethereum::{
+	Call::{transact, transact_batch},
+	PostLogContent, Transaction as EthereumTransaction,
+};
 use pallet_evm::{
 	Account as EVMAccount, EnsureAccountId20, FeeCalculator, IdentityAddressMapping, Runner,
 };
@@ -248,6 +253,10 @@ impl frame_system::Config for Runtime {
 	/// This is used as an identifier of the chain. 42 is the generic substrate prefix.
 	type SS58Prefix = SS58Prefix;
 	type MaxConsumers = ConstU32<16>;
+	/// Hooks run once the inherents of a block are applied.
+	type PostInherents = Ethereum;
+	/// The migrations spread over several blocks.
+	type MultiBlockMigrator = MultiBlockMigrations;
 }
 
 impl pallet_aura::Config for Runtime {
@@ -394,6 +403,28 @@ impl pallet_ethereum::Config for Runtime {
 	type StateRoot = pallet_ethereum::IntermediateStateRoot<Self::Version>;
 	type PostLogContent = PostBlockAndTxnHashes;
 	type ExtraDataLength = ConstU32<30>;
+	type BlockHashHistory = ConstBool<false>;
+	type BlockHashRetention = ConstU32<256>;
+	type BeaconRoot = GetDefault;
+}
+
+parameter_types! {
+	pub MbmServiceWeight: Weight = Perbill::from_percent(80) * BlockWeights::get().max_block;
+}
+
+impl pallet_migrations::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	#[cfg(not(feature = "runtime-benchmarks"))]
+	type Migrations = (pallet_ethereum::migrations::PruneBlockHashes<Runtime>,);
+	// Benchmarks need mocked migrations to work.
+	#[cfg(feature = "runtime-benchmarks")]
+	type Migrations = pallet_migrations::mock_helpers::MockedMigrations;
+	type CursorMaxLen = ConstU32<65_536>;
+	type IdentifierMaxLen = ConstU32<256>;
+	type MigrationStatusHandler = ();
+	type FailedMigrationHandler = frame_support::migrations::FreezeChainOnFailedMigration;
+	type MaxServiceWeight = MbmServiceWeight;
+	type WeightInfo = pallet_migrations::weights::SubstrateWeight<Runtime>;
 }
 
 parameter_types! {
@@ -507,6 +538,9 @@ mod runtime {
 
 	#[runtime::pallet_index(11)]
 	pub type ManualSeal = pallet_manual_seal;
+
+	#[runtime::pallet_index(12)]
+	pub type MultiBlockMigrations = pallet_migrations;
 }
 
 #[derive(Clone)]
@@ -580,11 +614,12 @@ impl fp_self_contained::SelfContainedCall for RuntimeCall {
 		info: Self::SignedInfo,
 	) -> Option<sp_runtime::DispatchResultWithInfo<PostDispatchInfoOf<Self>>> {
 		match self {
-			call @ RuntimeCall::Ethereum(pallet_ethereum::Call::transact { .. }) => {
-				Some(call.dispatch(RuntimeOrigin::from(
-					pallet_ethereum::RawOrigin::EthereumTransaction(info),
-				)))
-			}
+			call @ RuntimeCall::Ethereum(
+				pallet_ethereum::Call::transact { .. }
+				| pallet_ethereum::Call::transact_batch { .. },
+			) => Some(call.dispatch(RuntimeOrigin::from(
+				pallet_ethereum::RawOrigin::EthereumTransaction(info),
+			))),
 			_ => None,
 		}
 	}
@@ -598,6 +633,7 @@ mod benches {
 		[pallet_balances, Balances]
 		[pallet_timestamp, Timestamp]
 		[pallet_sudo, Sudo]
+		[pallet_migrations, MultiBlockMigrations]
 		[pallet_evm, EVM]
 		[pallet_evm_precompile_curve25519, EVMPrecompileCurve25519Bench::<Runtime>]
 		[pallet_evm_precompile_sha3fips, EVMPrecompileSha3FIPSBench::<Runtime>]
@@ -808,11 +844,12 @@ impl_runtime_apis! {
 			estimate: bool,
 			access_list: Option<Vec<(H160, Vec<H256>)>>,
 			authorization_list: Option<AuthorizationList>,
+			block_overrides: Option<fp_evm::BlockOverrides>,
 		) -> Result<pallet_evm::CallInfo, sp_runtime::DispatchError> {
 			use pallet_evm::GasWeightMapping as _;
 
 			let config = if estimate {
-				let mut config = <Runtime as pallet_evm::Config>::config().clone();
+				let mut config = pallet_evm::Pallet::<Runtime>::next_block_evm_config().clone();
 				config.estimate = true;
 				Some(config)
 			} else {
@@ -863,23 +900,29 @@ impl_runtime_apis! {
 					_ => (None, None),
 				};
 
-			<Runtime as pallet_evm::Config>::Runner::call(
-				from,
-				to,
-				data,
-				value,
-				gas_limit.unique_saturated_into(),
-				max_fee_per_gas,
-				max_priority_fee_per_gas,
-				nonce,
-				access_list.unwrap_or_default(),
-				authorization_list.unwrap_or_default(),
-				false,
-				true,
-				weight_limit,
-				proof_size_base_cost,
-				config.as_ref().unwrap_or(<Runtime as pallet_evm::Config>::config()),
-			).map_err(|err| err.error.into())
+			let mut block_overrides = block_overrides.unwrap_or_default();
+			fp_evm::using_block_overrides(&mut block_overrides, || {
+				<Runtime as pallet_evm::Config>::Runner::call(
+					from,
+					to,
+					data,
+					value,
+					gas_limit.unique_saturated_into(),
+					max_fee_per_gas,
+					max_priority_fee_per_gas,
+					nonce,
+					access_list.unwrap_or_default(),
+					authorization_list.unwrap_or_default(),
+					false,
+					true,
+					weight_limit,
+					proof_size_base_cost,
+					config
+						.as_ref()
+						.unwrap_or(pallet_evm::Pallet::<Runtime>::next_block_evm_config()),
+				)
+			})
+			.map_err(|err| err.error.into())
 		}
 
 		fn create(
@@ -893,11 +936,12 @@ impl_runtime_apis! {
 			estimate: bool,
 			access_list: Option<Vec<(H160, Vec<H256>)>>,
 			authorization_list: Option<AuthorizationList>,
+			block_overrides: Option<fp_evm::BlockOverrides>,
 		) -> Result<pallet_evm::CreateInfo, sp_runtime::DispatchError> {
 			use pallet_evm::GasWeightMapping as _;
 
 			let config = if estimate {
-				let mut config = <Runtime as pallet_evm::Config>::config().clone();
+				let mut config = pallet_evm::Pallet::<Runtime>::next_block_evm_config().clone();
 				config.estimate = true;
 				Some(config)
 			} else {
@@ -946,22 +990,28 @@ impl_runtime_apis! {
 					_ => (None, None),
 				};
 
-			<Runtime as pallet_evm::Config>::Runner::create(
-				from,
-				data,
-				value,
-				gas_limit.unique_saturated_into(),
-				max_fee_per_gas,
-				max_priority_fee_per_gas,
-				nonce,
-				access_list.unwrap_or_default(),
-				authorization_list.unwrap_or_default(),
-				false,
-				true,
-				weight_limit,
-				proof_size_base_cost,
-				config.as_ref().unwrap_or(<Runtime as pallet_evm::Config>::config()),
-			).map_err(|err| err.error.into())
+			let mut block_overrides = block_overrides.unwrap_or_default();
+			fp_evm::using_block_overrides(&mut block_overrides, || {
+				<Runtime as pallet_evm::Config>::Runner::create(
+					from,
+					data,
+					value,
+					gas_limit.unique_saturated_into(),
+					max_fee_per_gas,
+					max_priority_fee_per_gas,
+					nonce,
+					access_list.unwrap_or_default(),
+					authorization_list.unwrap_or_default(),
+					false,
+					true,
+					weight_limit,
+					proof_size_base_cost,
+					config
+						.as_ref()
+						.unwrap_or(pallet_evm::Pallet::<Runtime>::next_block_evm_config()),
+				)
+			})
+			.map_err(|err| err.error.into())
 		}
 
 		fn current_transaction_statuses() -> Option<Vec<TransactionStatus>> {
@@ -991,9 +1041,10 @@ impl_runtime_apis! {
 		fn extrinsic_filter(
 			xts: Vec<<Block as BlockT>::Extrinsic>,
 		) -> Vec<EthereumTransaction> {
-			xts.into_iter().filter_map(|xt| match xt.0.function {
-				RuntimeCall::Ethereum(transact { transaction }) => Some(transaction),
-				_ => None
+			xts.into_iter().flat_map(|xt| match xt.0.function {
+				RuntimeCall::Ethereum(transact { transaction }) => vec![transaction],
+				RuntimeCall::Ethereum(transact_batch { transactions }) => transactions,
+				_ => Vec::new(),
 			}).collect::<Vec<EthereumTransaction>>()
 		}
 
@@ -1021,6 +1072,351 @@ impl_runtime_apis! {
 		fn initialize_pending_block(header: &<Block as BlockT>::Header) {
 			Executive::initialize_block(header);
 		}
+
+		fn create_access_list(
+			from: H160,
+			to: Option<H160>,
+			data: Vec<u8>,
+			value: U256,
+			gas_limit: U256,
+			max_fee_per_gas: Option<U256>,
+			max_priority_fee_per_gas: Option<U256>,
+			nonce: Option<U256>,
+			access_list: Option<Vec<(H160, Vec<H256>)>>,
+			authorization_list: Option<AuthorizationList>,
+		) -> Result<(pallet_evm::CallInfo, Vec<(H160, Vec<H256>)>), sp_runtime::DispatchError> {
+			use pallet_evm::GasWeightMapping as _;
+
+			// Same estimation of the encoded transaction size as `call` and `create`.
+			let mut estimated_transaction_len = data.len() + 259;
+			if access_list.is_some() {
+				estimated_transaction_len += access_list.encoded_size();
+			}
+			if authorization_list.is_some() {
+				estimated_transaction_len += authorization_list.encoded_size();
+			}
+
+			let gas_limit = if gas_limit > U256::from(u64::MAX) {
+				u64::MAX
+			} else {
+				gas_limit.low_u64()
+			};
+			let (weight_limit, proof_size_base_cost) =
+				match <Runtime as pallet_evm::Config>::GasWeightMapping::gas_to_weight(
+					gas_limit,
+					true,
+				) {
+					weight_limit if weight_limit.proof_size() > 0 => {
+						(Some(weight_limit), Some(estimated_transaction_len as u64))
+					}
+					_ => (None, None),
+				};
+			// Like geth, the block author is only listed when the execution accesses it, so it
+			// must not be warmed beforehand.
+			let mut evm_config = pallet_evm::Pallet::<Runtime>::next_block_evm_config().clone();
+			evm_config.warm_coinbase_address = false;
+			let evm_config = &evm_config;
+			let access_list = access_list.unwrap_or_default();
+			let authorization_list = authorization_list.unwrap_or_default();
+
+			let (result, accessed) = fp_evm::using_accessed_recorder(|| match to {
+				Some(to) => <Runtime as pallet_evm::Config>::Runner::call(
+					from,
+					to,
+					data,
+					value,
+					gas_limit,
+					max_fee_per_gas,
+					max_priority_fee_per_gas,
+					nonce,
+					access_list,
+					authorization_list,
+					false,
+					true,
+					weight_limit,
+					proof_size_base_cost,
+					evm_config,
+				)
+				.map(|info| (info, to)),
+				None => <Runtime as pallet_evm::Config>::Runner::create(
+					from,
+					data,
+					value,
+					gas_limit,
+					max_fee_per_gas,
+					max_priority_fee_per_gas,
+					nonce,
+					access_list,
+					authorization_list,
+					false,
+					true,
+					weight_limit,
+					proof_size_base_cost,
+					evm_config,
+				)
+				.map(|info| {
+					let address = info.value;
+					let info = pallet_evm::CallInfo {
+						exit_reason: info.exit_reason,
+						value: Vec::new(),
+						used_gas: info.used_gas,
+						weight_info: info.weight_info,
+						logs: info.logs,
+					};
+					(info, address)
+				}),
+			});
+			let (info, to) = result.map_err(|err| err.error.into())?;
+
+			let precompiles = FrontierPrecompiles::<Runtime>::used_addresses();
+			let access_list = accessed
+				.into_iter()
+				.filter(|(address, _)| {
+					*address != from && *address != to && !precompiles.contains(address)
+				})
+				.map(|(address, keys)| (address, keys.into_iter().collect()))
+				.collect();
+			Ok((info, access_list))
+		}
+
+		fn simulate_call(
+			from: H160,
+			to: Option<H160>,
+			data: Vec<u8>,
+			value: U256,
+			gas_limit: U256,
+			max_fee_per_gas: Option<U256>,
+			max_priority_fee_per_gas: Option<U256>,
+			nonce: Option<U256>,
+			access_list: Option<Vec<(H160, Vec<H256>)>>,
+			authorization_list: Option<AuthorizationList>,
+			block_overrides: Option<fp_evm::BlockOverrides>,
+			validate: bool,
+		) -> Result<pallet_evm::CallInfo, sp_runtime::DispatchError> {
+			use pallet_evm::GasWeightMapping as _;
+
+			// Same estimation of the encoded transaction size as `call` and `create`.
+			let mut estimated_transaction_len = data.len() + 259;
+			if access_list.is_some() {
+				estimated_transaction_len += access_list.encoded_size();
+			}
+			if authorization_list.is_some() {
+				estimated_transaction_len += authorization_list.encoded_size();
+			}
+
+			let gas_limit = if gas_limit > U256::from(u64::MAX) {
+				u64::MAX
+			} else {
+				gas_limit.low_u64()
+			};
+			let (weight_limit, proof_size_base_cost) =
+				match <Runtime as pallet_evm::Config>::GasWeightMapping::gas_to_weight(
+					gas_limit,
+					true,
+				) {
+					weight_limit if weight_limit.proof_size() > 0 => {
+						(Some(weight_limit), Some(estimated_transaction_len as u64))
+					}
+					_ => (None, None),
+				};
+			let evm_config = pallet_evm::Pallet::<Runtime>::next_block_evm_config();
+			let access_list = access_list.unwrap_or_default();
+			let authorization_list = authorization_list.unwrap_or_default();
+
+			let mut block_overrides = block_overrides.unwrap_or_default();
+			fp_evm::using_block_overrides(&mut block_overrides, || match to {
+				Some(to) => <Runtime as pallet_evm::Config>::Runner::call(
+					from,
+					to,
+					data,
+					value,
+					gas_limit,
+					max_fee_per_gas,
+					max_priority_fee_per_gas,
+					nonce,
+					access_list,
+					authorization_list,
+					validate,
+					true,
+					weight_limit,
+					proof_size_base_cost,
+					evm_config,
+				),
+				None => <Runtime as pallet_evm::Config>::Runner::create(
+					from,
+					data,
+					value,
+					gas_limit,
+					max_fee_per_gas,
+					max_priority_fee_per_gas,
+					nonce,
+					access_list,
+					authorization_list,
+					validate,
+					true,
+					weight_limit,
+					proof_size_base_cost,
+					evm_config,
+				)
+				.map(|info| pallet_evm::CallInfo {
+					exit_reason: info.exit_reason,
+					value: pallet_evm::AccountCodes::<Runtime>::get(info.value),
+					used_gas: info.used_gas,
+					weight_info: info.weight_info,
+					logs: info.logs,
+				}),
+			})
+			.map_err(|err| err.error.into())
+		}
+	}
+
+	#[cfg(feature = "evm-tracing")]
+	impl fp_rpc::DebugRuntimeApi<Block> for Runtime {
+		fn trace_transaction(
+			xts: Vec<<Block as BlockT>::Extrinsic>,
+			transaction_hash: H256,
+			config: fp_evm::tracing::TracerConfig,
+		) -> Result<fp_evm::tracing::TraceResult, sp_runtime::DispatchError> {
+			use pallet_evm::runner::tracing::{using, Tracer};
+
+			for ext in xts.into_iter() {
+				let is_traced_batch = matches!(
+					&ext.0.function,
+					RuntimeCall::Ethereum(transact_batch { transactions })
+						if transactions
+							.iter()
+							.any(|transaction| transaction.hash() == transaction_hash)
+				);
+				if is_traced_batch {
+					// The batch reports the trace of each transaction it applies.
+					let mut tracer = Tracer::new(config);
+					let _ = using(&mut tracer, || Executive::apply_extrinsic(ext));
+					return tracer
+						.into_transaction_results()
+						.into_iter()
+						.find(|(hash, _)| *hash == transaction_hash)
+						.map(|(_, result)| result)
+						.ok_or(sp_runtime::DispatchError::Other(
+							"transaction not applied in block",
+						));
+				}
+				let is_traced = matches!(
+					&ext.0.function,
+					RuntimeCall::Ethereum(transact { transaction })
+						if transaction.hash() == transaction_hash
+				);
+				if is_traced {
+					let mut tracer = Tracer::new(config);
+					let _ = using(&mut tracer, || Executive::apply_extrinsic(ext));
+					return Ok(tracer.into_result());
+				}
+				let _ = Executive::apply_extrinsic(ext);
+			}
+
+			Err(sp_runtime::DispatchError::Other("transaction not found in block"))
+		}
+
+		fn trace_block(
+			xts: Vec<<Block as BlockT>::Extrinsic>,
+			config: fp_evm::tracing::TracerConfig,
+		) -> Result<Vec<fp_evm::tracing::TraceResult>, sp_runtime::DispatchError> {
+			use pallet_evm::runner::tracing::{using, Tracer};
+
+			let mut results = Vec::new();
+			for ext in xts.into_iter() {
+				if matches!(&ext.0.function, RuntimeCall::Ethereum(transact { .. })) {
+					let mut tracer = Tracer::new(config.clone());
+					let _ = using(&mut tracer, || Executive::apply_extrinsic(ext));
+					results.push(tracer.into_result());
+				} else if matches!(&ext.0.function, RuntimeCall::Ethereum(transact_batch { .. })) {
+					// The batch reports the trace of each transaction it applies.
+					let mut tracer = Tracer::new(config.clone());
+					let _ = using(&mut tracer, || Executive::apply_extrinsic(ext));
+					results.extend(
+						tracer
+							.into_transaction_results()
+							.into_iter()
+							.map(|(_, result)| result),
+					);
+				} else {
+					// Non-Ethereum extrinsics may still touch the EVM state.
+					let _ = Executive::apply_extrinsic(ext);
+				}
+			}
+
+			Ok(results)
+		}
+
+		fn trace_call(
+			from: H160,
+			to: Option<H160>,
+			data: Vec<u8>,
+			value: U256,
+			gas_limit: U256,
+			max_fee_per_gas: Option<U256>,
+			max_priority_fee_per_gas: Option<U256>,
+			nonce: Option<U256>,
+			access_list: Option<Vec<(H160, Vec<H256>)>>,
+			authorization_list: Option<AuthorizationList>,
+			block_overrides: Option<fp_evm::BlockOverrides>,
+			config: fp_evm::tracing::TracerConfig,
+		) -> Result<fp_evm::tracing::TraceResult, sp_runtime::DispatchError> {
+			use pallet_evm::runner::tracing::{using, Tracer};
+
+			let gas_limit = if gas_limit > U256::from(u64::MAX) {
+				u64::MAX
+			} else {
+				gas_limit.low_u64()
+			};
+			let evm_config = pallet_evm::Pallet::<Runtime>::next_block_evm_config();
+			let access_list = access_list.unwrap_or_default();
+			let authorization_list = authorization_list.unwrap_or_default();
+
+			let mut tracer = Tracer::new(config);
+			let mut block_overrides = block_overrides.unwrap_or_default();
+			fp_evm::using_block_overrides(&mut block_overrides, || {
+				using(&mut tracer, || match to {
+					Some(to) => <Runtime as pallet_evm::Config>::Runner::call(
+						from,
+						to,
+						data,
+						value,
+						gas_limit,
+						max_fee_per_gas,
+						max_priority_fee_per_gas,
+						nonce,
+						access_list,
+						authorization_list,
+						false,
+						true,
+						None,
+						None,
+						evm_config,
+					)
+					.map(|_| ()),
+					None => <Runtime as pallet_evm::Config>::Runner::create(
+						from,
+						data,
+						value,
+						gas_limit,
+						max_fee_per_gas,
+						max_priority_fee_per_gas,
+						nonce,
+						access_list,
+						authorization_list,
+						false,
+						true,
+						None,
+						None,
+						evm_config,
+					)
+					.map(|_| ()),
+				})
+			})
+			.map_err(|err| err.error.into())?;
+
+			Ok(tracer.into_result())
+		}
 	}
 
 	impl fp_rpc::ConvertTransactionRuntimeApi<Block> for Runtime {
@@ -0,0 +1,31 @@
+import { expect } from "chai";
+import { step } from "mocha-steps";
+
+import { CHAIN_ID } from "./config";
+import { createAndFinalizeBlock, customRequest, describeWithFrontier } from "./util";
+
+describeWithFrontier(
+	"Frontier RPC (v2 API)",
+	(context) => {
+		step("should serve the Eth API", async function () {
+			expect(await context.web3.eth.getChainId()).to.equal(CHAIN_ID);
+
+			await createAndFinalizeBlock(context.web3);
+			expect(await context.web3.eth.getBlockNumber()).to.equal(1);
+
+			const block = await customRequest(context.web3, "eth_getBlockByNumber", ["latest", false]);
+			expect(block.error).to.be.undefined;
+			expect(block.result.number).to.equal("0x1");
+		});
+
+		step("should serve the Net and Web3 APIs", async function () {
+			const version = await customRequest(context.web3, "net_version", []);
+			expect(version.result).to.equal(CHAIN_ID.toString());
+
+			const clientVersion = await customRequest(context.web3, "web3_clientVersion", []);
+			expect(clientVersion.error).to.be.undefined;
+		});
+	},
+	undefined,
+	["--eth-rpc-v2"]
+);
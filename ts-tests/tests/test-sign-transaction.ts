@@ -0,0 +1,80 @@
+import { ethers } from "ethers";
+import { expect } from "chai";
+import { step } from "mocha-steps";
+
+import { CHAIN_ID } from "./config";
+import { customRequest, describeWithFrontier } from "./util";
+
+// Key of the development signer, enabled with `--enable-dev-signer`.
+const DEV_SIGNER = new ethers.Wallet("0x" + "11".repeat(32));
+const TARGET = "0x1000000000000000000000000000000000000001";
+
+describeWithFrontier(
+	"Frontier RPC (Sign Transaction)",
+	(context) => {
+		step("should sign a transaction without submitting it", async function () {
+			const response = await customRequest(context.web3, "eth_signTransaction", [
+				{
+					from: DEV_SIGNER.address,
+					to: TARGET,
+					value: "0x1",
+					gas: "0x5208",
+					gasPrice: "0x3B9ACA00",
+				},
+			]);
+			expect(response.error).to.be.undefined;
+
+			const transaction = ethers.Transaction.from(response.result.raw);
+			expect(transaction.from).to.equal(DEV_SIGNER.address);
+			expect(transaction.to).to.equal(TARGET);
+			expect(transaction.nonce).to.equal(0);
+			expect(transaction.chainId).to.equal(BigInt(CHAIN_ID));
+			expect(response.result.tx.hash).to.equal(transaction.hash);
+
+			const pending = await customRequest(context.web3, "eth_getTransactionCount", [
+				DEV_SIGNER.address,
+				"pending",
+			]);
+			expect(pending.result).to.equal("0x0");
+		});
+
+		step("should sign the authorizations left unsigned with the sender", async function () {
+			const response = await customRequest(context.web3, "eth_signTransaction", [
+				{
+					from: DEV_SIGNER.address,
+					to: TARGET,
+					gas: "0x186A0",
+					maxFeePerGas: "0x3B9ACA00",
+					maxPriorityFeePerGas: "0x0",
+					authorizationList: [{ chainId: CHAIN_ID, address: TARGET, nonce: "0x1" }],
+				},
+			]);
+			expect(response.error).to.be.undefined;
+
+			const transaction = ethers.Transaction.from(response.result.raw);
+			expect(transaction.type).to.equal(4);
+			expect(transaction.from).to.equal(DEV_SIGNER.address);
+			const [authorization] = transaction.authorizationList;
+			expect(
+				ethers.verifyAuthorization(
+					{ chainId: CHAIN_ID, address: TARGET, nonce: 1 },
+					authorization.signature
+				)
+			).to.equal(DEV_SIGNER.address);
+		});
+
+		step("should require signed authorizations outside of the signing methods", async function () {
+			const response = await customRequest(context.web3, "eth_call", [
+				{
+					from: DEV_SIGNER.address,
+					to: TARGET,
+					authorizationList: [{ chainId: CHAIN_ID, address: TARGET, nonce: "0x1" }],
+				},
+				"latest",
+			]);
+			expect(response.error.code).to.equal(-32602);
+		});
+	},
+	undefined,
+	["--enable-dev-signer"]
+);